@@ -0,0 +1,821 @@
+use std::collections::BTreeSet;
+use std::mem;
+use std::ops::{Bound, RangeBounds};
+
+pub type RowId = u64;
+
+pub type NodeId = usize;
+
+/// The most keys a node holds before it is split in two.
+const DEFAULT_ORDER: usize = 32;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InternalNode {
+    keys: Vec<RowId>,
+    children: Vec<NodeId>,
+}
+
+impl InternalNode {
+    pub fn new(keys: Vec<RowId>, children: Vec<NodeId>) -> Self {
+        InternalNode { keys, children }
+    }
+
+    /// `keys[i]` separates the keys stored under `children[i]`, which are all smaller, from the ones
+    /// stored under `children[i + 1]`, which are not.
+    pub fn keys(&self) -> &[RowId] {
+        self.keys.as_ref()
+    }
+
+    pub fn children(&self) -> &[NodeId] {
+        self.children.as_ref()
+    }
+
+    fn child_index(&self, key: RowId) -> usize {
+        self.keys.partition_point(|separator| *separator <= key)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeafNode<V> {
+    keys: Vec<RowId>,
+    values: Vec<V>,
+    next: Option<NodeId>,
+}
+
+impl<V> LeafNode<V> {
+    pub fn new(keys: Vec<RowId>, values: Vec<V>, next: Option<NodeId>) -> Self {
+        LeafNode { keys, values, next }
+    }
+
+    pub fn keys(&self) -> &[RowId] {
+        self.keys.as_ref()
+    }
+
+    pub fn values(&self) -> &[V] {
+        self.values.as_ref()
+    }
+
+    /// The leaf holding the next larger keys.
+    pub fn next(&self) -> Option<NodeId> {
+        self.next
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node<V> {
+    Internal(InternalNode),
+    Leaf(LeafNode<V>),
+}
+
+impl<V> Node<V> {
+    fn len(&self) -> usize {
+        match self {
+            Node::Internal(node) => node.keys.len(),
+            Node::Leaf(leaf) => leaf.keys.len(),
+        }
+    }
+}
+
+/// A B+tree mapping row ids to values.
+///
+/// Nodes live in an arena and refer to each other by `NodeId`, values are only stored in the leaves
+/// and every leaf links to its right-hand sibling so range scans never have to climb back up the
+/// tree. The tree remembers which nodes changed since the last call to `take_changes` so a
+/// backend which persists the nodes only has to write those.
+pub struct BPlusTree<V> {
+    order: usize,
+    nodes: Vec<Option<Node<V>>>,
+    free: Vec<NodeId>,
+    root: NodeId,
+    len: usize,
+    dirty: BTreeSet<NodeId>,
+    freed: BTreeSet<NodeId>,
+}
+
+impl<V> Default for BPlusTree<V> {
+    fn default() -> Self {
+        BPlusTree::new()
+    }
+}
+
+impl<V> BPlusTree<V> {
+    pub fn new() -> Self {
+        BPlusTree::with_order(DEFAULT_ORDER)
+    }
+
+    pub fn with_order(order: usize) -> Self {
+        assert!(order >= 3, "A B+tree needs an order of at least 3 but got {}", order);
+
+        let mut tree = BPlusTree {
+            order,
+            nodes: Vec::new(),
+            free: Vec::new(),
+            root: 0,
+            len: 0,
+            dirty: BTreeSet::new(),
+            freed: BTreeSet::new(),
+        };
+        tree.root = tree.allocate(Node::Leaf(LeafNode::new(Vec::new(), Vec::new(), None)));
+        tree
+    }
+
+    /// Rebuilds a tree from nodes previously read through `root` and `node`, where each node's id is
+    /// its index in `nodes`. The rebuilt tree starts without any pending changes.
+    pub fn from_nodes(root: NodeId, nodes: Vec<Node<V>>) -> Self {
+        let len = nodes.iter()
+            .map(|node| match node {
+                Node::Leaf(leaf) => leaf.keys.len(),
+                Node::Internal(_) => 0,
+            })
+            .sum();
+
+        BPlusTree {
+            order: DEFAULT_ORDER,
+            nodes: nodes.into_iter().map(Some).collect(),
+            free: Vec::new(),
+            root,
+            len,
+            dirty: BTreeSet::new(),
+            freed: BTreeSet::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn root(&self) -> NodeId {
+        self.root
+    }
+
+    pub fn node(&self, id: NodeId) -> &Node<V> {
+        self.nodes[id].as_ref().expect("Node has been freed")
+    }
+
+    /// Returns the nodes written and the nodes freed since the previous call.
+    pub fn take_changes(&mut self) -> (Vec<NodeId>, Vec<NodeId>) {
+        let dirty = mem::take(&mut self.dirty).into_iter().collect();
+        let freed = mem::take(&mut self.freed).into_iter().collect();
+        (dirty, freed)
+    }
+
+    pub fn get(&self, key: RowId) -> Option<&V> {
+        let leaf = self.leaf(self.find_leaf(key));
+        leaf.keys.binary_search(&key).ok().map(|index| &leaf.values[index])
+    }
+
+    pub fn get_mut(&mut self, key: RowId) -> Option<&mut V> {
+        let id = self.find_leaf(key);
+        let index = self.leaf(id).keys.binary_search(&key).ok()?;
+        self.dirty.insert(id);
+        Some(&mut self.leaf_mut(id).values[index])
+    }
+
+    pub fn last_key(&self) -> Option<RowId> {
+        let mut id = self.root;
+        loop {
+            match self.node(id) {
+                Node::Internal(node) => id = *node.children.last().unwrap(),
+                Node::Leaf(leaf) => return leaf.keys.last().copied(),
+            }
+        }
+    }
+
+    /// Inserts `value` under `key`, returning the value it replaced.
+    pub fn insert(&mut self, key: RowId, value: V) -> Option<V> {
+        let (previous, split) = self.insert_into(self.root, key, value);
+
+        if let Some((separator, right)) = split {
+            let root = InternalNode::new(vec![separator], vec![self.root, right]);
+            self.root = self.allocate(Node::Internal(root));
+        }
+
+        if previous.is_none() {
+            self.len += 1;
+        }
+        previous
+    }
+
+    pub fn remove(&mut self, key: RowId) -> Option<V> {
+        let removed = self.remove_from(self.root, key)?;
+        self.len -= 1;
+
+        if let Node::Internal(node) = self.node(self.root) {
+            if node.children.len() == 1 {
+                let child = node.children[0];
+                self.release(self.root);
+                self.root = child;
+            }
+        }
+
+        Some(removed)
+    }
+
+    /// Removes every value, releasing all of the nodes for a new empty root.
+    pub fn clear(&mut self) {
+        for id in 0..self.nodes.len() {
+            if self.nodes[id].is_some() {
+                self.release(id);
+            }
+        }
+
+        self.root = self.allocate(Node::Leaf(LeafNode::new(Vec::new(), Vec::new(), None)));
+        self.len = 0;
+    }
+
+    /// Changes every value in place, which marks every leaf as written.
+    pub fn update_values<F: FnMut(&mut V)>(&mut self, mut f: F) {
+        for (id, node) in self.nodes.iter_mut().enumerate() {
+            if let Some(Node::Leaf(leaf)) = node {
+                leaf.values.iter_mut().for_each(&mut f);
+                self.dirty.insert(id);
+            }
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, V> {
+        self.range(..)
+    }
+
+    pub fn range<R: RangeBounds<RowId>>(&self, range: R) -> Iter<'_, V> {
+        let (leaf, index) = match range.start_bound() {
+            Bound::Included(key) => {
+                let leaf = self.find_leaf(*key);
+                (leaf, self.leaf(leaf).keys.partition_point(|k| k < key))
+            }
+            Bound::Excluded(key) => {
+                let leaf = self.find_leaf(*key);
+                (leaf, self.leaf(leaf).keys.partition_point(|k| k <= key))
+            }
+            Bound::Unbounded => {
+                let mut id = self.root;
+                while let Node::Internal(node) = self.node(id) {
+                    id = node.children[0];
+                }
+                (id, 0)
+            }
+        };
+
+        Iter {
+            tree: self,
+            leaf: Some(leaf),
+            index,
+            end: range.end_bound().cloned(),
+        }
+    }
+
+    fn insert_into(&mut self, id: NodeId, key: RowId, value: V) -> (Option<V>, Option<(RowId, NodeId)>) {
+        self.dirty.insert(id);
+
+        let index = match self.node_mut(id) {
+            Node::Leaf(leaf) => {
+                match leaf.keys.binary_search(&key) {
+                    Ok(index) => return (Some(mem::replace(&mut leaf.values[index], value)), None),
+                    Err(index) => {
+                        leaf.keys.insert(index, key);
+                        leaf.values.insert(index, value);
+                    }
+                }
+                return (None, self.split_if_full(id));
+            }
+            Node::Internal(node) => node.child_index(key),
+        };
+
+        let child = self.internal(id).children[index];
+        let (previous, split) = self.insert_into(child, key, value);
+
+        if let Some((separator, right)) = split {
+            let node = self.internal_mut(id);
+            node.keys.insert(index, separator);
+            node.children.insert(index + 1, right);
+            return (previous, self.split_if_full(id));
+        }
+
+        (previous, None)
+    }
+
+    /// Splits an overfull node, returning the separator key and the new right-hand node.
+    fn split_if_full(&mut self, id: NodeId) -> Option<(RowId, NodeId)> {
+        if self.node(id).len() <= self.order {
+            return None;
+        }
+
+        match self.node_mut(id) {
+            Node::Leaf(leaf) => {
+                let middle = leaf.keys.len() / 2;
+                let keys = leaf.keys.split_off(middle);
+                let values = leaf.values.split_off(middle);
+                let separator = keys[0];
+
+                let right = LeafNode::new(keys, values, leaf.next);
+                let right = self.allocate(Node::Leaf(right));
+                self.leaf_mut(id).next = Some(right);
+
+                Some((separator, right))
+            }
+            Node::Internal(node) => {
+                let middle = node.keys.len() / 2;
+                let keys = node.keys.split_off(middle + 1);
+                let separator = node.keys.pop().unwrap();
+                let children = node.children.split_off(middle + 1);
+
+                let right = self.allocate(Node::Internal(InternalNode::new(keys, children)));
+
+                Some((separator, right))
+            }
+        }
+    }
+
+    fn remove_from(&mut self, id: NodeId, key: RowId) -> Option<V> {
+        let index = match self.node_mut(id) {
+            Node::Leaf(leaf) => {
+                let index = leaf.keys.binary_search(&key).ok()?;
+                leaf.keys.remove(index);
+                let removed = leaf.values.remove(index);
+                self.dirty.insert(id);
+                return Some(removed);
+            }
+            Node::Internal(node) => node.child_index(key),
+        };
+
+        let child = self.internal(id).children[index];
+        let removed = self.remove_from(child, key)?;
+
+        if self.node(child).len() < self.order / 2 {
+            self.rebalance(id, index);
+        }
+
+        Some(removed)
+    }
+
+    /// Fixes the underflowing child at `index` of `parent` by merging it with a sibling, or when the
+    /// two would not fit in one node, by sharing the sibling's entries evenly between them.
+    fn rebalance(&mut self, parent: NodeId, index: usize) {
+        let left_index = if index > 0 { index - 1 } else { index };
+        let left_id = self.internal(parent).children[left_index];
+        let right_id = self.internal(parent).children[left_index + 1];
+
+        let left = self.take(left_id);
+        let right = self.take(right_id);
+
+        match (left, right) {
+            (Node::Leaf(mut left), Node::Leaf(right)) => {
+                left.keys.extend(right.keys);
+                left.values.extend(right.values);
+
+                if left.keys.len() <= self.order {
+                    left.next = right.next;
+                    self.put(left_id, Node::Leaf(left));
+                    self.release(right_id);
+                    self.remove_child(parent, left_index);
+                } else {
+                    let middle = left.keys.len() / 2;
+                    let keys = left.keys.split_off(middle);
+                    let values = left.values.split_off(middle);
+                    self.internal_mut(parent).keys[left_index] = keys[0];
+                    self.put(left_id, Node::Leaf(left));
+                    self.put(right_id, Node::Leaf(LeafNode::new(keys, values, right.next)));
+                }
+            }
+            (Node::Internal(mut left), Node::Internal(right)) => {
+                left.keys.push(self.internal(parent).keys[left_index]);
+                left.keys.extend(right.keys);
+                left.children.extend(right.children);
+
+                if left.keys.len() <= self.order {
+                    self.put(left_id, Node::Internal(left));
+                    self.release(right_id);
+                    self.remove_child(parent, left_index);
+                } else {
+                    let middle = left.keys.len() / 2;
+                    let keys = left.keys.split_off(middle + 1);
+                    let separator = left.keys.pop().unwrap();
+                    let children = left.children.split_off(middle + 1);
+                    self.internal_mut(parent).keys[left_index] = separator;
+                    self.put(left_id, Node::Internal(left));
+                    self.put(right_id, Node::Internal(InternalNode::new(keys, children)));
+                }
+            }
+            _ => unreachable!("Siblings are always at the same depth"),
+        }
+    }
+
+    /// Drops the separator at `index` and the child to its right after that child has been merged
+    /// into its left sibling.
+    fn remove_child(&mut self, parent: NodeId, index: usize) {
+        let node = self.internal_mut(parent);
+        node.keys.remove(index);
+        node.children.remove(index + 1);
+    }
+
+    fn find_leaf(&self, key: RowId) -> NodeId {
+        let mut id = self.root;
+        while let Node::Internal(node) = self.node(id) {
+            id = node.children[node.child_index(key)];
+        }
+        id
+    }
+
+    fn allocate(&mut self, node: Node<V>) -> NodeId {
+        let id = match self.free.pop() {
+            Some(id) => {
+                self.nodes[id] = Some(node);
+                self.freed.remove(&id);
+                id
+            }
+            None => {
+                self.nodes.push(Some(node));
+                self.nodes.len() - 1
+            }
+        };
+        self.dirty.insert(id);
+        id
+    }
+
+    fn release(&mut self, id: NodeId) {
+        self.nodes[id] = None;
+        self.free.push(id);
+        self.dirty.remove(&id);
+        self.freed.insert(id);
+    }
+
+    fn take(&mut self, id: NodeId) -> Node<V> {
+        self.nodes[id].take().expect("Node has been freed")
+    }
+
+    fn put(&mut self, id: NodeId, node: Node<V>) {
+        self.nodes[id] = Some(node);
+        self.dirty.insert(id);
+    }
+
+    fn node_mut(&mut self, id: NodeId) -> &mut Node<V> {
+        self.nodes[id].as_mut().expect("Node has been freed")
+    }
+
+    fn leaf(&self, id: NodeId) -> &LeafNode<V> {
+        match self.node(id) {
+            Node::Leaf(leaf) => leaf,
+            Node::Internal(_) => panic!("Expected node {} to be a leaf", id),
+        }
+    }
+
+    fn leaf_mut(&mut self, id: NodeId) -> &mut LeafNode<V> {
+        match self.node_mut(id) {
+            Node::Leaf(leaf) => leaf,
+            Node::Internal(_) => panic!("Expected node {} to be a leaf", id),
+        }
+    }
+
+    fn internal(&self, id: NodeId) -> &InternalNode {
+        match self.node(id) {
+            Node::Internal(node) => node,
+            Node::Leaf(_) => panic!("Expected node {} to be an internal node", id),
+        }
+    }
+
+    fn internal_mut(&mut self, id: NodeId) -> &mut InternalNode {
+        self.dirty.insert(id);
+        match self.node_mut(id) {
+            Node::Internal(node) => node,
+            Node::Leaf(_) => panic!("Expected node {} to be an internal node", id),
+        }
+    }
+}
+
+/// Walks the leaves from left to right, following the sibling links.
+pub struct Iter<'a, V> {
+    tree: &'a BPlusTree<V>,
+    leaf: Option<NodeId>,
+    index: usize,
+    end: Bound<RowId>,
+}
+
+impl<'a, V> Iterator for Iter<'a, V> {
+    type Item = (RowId, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let leaf = self.tree.leaf(self.leaf?);
+
+            if self.index >= leaf.keys.len() {
+                self.leaf = leaf.next;
+                self.index = 0;
+                continue;
+            }
+
+            let key = leaf.keys[self.index];
+            let in_range = match self.end {
+                Bound::Included(end) => key <= end,
+                Bound::Excluded(end) => key < end,
+                Bound::Unbounded => true,
+            };
+            if !in_range {
+                self.leaf = None;
+                return None;
+            }
+
+            self.index += 1;
+            return Some((key, &leaf.values[self.index - 1]));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap, HashSet};
+
+    use super::*;
+
+    /// A xorshift generator, so the random operations are the same on every run.
+    struct Random(u64);
+
+    impl Random {
+        fn next(&mut self, bound: u64) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0 % bound
+        }
+    }
+
+    /// Checks the shape of the tree, returning its keys in order. Every node but the root has to be
+    /// at least half full, every leaf has to be at the same depth, every key has to be within the
+    /// bounds its parents give it, the leaf links have to visit the leaves in order and every node
+    /// which is not freed has to be part of the tree.
+    fn check<V>(tree: &BPlusTree<V>) -> Vec<RowId> {
+        fn walk<V>(tree: &BPlusTree<V>, id: NodeId, bounds: (Option<RowId>, Option<RowId>), depth: usize, leaves: &mut Vec<(NodeId, usize)>, keys: &mut Vec<RowId>) {
+            let node = tree.node(id);
+            if id != tree.root {
+                assert!(node.len() >= tree.order / 2, "node {} has {} keys", id, node.len());
+            }
+            assert!(node.len() <= tree.order, "node {} has {} keys", id, node.len());
+
+            let node_keys = match node {
+                Node::Leaf(leaf) => &leaf.keys,
+                Node::Internal(internal) => &internal.keys,
+            };
+            assert!(node_keys.windows(2).all(|pair| pair[0] < pair[1]), "node {} is not sorted", id);
+            assert!(node_keys.iter().all(|key| bounds.0.is_none_or(|low| *key >= low) && bounds.1.is_none_or(|high| *key < high)));
+
+            match node {
+                Node::Leaf(leaf) => {
+                    assert_eq!(leaf.keys.len(), leaf.values.len());
+                    leaves.push((id, depth));
+                    keys.extend(&leaf.keys);
+                }
+                Node::Internal(internal) => {
+                    assert_eq!(internal.children.len(), internal.keys.len() + 1);
+                    for (index, child) in internal.children.iter().enumerate() {
+                        let low = if index == 0 { bounds.0 } else { Some(internal.keys[index - 1]) };
+                        let high = internal.keys.get(index).copied().or(bounds.1);
+                        walk(tree, *child, (low, high), depth + 1, leaves, keys);
+                    }
+                }
+            }
+        }
+
+        let mut leaves = Vec::new();
+        let mut keys = Vec::new();
+        walk(tree, tree.root, (None, None), 0, &mut leaves, &mut keys);
+
+        assert!(leaves.iter().all(|(_, depth)| *depth == leaves[0].1), "the leaves are at different depths");
+        assert_eq!(keys.len(), tree.len());
+
+        let mut linked = Vec::new();
+        let mut next = Some(leaves[0].0);
+        while let Some(id) = next {
+            linked.push(id);
+            next = tree.leaf(id).next;
+        }
+        assert_eq!(linked, leaves.iter().map(|(id, _)| *id).collect::<Vec<NodeId>>(), "the leaf links skip or repeat leaves");
+
+        let live = tree.nodes.iter().filter(|node| node.is_some()).count();
+        let internal = tree.nodes.iter().filter(|node| matches!(node, Some(Node::Internal(_)))).count();
+        assert_eq!(live, leaves.len() + internal, "some nodes are not part of the tree");
+
+        keys
+    }
+
+    fn depth<V>(tree: &BPlusTree<V>) -> usize {
+        let mut depth = 1;
+        let mut id = tree.root;
+        while let Node::Internal(node) = tree.node(id) {
+            id = node.children[0];
+            depth += 1;
+        }
+        depth
+    }
+
+    fn entries(tree: &BPlusTree<u64>) -> Vec<(RowId, u64)> {
+        tree.iter().map(|(key, value)| (key, *value)).collect()
+    }
+
+    #[test]
+    fn random_inserts_and_removes_match_a_btreemap() {
+        for order in [3, 4, 5, DEFAULT_ORDER] {
+            let mut random = Random(0x2545_f491_4f6c_dd1d + order as u64);
+            let mut tree = BPlusTree::with_order(order);
+            let mut expected = BTreeMap::new();
+
+            for step in 0..20_000 {
+                let key = random.next(3_000);
+                if random.next(3) == 0 {
+                    assert_eq!(tree.remove(key), expected.remove(&key), "removing {} at order {}", key, order);
+                } else {
+                    assert_eq!(tree.insert(key, step), expected.insert(key, step), "inserting {} at order {}", key, order);
+                }
+                assert_eq!(tree.get(key), expected.get(&key));
+
+                if step % 1_000 == 0 {
+                    assert_eq!(check(&tree), expected.keys().copied().collect::<Vec<RowId>>());
+                }
+            }
+
+            assert_eq!(entries(&tree), expected.iter().map(|(key, value)| (*key, *value)).collect::<Vec<(RowId, u64)>>());
+            assert_eq!(tree.last_key(), expected.keys().next_back().copied());
+
+            // Emptying the tree in a random order collapses it back into a single leaf
+            let mut keys = expected.keys().copied().collect::<Vec<RowId>>();
+            while !keys.is_empty() {
+                let key = keys.swap_remove(random.next(keys.len() as u64) as usize);
+                assert_eq!(tree.remove(key), expected.remove(&key));
+                if keys.len() % 500 == 0 {
+                    assert_eq!(check(&tree), expected.keys().copied().collect::<Vec<RowId>>());
+                }
+            }
+            assert!(tree.is_empty());
+            assert_eq!(depth(&tree), 1);
+            assert_eq!(tree.last_key(), None);
+        }
+    }
+
+    #[test]
+    fn leaves_split_at_the_default_order() {
+        let mut tree = BPlusTree::new();
+        for key in 0..DEFAULT_ORDER as u64 {
+            tree.insert(key, key);
+        }
+        assert_eq!(depth(&tree), 1);
+
+        tree.insert(DEFAULT_ORDER as u64, 0);
+        assert_eq!(depth(&tree), 2);
+        assert_eq!(tree.internal(tree.root).keys(), &[16]);
+        check(&tree);
+    }
+
+    #[test]
+    fn internal_nodes_split_at_the_default_order() {
+        let mut tree = BPlusTree::new();
+        let mut key = 0;
+
+        // Adding keys in order leaves every full leaf half empty, so each leaf holds 16 or 17 keys
+        while depth(&tree) < 3 {
+            tree.insert(key, key);
+            key += 1;
+        }
+
+        let root = tree.internal(tree.root);
+        assert_eq!(root.children().len(), 2);
+        assert_eq!(tree.internal(root.children()[0]).keys().len(), 16);
+        assert_eq!(tree.internal(root.children()[1]).keys().len(), 16);
+        check(&tree);
+    }
+
+    #[test]
+    fn underflowing_leaves_merge_and_the_root_collapses() {
+        let mut tree = BPlusTree::new();
+        for key in 0..=DEFAULT_ORDER as u64 {
+            tree.insert(key, key);
+        }
+
+        // The leaves hold 16 and 17 keys, which fit in one leaf once one of them is gone
+        tree.remove(0);
+        assert_eq!(depth(&tree), 1);
+        assert_eq!(tree.leaf(tree.root).keys(), (1..=DEFAULT_ORDER as u64).collect::<Vec<RowId>>().as_slice());
+        check(&tree);
+    }
+
+    #[test]
+    fn underflowing_leaves_borrow_from_a_full_sibling() {
+        let mut tree = BPlusTree::new();
+        for key in 0..48 {
+            tree.insert(key, key);
+        }
+        assert_eq!(tree.internal(tree.root).keys(), &[16]);
+
+        // The leaves hold 15 and 32 keys, too many for one leaf, so they share them out instead
+        tree.remove(0);
+        let root = tree.internal(tree.root);
+        assert_eq!(root.keys(), &[24]);
+        assert_eq!(tree.leaf(root.children()[0]).keys().len(), 23);
+        assert_eq!(tree.leaf(root.children()[1]).keys().len(), 24);
+        assert_eq!(check(&tree), (1..48).collect::<Vec<RowId>>());
+    }
+
+    #[test]
+    fn ranges_follow_the_leaf_links_between_their_bounds() {
+        let mut tree = BPlusTree::with_order(4);
+        let keys = (0..30).map(|key| key * 2).collect::<Vec<RowId>>();
+        for key in &keys {
+            tree.insert(*key, *key * 10);
+        }
+        check(&tree);
+
+        let bound = |kind: u64, key: RowId| match kind {
+            0 => Bound::Included(key),
+            1 => Bound::Excluded(key),
+            _ => Bound::Unbounded,
+        };
+
+        for start in 0..=61 {
+            for end in 0..=61 {
+                for kinds in 0..9 {
+                    let range = (bound(kinds / 3, start), bound(kinds % 3, end));
+                    let expected = keys.iter()
+                        .filter(|key| range.contains(key))
+                        .map(|key| (*key, *key * 10))
+                        .collect::<Vec<(RowId, u64)>>();
+
+                    let found = tree.range(range).map(|(key, value)| (key, *value)).collect::<Vec<(RowId, u64)>>();
+                    assert_eq!(found, expected, "{:?}", range);
+                }
+            }
+        }
+
+        assert_eq!(tree.range(RowId::MAX..).count(), 0);
+        assert_eq!(tree.range(..=RowId::MAX).count(), keys.len());
+        assert_eq!(tree.range(58..).map(|(key, _)| key).collect::<Vec<RowId>>(), vec![58]);
+    }
+
+    /// Writes the changes since the last call into `disk`, the way a backend persists the tree.
+    fn persist(tree: &mut BPlusTree<u64>, disk: &mut HashMap<NodeId, Node<u64>>) {
+        let (dirty, freed) = tree.take_changes();
+        assert!(dirty.iter().all(|id| !freed.contains(id)), "a node is both written and freed");
+
+        for id in freed {
+            disk.remove(&id);
+        }
+        for id in dirty {
+            disk.insert(id, tree.node(id).clone());
+        }
+    }
+
+    /// Reads the tree back from `disk`, giving the nodes new ids as a backend does when loading.
+    fn reload(root: NodeId, disk: &HashMap<NodeId, Node<u64>>) -> BPlusTree<u64> {
+        let ids = disk.keys().copied().enumerate().map(|(new, old)| (old, new)).collect::<HashMap<NodeId, NodeId>>();
+        let mut nodes = vec![None; disk.len()];
+
+        for (old, node) in disk {
+            let node = match node {
+                Node::Leaf(leaf) => Node::Leaf(LeafNode::new(leaf.keys.clone(), leaf.values.clone(), leaf.next.map(|next| ids[&next]))),
+                Node::Internal(internal) => Node::Internal(InternalNode::new(
+                    internal.keys.clone(),
+                    internal.children.iter().map(|child| ids[child]).collect(),
+                )),
+            };
+            nodes[ids[old]] = Some(node);
+        }
+
+        BPlusTree::from_nodes(ids[&root], nodes.into_iter().map(Option::unwrap).collect())
+    }
+
+    #[test]
+    fn changes_are_enough_to_rebuild_the_tree() {
+        let mut random = Random(0x9e37_79b9_7f4a_7c15);
+        let mut tree = BPlusTree::new();
+        let mut disk = HashMap::new();
+        let mut expected = BTreeMap::new();
+
+        for round in 0..50 {
+            for _ in 0..200 {
+                let key = random.next(2_000);
+                if random.next(4) == 0 {
+                    tree.remove(key);
+                    expected.remove(&key);
+                } else {
+                    tree.insert(key, round);
+                    expected.insert(key, round);
+                }
+            }
+            if round == 25 {
+                tree.clear();
+                expected.clear();
+            }
+
+            persist(&mut tree, &mut disk);
+            let reloaded = reload(tree.root(), &disk);
+
+            assert_eq!(check(&reloaded), expected.keys().copied().collect::<Vec<RowId>>());
+            assert_eq!(entries(&reloaded), entries(&tree));
+        }
+
+        // Nothing left to write once the changes have been taken
+        assert_eq!(tree.take_changes(), (Vec::new(), Vec::new()));
+        let freed = tree.nodes.iter().enumerate().filter(|(_, node)| node.is_none()).map(|(id, _)| id).collect::<HashSet<NodeId>>();
+        assert!(disk.keys().all(|id| !freed.contains(id)));
+    }
+}
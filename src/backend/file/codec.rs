@@ -0,0 +1,58 @@
+use std::convert::TryInto;
+
+use crate::Result;
+
+pub fn put_u8(buffer: &mut Vec<u8>, value: u8) {
+    buffer.push(value);
+}
+
+pub fn put_u32(buffer: &mut Vec<u8>, value: u32) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+pub fn put_u64(buffer: &mut Vec<u8>, value: u64) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+pub fn put_string(buffer: &mut Vec<u8>, value: &str) {
+    put_u32(buffer, value.len() as u32);
+    buffer.extend_from_slice(value.as_bytes());
+}
+
+/// Reads values back in the order they were written by the `put_*` functions.
+pub struct Decoder<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Decoder { bytes, offset: 0 }
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8]> {
+        if self.offset + length > self.bytes.len() {
+            return Err(format!("Unexpected end of data reading {} bytes at offset {}", length, self.offset).into());
+        }
+        let slice = &self.bytes[self.offset..self.offset + length];
+        self.offset += length;
+        Ok(slice)
+    }
+
+    pub fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into()?))
+    }
+
+    pub fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into()?))
+    }
+
+    pub fn string(&mut self) -> Result<String> {
+        let length = self.u32()? as usize;
+        Ok(String::from_utf8(self.take(length)?.to_vec())?)
+    }
+}
@@ -0,0 +1,851 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::backend::{Backend, QueryResults};
+use crate::backend::btree::{BPlusTree, InternalNode, LeafNode, Node, NodeId};
+use crate::backend::file::codec::Decoder;
+use crate::backend::file::pager::{Page, PAGE_SIZE, PageId, Pager};
+use crate::backend::memory::{Column, ColumnTypes, InMemoryBackend, MemoryCell, Rows, RowTree, Table};
+use crate::Result;
+use crate::statements::alter::{AlterTableAction, AlterTableStatement};
+use crate::statements::create::{ColumnConstraint, CreateTableStatement};
+use crate::statements::delete::DeleteStatement;
+use crate::statements::describe::DescribeStatement;
+use crate::statements::drop::DropTableStatement;
+use crate::statements::expression::Literal;
+use crate::statements::insert::InsertStatement;
+use crate::statements::select::SelectStatement;
+use crate::statements::truncate::TruncateTableStatement;
+use crate::statements::update::UpdateStatement;
+use crate::types::decimal::{self, Decimal};
+use crate::types::temporal::{Date, Time, Timestamp};
+
+mod codec;
+mod pager;
+mod wal;
+
+const MAGIC: &[u8; 8] = b"LTWADB04";
+
+const HEADER_PAGE: PageId = 0;
+
+/// Page 0 always holds the header, so it doubles as the "no page" marker in page links.
+const NO_PAGE: PageId = 0;
+
+const CHAIN_HEADER_SIZE: usize = 8;
+const CHAIN_PAYLOAD_SIZE: usize = PAGE_SIZE - CHAIN_HEADER_SIZE;
+
+const NODE_LEAF: u8 = 1;
+const NODE_INTERNAL: u8 = 2;
+
+const TYPE_INT32: u8 = 1;
+const TYPE_STRING: u8 = 2;
+const TYPE_NULL: u8 = 3;
+const TYPE_INT64: u8 = 4;
+const TYPE_DOUBLE: u8 = 5;
+const TYPE_BOOLEAN: u8 = 6;
+const TYPE_DATE: u8 = 7;
+const TYPE_TIME: u8 = 8;
+const TYPE_TIMESTAMP: u8 = 9;
+const TYPE_DECIMAL: u8 = 10;
+
+const CONSTRAINT_PRIMARY_KEY: u8 = 1;
+const CONSTRAINT_NOT_NULL: u8 = 2;
+const CONSTRAINT_UNIQUE: u8 = 3;
+const CONSTRAINT_DEFAULT: u8 = 4;
+
+/// Where each node of a table's row tree is stored.
+type NodePages = HashMap<NodeId, PageId>;
+
+struct Header {
+    catalog_page: PageId,
+    free_list_page: PageId,
+}
+
+/// A backend which keeps every table in a single database file.
+///
+/// The file is split into `PAGE_SIZE` pages. Page 0 is the header, which points at the catalog and
+/// at a linked list of free pages. The catalog and every B+tree node holding a table's rows are
+/// stored as chains of pages, each page starting with the id of the next page in the chain and the
+/// number of payload bytes it holds. Statements are executed against an in-memory copy of the
+/// tables and the tree nodes a statement changed are written back before the statement returns.
+///
+/// Each mutating statement is committed to a write-ahead log stored next to the database file,
+/// with `-wal` appended to its name, before it returns. Opening the database replays any
+/// transaction which was committed to the log but not yet copied into the database file.
+pub struct FileBackend {
+    pager: Pager,
+    header: Header,
+    node_pages: HashMap<String, NodePages>,
+    memory: InMemoryBackend,
+}
+
+impl FileBackend {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let pager = Pager::open(path)?;
+        let is_new = pager.page_count() == 0;
+
+        let mut backend = FileBackend {
+            pager,
+            header: Header { catalog_page: NO_PAGE, free_list_page: NO_PAGE },
+            node_pages: HashMap::new(),
+            memory: InMemoryBackend::new(HashMap::new()),
+        };
+
+        if is_new {
+            backend.pager.write_page(HEADER_PAGE, &[0; PAGE_SIZE])?;
+            backend.write_catalog()?;
+            backend.pager.commit()?;
+        } else {
+            backend.load()?;
+        }
+
+        Ok(backend)
+    }
+
+    /// Replaces the in-memory state with what has been committed to the file.
+    fn load(&mut self) -> Result<()> {
+        self.header = read_header(&mut self.pager)?;
+
+        let mut node_pages = HashMap::new();
+        let mut tables = HashMap::new();
+
+        for (name, root_page, columns) in read_catalog(&mut self.pager, &self.header)? {
+            let (tree, pages) = read_tree(&mut self.pager, root_page)?;
+
+            let stride = columns.len();
+            tables.insert(name.clone(), Table::new(columns, Rows::from_tree(stride, tree)));
+            node_pages.insert(name, pages);
+        }
+
+        self.node_pages = node_pages;
+        self.memory = InMemoryBackend::new(tables);
+        Ok(())
+    }
+
+    fn persist_table(&mut self, name: &str) -> Result<()> {
+        self.persist(|backend| backend.write_table(name))
+    }
+
+    /// Writes a statement's changes with `write` and commits them.
+    fn persist<F: FnOnce(&mut Self) -> Result<()>>(&mut self, write: F) -> Result<()> {
+        let result = write(self).and_then(|_| self.pager.commit());
+
+        // The in-memory tables already reflect the statement, so go back to the last commit
+        if result.is_err() {
+            self.pager.rollback();
+            self.load()?;
+        }
+
+        result
+    }
+
+    fn write_table(&mut self, name: &str) -> Result<()> {
+        let (dirty, freed) = match self.memory.table_mut(name) {
+            Some(table) => table.rows_mut().tree_mut().take_changes(),
+            None => return Err(format!("Table {:#?} not found", name).into()),
+        };
+
+        let mut node_pages = self.node_pages.remove(name).unwrap_or_default();
+
+        for node in freed {
+            if let Some(page) = node_pages.remove(&node) {
+                self.write_chain(page, &[])?;
+            }
+        }
+
+        // Every node needs a page before any is encoded, as parents and leaves refer to other pages
+        for node in &dirty {
+            if !node_pages.contains_key(node) {
+                let page = self.allocate_page()?;
+                node_pages.insert(*node, page);
+            }
+        }
+
+        let tree = self.memory.table(name).unwrap().rows().tree();
+        let encoded = dirty.iter()
+            .map(|node| (node_pages[node], encode_node(tree.node(*node), &node_pages)))
+            .collect::<Vec<(PageId, Vec<u8>)>>();
+
+        for (page, bytes) in encoded {
+            self.write_chain(page, &bytes)?;
+        }
+
+        self.node_pages.insert(name.to_owned(), node_pages);
+
+        self.write_catalog()
+    }
+
+    /// Frees every page of a table which is no longer in the catalog.
+    fn remove_table(&mut self, name: &str) -> Result<()> {
+        for page in self.node_pages.remove(name).unwrap_or_default().into_values() {
+            self.write_chain(page, &[])?;
+        }
+
+        self.write_catalog()
+    }
+
+    fn write_catalog(&mut self) -> Result<()> {
+        let mut names = self.memory.tables().map(|(name, _)| name.to_owned()).collect::<Vec<String>>();
+        names.sort();
+
+        let mut bytes = Vec::new();
+        codec::put_u32(&mut bytes, names.len() as u32);
+
+        for name in names {
+            let table = self.memory.table(&name).unwrap();
+            let root_page = self.node_pages.get(&name)
+                .and_then(|pages| pages.get(&table.rows().tree().root()))
+                .copied()
+                .unwrap_or(NO_PAGE);
+
+            codec::put_string(&mut bytes, &name);
+            codec::put_u32(&mut bytes, root_page);
+            codec::put_u32(&mut bytes, table.columns().len() as u32);
+            for column in table.columns() {
+                codec::put_string(&mut bytes, column.name());
+                encode_column_type(&mut bytes, column.column_type());
+                codec::put_u32(&mut bytes, column.constraints().len() as u32);
+                for constraint in column.constraints() {
+                    encode_constraint(&mut bytes, constraint);
+                }
+            }
+        }
+
+        self.header.catalog_page = self.write_chain(self.header.catalog_page, &bytes)?;
+        self.write_header()
+    }
+
+    fn write_header(&mut self) -> Result<()> {
+        let mut bytes = Vec::with_capacity(PAGE_SIZE);
+        bytes.extend_from_slice(MAGIC);
+        codec::put_u32(&mut bytes, PAGE_SIZE as u32);
+        codec::put_u32(&mut bytes, self.header.catalog_page);
+        codec::put_u32(&mut bytes, self.header.free_list_page);
+
+        let mut page = [0; PAGE_SIZE];
+        page[..bytes.len()].copy_from_slice(&bytes);
+        self.pager.write_page(HEADER_PAGE, &page)
+    }
+
+    /// Overwrites the chain starting at `head` with `bytes`, reusing its pages where possible, and
+    /// returns the first page of the new chain.
+    fn write_chain(&mut self, head: PageId, bytes: &[u8]) -> Result<PageId> {
+        let mut existing = Vec::new();
+        let mut next = head;
+        while next != NO_PAGE {
+            existing.push(next);
+            next = chain_next(&self.pager.read_page(next)?);
+        }
+
+        let chunks = bytes.chunks(CHAIN_PAYLOAD_SIZE).collect::<Vec<&[u8]>>();
+
+        let mut ids = Vec::with_capacity(chunks.len());
+        for index in 0..chunks.len() {
+            match existing.get(index) {
+                Some(id) => ids.push(*id),
+                None => ids.push(self.allocate_page()?),
+            }
+        }
+
+        for (index, chunk) in chunks.iter().enumerate() {
+            let next = ids.get(index + 1).copied().unwrap_or(NO_PAGE);
+
+            let mut page = [0; PAGE_SIZE];
+            page[0..4].copy_from_slice(&next.to_le_bytes());
+            page[4..8].copy_from_slice(&(chunk.len() as u32).to_le_bytes());
+            page[CHAIN_HEADER_SIZE..CHAIN_HEADER_SIZE + chunk.len()].copy_from_slice(chunk);
+
+            self.pager.write_page(ids[index], &page)?;
+        }
+
+        for id in existing.into_iter().skip(chunks.len()) {
+            self.free_page(id)?;
+        }
+
+        Ok(ids.first().copied().unwrap_or(NO_PAGE))
+    }
+
+    fn allocate_page(&mut self) -> Result<PageId> {
+        if self.header.free_list_page != NO_PAGE {
+            let id = self.header.free_list_page;
+            self.header.free_list_page = chain_next(&self.pager.read_page(id)?);
+
+            // Clear the free list link so the page is not mistaken for the start of a chain
+            self.pager.write_page(id, &[0; PAGE_SIZE])?;
+            return Ok(id);
+        }
+
+        // Write the page straight away so the next allocation does not hand out the same id
+        let id = self.pager.page_count();
+        self.pager.write_page(id, &[0; PAGE_SIZE])?;
+        Ok(id)
+    }
+
+    fn free_page(&mut self, id: PageId) -> Result<()> {
+        let mut page = [0; PAGE_SIZE];
+        page[0..4].copy_from_slice(&self.header.free_list_page.to_le_bytes());
+        self.pager.write_page(id, &page)?;
+
+        self.header.free_list_page = id;
+        Ok(())
+    }
+}
+
+fn read_header(pager: &mut Pager) -> Result<Header> {
+    let page = pager.read_page(HEADER_PAGE)?;
+    if &page[0..8] != MAGIC {
+        return Err("The file is not a database file".into());
+    }
+
+    let mut decoder = Decoder::new(&page[8..]);
+    let page_size = decoder.u32()?;
+    if page_size as usize != PAGE_SIZE {
+        return Err(format!("Expected a page size of {} but the file uses {}", PAGE_SIZE, page_size).into());
+    }
+
+    Ok(Header {
+        catalog_page: decoder.u32()?,
+        free_list_page: decoder.u32()?,
+    })
+}
+
+fn read_catalog(pager: &mut Pager, header: &Header) -> Result<Vec<(String, PageId, Vec<Column>)>> {
+    let catalog = read_chain(pager, header.catalog_page)?;
+    let mut decoder = Decoder::new(&catalog);
+
+    let mut tables = Vec::new();
+    for _ in 0..decoder.u32()? {
+        let name = decoder.string()?;
+        let data_page = decoder.u32()?;
+
+        let mut columns = Vec::new();
+        for _ in 0..decoder.u32()? {
+            let column_name = decoder.string()?;
+            let column_type = decode_column_type(&mut decoder)?;
+
+            let mut constraints = Vec::new();
+            for _ in 0..decoder.u32()? {
+                constraints.push(decode_constraint(&mut decoder)?);
+            }
+
+            columns.push(Column::new(column_name, column_type, constraints));
+        }
+
+        tables.push((name, data_page, columns));
+    }
+
+    Ok(tables)
+}
+
+/// Reads every node reachable from `root_page`, numbering the nodes in the order they are found.
+fn read_tree(pager: &mut Pager, root_page: PageId) -> Result<(RowTree, NodePages)> {
+    let mut node_ids = HashMap::new();
+    node_ids.insert(root_page, 0);
+
+    let mut nodes = vec![None];
+    let mut queue = vec![root_page];
+
+    while let Some(page) = queue.pop() {
+        let bytes = read_chain(pager, page)?;
+
+        let node = decode_node(&mut Decoder::new(&bytes), |linked_page| {
+            let next_id = node_ids.len();
+            *node_ids.entry(linked_page).or_insert_with(|| {
+                queue.push(linked_page);
+                next_id
+            })
+        })?;
+
+        nodes.resize_with(node_ids.len(), || None);
+        nodes[node_ids[&page]] = Some(node);
+    }
+
+    let nodes = nodes.into_iter()
+        .map(|node| node.ok_or_else(|| "A tree node refers to a page which was never read".into()))
+        .collect::<Result<Vec<Node<Vec<MemoryCell>>>>>()?;
+
+    let pages = node_ids.into_iter().map(|(page, node)| (node, page)).collect();
+
+    Ok((BPlusTree::from_nodes(0, nodes), pages))
+}
+
+fn encode_node(node: &Node<Vec<MemoryCell>>, node_pages: &NodePages) -> Vec<u8> {
+    let mut bytes = Vec::new();
+
+    match node {
+        Node::Leaf(leaf) => {
+            codec::put_u8(&mut bytes, NODE_LEAF);
+            codec::put_u32(&mut bytes, leaf.keys().len() as u32);
+            for (key, row) in leaf.keys().iter().zip(leaf.values()) {
+                codec::put_u64(&mut bytes, *key);
+                codec::put_u32(&mut bytes, row.len() as u32);
+                for cell in row {
+                    encode_cell(&mut bytes, cell);
+                }
+            }
+            codec::put_u32(&mut bytes, leaf.next().map_or(NO_PAGE, |next| node_pages[&next]));
+        }
+        Node::Internal(node) => {
+            codec::put_u8(&mut bytes, NODE_INTERNAL);
+            codec::put_u32(&mut bytes, node.keys().len() as u32);
+            for key in node.keys() {
+                codec::put_u64(&mut bytes, *key);
+            }
+            for child in node.children() {
+                codec::put_u32(&mut bytes, node_pages[child]);
+            }
+        }
+    }
+
+    bytes
+}
+
+/// Decodes a node, using `node_id` to turn the pages it links to into node ids.
+fn decode_node<F: FnMut(PageId) -> NodeId>(decoder: &mut Decoder, mut node_id: F) -> Result<Node<Vec<MemoryCell>>> {
+    match decoder.u8()? {
+        NODE_LEAF => {
+            let count = decoder.u32()? as usize;
+            let mut keys = Vec::with_capacity(count);
+            let mut values = Vec::with_capacity(count);
+
+            for _ in 0..count {
+                keys.push(decoder.u64()?);
+                let mut row = Vec::new();
+                for _ in 0..decoder.u32()? {
+                    row.push(decode_cell(decoder)?);
+                }
+                values.push(row);
+            }
+
+            let next = match decoder.u32()? {
+                NO_PAGE => None,
+                page => Some(node_id(page)),
+            };
+
+            Ok(Node::Leaf(LeafNode::new(keys, values, next)))
+        }
+        NODE_INTERNAL => {
+            let count = decoder.u32()? as usize;
+            let mut keys = Vec::with_capacity(count);
+            for _ in 0..count {
+                keys.push(decoder.u64()?);
+            }
+
+            let mut children = Vec::with_capacity(count + 1);
+            for _ in 0..=count {
+                children.push(node_id(decoder.u32()?));
+            }
+
+            Ok(Node::Internal(InternalNode::new(keys, children)))
+        }
+        tag => Err(format!("Unknown tree node tag {}", tag).into()),
+    }
+}
+
+fn read_chain(pager: &mut Pager, head: PageId) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let mut next = head;
+
+    while next != NO_PAGE {
+        let page = pager.read_page(next)?;
+        let mut decoder = Decoder::new(&page[4..8]);
+        let length = decoder.u32()? as usize;
+        if length > CHAIN_PAYLOAD_SIZE {
+            return Err(format!("Page {} claims {} bytes of payload", next, length).into());
+        }
+        bytes.extend_from_slice(&page[CHAIN_HEADER_SIZE..CHAIN_HEADER_SIZE + length]);
+        next = chain_next(&page);
+    }
+
+    Ok(bytes)
+}
+
+fn chain_next(page: &Page) -> PageId {
+    PageId::from_le_bytes([page[0], page[1], page[2], page[3]])
+}
+
+/// Column types share the cell type tags, with a decimal's precision and scale following its tag.
+fn encode_column_type(bytes: &mut Vec<u8>, column_type: &ColumnTypes) {
+    let tag = match column_type {
+        ColumnTypes::Int32 => TYPE_INT32,
+        ColumnTypes::Int64 => TYPE_INT64,
+        ColumnTypes::Double => TYPE_DOUBLE,
+        ColumnTypes::Boolean => TYPE_BOOLEAN,
+        ColumnTypes::String => TYPE_STRING,
+        ColumnTypes::Date => TYPE_DATE,
+        ColumnTypes::Time => TYPE_TIME,
+        ColumnTypes::Timestamp => TYPE_TIMESTAMP,
+        ColumnTypes::Interval => unreachable!("no column holds intervals"),
+        ColumnTypes::Decimal(precision, scale) => {
+            codec::put_u8(bytes, TYPE_DECIMAL);
+            codec::put_u8(bytes, *precision);
+            codec::put_u8(bytes, *scale);
+            return;
+        }
+    };
+    codec::put_u8(bytes, tag)
+}
+
+fn decode_column_type(decoder: &mut Decoder) -> Result<ColumnTypes> {
+    match decoder.u8()? {
+        TYPE_DECIMAL => {
+            let (precision, scale) = (decoder.u8()?, decoder.u8()?);
+            if precision == 0 || precision > decimal::MAX_PRECISION || scale > precision {
+                return Err(format!("Invalid DECIMAL({}, {}) column type", precision, scale).into());
+            }
+            Ok(ColumnTypes::Decimal(precision, scale))
+        }
+        TYPE_INT32 => Ok(ColumnTypes::Int32),
+        TYPE_INT64 => Ok(ColumnTypes::Int64),
+        TYPE_DOUBLE => Ok(ColumnTypes::Double),
+        TYPE_BOOLEAN => Ok(ColumnTypes::Boolean),
+        TYPE_STRING => Ok(ColumnTypes::String),
+        TYPE_DATE => Ok(ColumnTypes::Date),
+        TYPE_TIME => Ok(ColumnTypes::Time),
+        TYPE_TIMESTAMP => Ok(ColumnTypes::Timestamp),
+        tag => Err(format!("Unknown column type tag {}", tag).into()),
+    }
+}
+
+fn encode_constraint(bytes: &mut Vec<u8>, constraint: &ColumnConstraint) {
+    match constraint {
+        ColumnConstraint::PrimaryKey => codec::put_u8(bytes, CONSTRAINT_PRIMARY_KEY),
+        ColumnConstraint::NotNull => codec::put_u8(bytes, CONSTRAINT_NOT_NULL),
+        ColumnConstraint::Unique => codec::put_u8(bytes, CONSTRAINT_UNIQUE),
+        ColumnConstraint::Default(value) => {
+            codec::put_u8(bytes, CONSTRAINT_DEFAULT);
+            encode_literal(bytes, value);
+        }
+    }
+}
+
+fn decode_constraint(decoder: &mut Decoder) -> Result<ColumnConstraint> {
+    match decoder.u8()? {
+        CONSTRAINT_PRIMARY_KEY => Ok(ColumnConstraint::PrimaryKey),
+        CONSTRAINT_NOT_NULL => Ok(ColumnConstraint::NotNull),
+        CONSTRAINT_UNIQUE => Ok(ColumnConstraint::Unique),
+        CONSTRAINT_DEFAULT => Ok(ColumnConstraint::Default(decode_literal(decoder)?)),
+        tag => Err(format!("Unknown column constraint tag {}", tag).into()),
+    }
+}
+
+/// Literals share the cell encoding.
+fn encode_literal(bytes: &mut Vec<u8>, literal: &Literal) {
+    let cell = match literal {
+        Literal::Integer(value) => MemoryCell::Int64(*value),
+        Literal::Decimal(value) => MemoryCell::Decimal(*value),
+        Literal::Double(value) => MemoryCell::Double(*value),
+        Literal::String(value) => MemoryCell::String(value.to_string()),
+        Literal::Boolean(value) => MemoryCell::Boolean(*value),
+        Literal::Date(value) => MemoryCell::Date(*value),
+        Literal::Time(value) => MemoryCell::Time(*value),
+        Literal::Timestamp(value) => MemoryCell::Timestamp(*value),
+        Literal::Interval(_) => unreachable!("no column holds intervals, so none can default to one"),
+        Literal::Null => MemoryCell::Null,
+    };
+    encode_cell(bytes, &cell)
+}
+
+fn decode_literal(decoder: &mut Decoder) -> Result<Literal> {
+    match decode_cell(decoder)? {
+        MemoryCell::Int32(value) => Ok(Literal::Integer(value.into())),
+        MemoryCell::Int64(value) => Ok(Literal::Integer(value)),
+        MemoryCell::Decimal(value) => Ok(Literal::Decimal(value)),
+        MemoryCell::Double(value) => Ok(Literal::Double(value)),
+        MemoryCell::String(value) => Ok(Literal::String(value)),
+        MemoryCell::Boolean(value) => Ok(Literal::Boolean(value)),
+        MemoryCell::Date(value) => Ok(Literal::Date(value)),
+        MemoryCell::Time(value) => Ok(Literal::Time(value)),
+        MemoryCell::Timestamp(value) => Ok(Literal::Timestamp(value)),
+        MemoryCell::Null => Ok(Literal::Null),
+    }
+}
+
+/// Signed integers are stored as their two's complement bits and doubles as their IEEE 754 bits.
+/// Decimals are stored as their scale followed by the 128 bits of their digits, low half first.
+/// Dates are stored as days and times as microseconds, both counted from the Unix epoch.
+fn encode_cell(bytes: &mut Vec<u8>, cell: &MemoryCell) {
+    match cell {
+        MemoryCell::Int32(value) => {
+            codec::put_u8(bytes, TYPE_INT32);
+            codec::put_u32(bytes, *value as u32);
+        }
+        MemoryCell::Int64(value) => {
+            codec::put_u8(bytes, TYPE_INT64);
+            codec::put_u64(bytes, *value as u64);
+        }
+        MemoryCell::Decimal(value) => {
+            codec::put_u8(bytes, TYPE_DECIMAL);
+            codec::put_u8(bytes, value.scale());
+            codec::put_u64(bytes, value.value() as u64);
+            codec::put_u64(bytes, (value.value() >> 64) as u64);
+        }
+        MemoryCell::Double(value) => {
+            codec::put_u8(bytes, TYPE_DOUBLE);
+            codec::put_u64(bytes, value.to_bits());
+        }
+        MemoryCell::Boolean(value) => {
+            codec::put_u8(bytes, TYPE_BOOLEAN);
+            codec::put_u8(bytes, *value as u8);
+        }
+        MemoryCell::String(value) => {
+            codec::put_u8(bytes, TYPE_STRING);
+            codec::put_string(bytes, value);
+        }
+        MemoryCell::Date(value) => {
+            codec::put_u8(bytes, TYPE_DATE);
+            codec::put_u32(bytes, value.days() as u32);
+        }
+        MemoryCell::Time(value) => {
+            codec::put_u8(bytes, TYPE_TIME);
+            codec::put_u64(bytes, value.micros() as u64);
+        }
+        MemoryCell::Timestamp(value) => {
+            codec::put_u8(bytes, TYPE_TIMESTAMP);
+            codec::put_u64(bytes, value.micros() as u64);
+        }
+        MemoryCell::Null => codec::put_u8(bytes, TYPE_NULL),
+    }
+}
+
+fn decode_cell(decoder: &mut Decoder) -> Result<MemoryCell> {
+    match decoder.u8()? {
+        TYPE_INT32 => Ok(MemoryCell::Int32(decoder.u32()? as i32)),
+        TYPE_INT64 => Ok(MemoryCell::Int64(decoder.u64()? as i64)),
+        TYPE_DOUBLE => Ok(MemoryCell::Double(f64::from_bits(decoder.u64()?))),
+        TYPE_DECIMAL => {
+            let scale = decoder.u8()?;
+            let (low, high) = (decoder.u64()?, decoder.u64()?);
+            let value = Decimal::new(((high as i128) << 64) | low as i128, scale);
+            if scale > decimal::MAX_PRECISION || !value.fits(decimal::MAX_PRECISION) {
+                return Err(format!("Invalid decimal of {} at scale {}", value.value(), scale).into());
+            }
+            Ok(MemoryCell::Decimal(value))
+        }
+        TYPE_BOOLEAN => Ok(MemoryCell::Boolean(decoder.u8()? != 0)),
+        TYPE_STRING => Ok(MemoryCell::String(decoder.string()?)),
+        TYPE_DATE => {
+            let days = decoder.u32()? as i32;
+            Date::from_days(days.into()).map(MemoryCell::Date).ok_or_else(|| format!("Invalid date of {} days", days).into())
+        }
+        TYPE_TIME => {
+            let micros = decoder.u64()? as i64;
+            Time::from_micros(micros).map(MemoryCell::Time).ok_or_else(|| format!("Invalid time of {} microseconds", micros).into())
+        }
+        TYPE_TIMESTAMP => {
+            let micros = decoder.u64()? as i64;
+            Timestamp::from_micros(micros).map(MemoryCell::Timestamp).ok_or_else(|| format!("Invalid timestamp of {} microseconds", micros).into())
+        }
+        TYPE_NULL => Ok(MemoryCell::Null),
+        tag => Err(format!("Unknown cell type tag {}", tag).into()),
+    }
+}
+
+impl Backend for FileBackend {
+    fn create_table(&mut self, stmt: &CreateTableStatement) -> Result<bool> {
+        let created = self.memory.create_table(stmt)?;
+        if created {
+            self.persist_table(stmt.table_name())?;
+        }
+        Ok(created)
+    }
+
+    fn insert(&mut self, stmt: &InsertStatement) -> Result<usize> {
+        let inserted = self.memory.insert(stmt)?;
+        if inserted > 0 {
+            self.persist_table(stmt.table_name())?;
+        }
+        Ok(inserted)
+    }
+
+    fn select(&mut self, stmt: &SelectStatement) -> Result<QueryResults> {
+        self.memory.select(stmt)
+    }
+
+    fn update(&mut self, stmt: &UpdateStatement) -> Result<usize> {
+        let affected = self.memory.update(stmt)?;
+        if affected > 0 {
+            self.persist_table(stmt.table_name())?;
+        }
+        Ok(affected)
+    }
+
+    fn delete(&mut self, stmt: &DeleteStatement) -> Result<usize> {
+        let affected = self.memory.delete(stmt)?;
+        if affected > 0 {
+            self.persist_table(stmt.table_name())?;
+        }
+        Ok(affected)
+    }
+
+    fn drop_table(&mut self, stmt: &DropTableStatement) -> Result<bool> {
+        let dropped = self.memory.drop_table(stmt)?;
+        if dropped {
+            self.persist(|backend| backend.remove_table(stmt.table_name()))?;
+        }
+        Ok(dropped)
+    }
+
+    fn truncate(&mut self, stmt: &TruncateTableStatement) -> Result<usize> {
+        let removed = self.memory.truncate(stmt)?;
+        if removed > 0 {
+            self.persist_table(stmt.table_name())?;
+        }
+        Ok(removed)
+    }
+
+    fn alter_table(&mut self, stmt: &AlterTableStatement) -> Result<()> {
+        self.memory.alter_table(stmt)?;
+
+        match stmt.action() {
+            AlterTableAction::RenameTable(name) => {
+                // The tree keeps its pages, they are only listed under the new name
+                if let Some(pages) = self.node_pages.remove(stmt.table_name()) {
+                    self.node_pages.insert(name.to_owned(), pages);
+                }
+                self.persist_table(name)
+            }
+            _ => self.persist_table(stmt.table_name()),
+        }
+    }
+
+    fn table_names(&self) -> Vec<String> {
+        self.memory.table_names()
+    }
+
+    fn table_schema(&self, table: &str) -> Result<CreateTableStatement> {
+        self.memory.table_schema(table)
+    }
+
+    fn show_tables(&self) -> Result<QueryResults> {
+        self.memory.show_tables()
+    }
+
+    fn describe(&self, stmt: &DescribeStatement) -> Result<QueryResults> {
+        self.memory.describe(stmt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use crate::backend::memory::tests::{error, query, run};
+
+    use super::*;
+
+    /// A database file of its own, which starts out without a file or a write-ahead log.
+    fn database_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("file-backend-{}-{}", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_file(pager::wal_path(&path));
+        path
+    }
+
+    /// The rows of every query, each prefixed by the query so a difference shows where it is.
+    fn results(backend: &mut dyn Backend, queries: &[&str]) -> Vec<String> {
+        queries.iter()
+            .flat_map(|sql| query(backend, sql).into_iter().map(move |row| format!("{}: {}", sql, row)))
+            .collect()
+    }
+
+    /// Fills `numbers` with the values 1 to 1024, enough for its B+tree to split.
+    fn numbers(backend: &mut dyn Backend) {
+        run(backend, "INSERT INTO numbers (n) VALUES (1)").unwrap();
+        for count in (0..10).map(|doublings| 1 << doublings) {
+            run(backend, &format!("INSERT INTO numbers SELECT n + {}, note FROM numbers", count)).unwrap();
+        }
+    }
+
+    #[test]
+    fn reopened_databases_return_the_same_rows() {
+        let path = database_path("reopen");
+        let long = "x".repeat(PAGE_SIZE * 3);
+        let queries = [
+            "SHOW TABLES",
+            "SELECT * FROM people ORDER BY id",
+            "SELECT COUNT(*), SUM(n), MIN(note), MAX(note) FROM numbers",
+            "SELECT * FROM numbers WHERE n >= 510 AND n < 513",
+        ];
+
+        let expected = {
+            let mut backend = FileBackend::open(&path).unwrap();
+            run(&mut backend, &format!("CREATE TABLE users (id INT PRIMARY KEY, name TEXT NOT NULL, bio TEXT, email TEXT);
+                INSERT INTO users VALUES (1, 'ann', '{}', 'a'), (2, 'bob', NULL, 'b'), (3, 'cat', 'short', 'c');
+                UPDATE users SET bio = 'shorter' WHERE id = 3;
+                DELETE FROM users WHERE id = 2;
+                ALTER TABLE users ADD COLUMN age INT DEFAULT 30;
+                ALTER TABLE users RENAME COLUMN name TO nick;
+                ALTER TABLE users DROP COLUMN email;
+                ALTER TABLE users RENAME TO people;
+                CREATE TABLE gone (id INT);
+                INSERT INTO gone VALUES (1);
+                DROP TABLE gone;
+                CREATE TABLE numbers (n INT PRIMARY KEY, note TEXT DEFAULT 'n');", long)).unwrap();
+            numbers(&mut backend);
+            results(&mut backend, &queries)
+        };
+        assert_eq!(expected[..3], [
+            "SHOW TABLES: numbers".to_owned(),
+            "SHOW TABLES: people".to_owned(),
+            format!("SELECT * FROM people ORDER BY id: 1, ann, {}, 30", long),
+        ]);
+        assert_eq!(expected[4..], [
+            "SELECT COUNT(*), SUM(n), MIN(note), MAX(note) FROM numbers: 1024, 524800, n, n",
+            "SELECT * FROM numbers WHERE n >= 510 AND n < 513: 510, n",
+            "SELECT * FROM numbers WHERE n >= 510 AND n < 513: 511, n",
+            "SELECT * FROM numbers WHERE n >= 510 AND n < 513: 512, n",
+        ]);
+
+        let mut backend = FileBackend::open(&path).unwrap();
+        assert_eq!(results(&mut backend, &queries), expected);
+        assert!(error(&mut backend, "SELECT * FROM users").contains("not found"));
+        assert!(error(&mut backend, "INSERT INTO people VALUES (1, 'dup', NULL, 1)").contains("PRIMARY KEY"));
+
+        drop(backend);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn freed_pages_are_used_again() {
+        let path = database_path("free");
+        let mut backend = FileBackend::open(&path).unwrap();
+
+        let create = format!("CREATE TABLE numbers (n INT, note TEXT DEFAULT '{}')", "x".repeat(PAGE_SIZE / 4));
+        run(&mut backend, &create).unwrap();
+        numbers(&mut backend);
+        let page_count = backend.pager.page_count();
+
+        // The dropped table's pages hold the new one, so the file does not grow
+        run(&mut backend, &format!("DROP TABLE numbers; {}", create)).unwrap();
+        numbers(&mut backend);
+        assert!(backend.pager.page_count() <= page_count, "{} pages grew to {}", page_count, backend.pager.page_count());
+
+        drop(backend);
+        let mut backend = FileBackend::open(&path).unwrap();
+        assert_eq!(query(&mut backend, "SELECT COUNT(*), SUM(n) FROM numbers"), vec!["1024, 524800"]);
+
+        drop(backend);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn statements_which_change_nothing_commit_nothing() {
+        let path = database_path("unchanged");
+        let mut backend = FileBackend::open(&path).unwrap();
+        run(&mut backend, "CREATE TABLE users (id INT); CREATE TABLE empty (id INT); INSERT INTO users VALUES (1);").unwrap();
+
+        let log_length = fs::metadata(pager::wal_path(&path)).unwrap().len();
+        run(&mut backend, "INSERT INTO users SELECT id FROM empty;
+            TRUNCATE empty;
+            UPDATE users SET id = 2 WHERE id = 5;
+            DELETE FROM users WHERE id = 5;
+            CREATE TABLE IF NOT EXISTS users (other TEXT);
+            DROP TABLE IF EXISTS missing;").unwrap();
+        assert_eq!(fs::metadata(pager::wal_path(&path)).unwrap().len(), log_length);
+
+        drop(backend);
+        fs::remove_file(path).unwrap();
+    }
+}
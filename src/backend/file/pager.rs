@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use log::{debug, warn};
+
+use crate::backend::file::wal::WriteAheadLog;
+use crate::Result;
+
+pub const PAGE_SIZE: usize = 4096;
+
+pub type PageId = u32;
+
+pub type Page = [u8; PAGE_SIZE];
+
+/// The number of committed pages kept in the write-ahead log before they are copied into the
+/// database file.
+const CHECKPOINT_THRESHOLD: usize = 256;
+
+/// Reads and writes fixed-size pages of a single database file.
+///
+/// Writes are buffered until `commit`, which appends them to the write-ahead log next to the
+/// database file and syncs it. Committed pages are served from memory until the next checkpoint
+/// copies them into the database file and empties the log.
+pub struct Pager {
+    file: File,
+    wal: WriteAheadLog,
+    page_count: u32,
+    committed_page_count: u32,
+    committed: HashMap<PageId, Page>,
+    dirty: HashMap<PageId, Page>,
+}
+
+impl Pager {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path.as_ref())?;
+
+        let mut pager = Pager {
+            file,
+            wal: WriteAheadLog::open(wal_path(path.as_ref()))?,
+            page_count: 0,
+            committed_page_count: 0,
+            committed: HashMap::new(),
+            dirty: HashMap::new(),
+        };
+
+        let recovered = pager.wal.recover()?;
+        if !recovered.is_empty() {
+            warn!("Replaying {} pages from the write-ahead log", recovered.len());
+        }
+        pager.committed.extend(recovered);
+        pager.checkpoint()?;
+
+        let length = pager.file.metadata()?.len();
+        if length % PAGE_SIZE as u64 != 0 {
+            return Err(format!("Database file length {} is not a multiple of the page size {}", length, PAGE_SIZE).into());
+        }
+
+        pager.page_count = (length / PAGE_SIZE as u64) as u32;
+        pager.committed_page_count = pager.page_count;
+
+        Ok(pager)
+    }
+
+    pub fn page_count(&self) -> u32 {
+        self.page_count
+    }
+
+    pub fn read_page(&mut self, id: PageId) -> Result<Page> {
+        if id >= self.page_count {
+            return Err(format!("Page {} is out of bounds, the file only has {} pages", id, self.page_count).into());
+        }
+
+        if let Some(page) = self.dirty.get(&id).or_else(|| self.committed.get(&id)) {
+            return Ok(*page);
+        }
+
+        let mut page = [0; PAGE_SIZE];
+        self.file.seek(SeekFrom::Start(id as u64 * PAGE_SIZE as u64))?;
+        self.file.read_exact(&mut page)?;
+
+        Ok(page)
+    }
+
+    /// Writes a page, growing the file when `id` is the page just past its end. Writing a page
+    /// back unchanged is skipped so it does not take up room in the write-ahead log.
+    pub fn write_page(&mut self, id: PageId, page: &Page) -> Result<()> {
+        if id > self.page_count {
+            return Err(format!("Page {} would leave a gap after the last page {}", id, self.page_count).into());
+        }
+
+        if id < self.page_count && self.read_page(id)?[..] == page[..] {
+            return Ok(());
+        }
+
+        self.dirty.insert(id, *page);
+
+        if id == self.page_count {
+            self.page_count += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Makes every page written since the last commit durable.
+    pub fn commit(&mut self) -> Result<()> {
+        if self.dirty.is_empty() {
+            return Ok(());
+        }
+
+        let mut frames = self.dirty.iter().map(|(id, page)| (*id, page)).collect::<Vec<(PageId, &Page)>>();
+        frames.sort_by_key(|(id, _)| *id);
+        self.wal.append(&frames)?;
+
+        self.committed.extend(self.dirty.drain());
+        self.committed_page_count = self.page_count;
+
+        if self.committed.len() >= CHECKPOINT_THRESHOLD {
+            self.checkpoint()?;
+        }
+
+        Ok(())
+    }
+
+    /// Throws away every page written since the last commit.
+    pub fn rollback(&mut self) {
+        self.dirty.clear();
+        self.page_count = self.committed_page_count;
+    }
+
+    /// Copies the committed pages into the database file and empties the write-ahead log.
+    pub fn checkpoint(&mut self) -> Result<()> {
+        if self.committed.is_empty() {
+            // Nothing to copy, but any torn transaction left at the end of the log has to go
+            return self.wal.truncate();
+        }
+
+        let mut ids = self.committed.keys().copied().collect::<Vec<PageId>>();
+        ids.sort_unstable();
+
+        for id in &ids {
+            self.file.seek(SeekFrom::Start(*id as u64 * PAGE_SIZE as u64))?;
+            self.file.write_all(&self.committed[id])?;
+        }
+        self.file.sync_all()?;
+
+        // The log may only be emptied once the pages it holds are safely in the database file
+        self.wal.truncate()?;
+        self.committed.clear();
+
+        debug!("Checkpointed {} pages", ids.len());
+        Ok(())
+    }
+}
+
+impl Drop for Pager {
+    fn drop(&mut self) {
+        if let Err(err) = self.checkpoint() {
+            warn!("Unable to checkpoint the write-ahead log: {}", err);
+        }
+    }
+}
+
+/// The write-ahead log lives next to the database file with `-wal` appended to its name.
+pub fn wal_path(path: &Path) -> PathBuf {
+    let mut wal_path = OsString::from(path.as_os_str());
+    wal_path.push("-wal");
+    PathBuf::from(wal_path)
+}
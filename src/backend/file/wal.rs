@@ -0,0 +1,203 @@
+use std::convert::TryInto;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use log::warn;
+
+use crate::backend::file::pager::{Page, PAGE_SIZE, PageId};
+use crate::Result;
+
+/// Marks a commit record. Page ids never reach this value because the file would be 16 TiB.
+const COMMIT_MARKER: u32 = u32::MAX;
+
+const FRAME_SIZE: usize = 4 + PAGE_SIZE;
+const COMMIT_SIZE: usize = 12;
+
+/// An append-only log of page images.
+///
+/// Each transaction is written as a sequence of frames, a page id followed by the full page, and
+/// finished with a commit record holding the number of frames and a checksum over them. A
+/// transaction only counts once its commit record has been written and synced, so a log cut short
+/// at any byte recovers to the last complete commit.
+pub struct WriteAheadLog {
+    file: File,
+}
+
+impl WriteAheadLog {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+
+        Ok(WriteAheadLog { file })
+    }
+
+    pub fn append(&mut self, frames: &[(PageId, &Page)]) -> Result<()> {
+        let mut bytes = Vec::with_capacity(frames.len() * FRAME_SIZE + COMMIT_SIZE);
+        for (id, page) in frames {
+            bytes.extend_from_slice(&id.to_le_bytes());
+            bytes.extend_from_slice(&page[..]);
+        }
+
+        let checksum = checksum(&bytes);
+        bytes.extend_from_slice(&COMMIT_MARKER.to_le_bytes());
+        bytes.extend_from_slice(&(frames.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&checksum.to_le_bytes());
+
+        self.file.seek(SeekFrom::End(0))?;
+        self.file.write_all(&bytes)?;
+        self.file.sync_data()?;
+
+        Ok(())
+    }
+
+    /// Returns the frames of every committed transaction in the order they were written, ignoring
+    /// a trailing transaction which never finished committing.
+    pub fn recover(&mut self) -> Result<Vec<(PageId, Page)>> {
+        let mut bytes = Vec::new();
+        self.file.seek(SeekFrom::Start(0))?;
+        self.file.read_to_end(&mut bytes)?;
+
+        let mut committed = Vec::new();
+        let mut pending = Vec::new();
+        let mut transaction_start = 0;
+        let mut offset = 0;
+
+        while offset + 4 <= bytes.len() {
+            let id = read_u32(&bytes, offset);
+
+            if id != COMMIT_MARKER {
+                if offset + FRAME_SIZE > bytes.len() {
+                    break;
+                }
+                let mut page = [0; PAGE_SIZE];
+                page.copy_from_slice(&bytes[offset + 4..offset + FRAME_SIZE]);
+                pending.push((id, page));
+                offset += FRAME_SIZE;
+                continue;
+            }
+
+            if offset + COMMIT_SIZE > bytes.len() {
+                break;
+            }
+            let frame_count = read_u32(&bytes, offset + 4) as usize;
+            let expected = read_u32(&bytes, offset + 8);
+            if frame_count != pending.len() || checksum(&bytes[transaction_start..offset]) != expected {
+                break;
+            }
+
+            committed.append(&mut pending);
+            offset += COMMIT_SIZE;
+            transaction_start = offset;
+        }
+
+        if transaction_start != bytes.len() {
+            warn!("Discarding {} bytes of uncommitted write-ahead log", bytes.len() - transaction_start);
+        }
+
+        Ok(committed)
+    }
+
+    pub fn truncate(&mut self) -> Result<()> {
+        if self.file.metadata()?.len() == 0 {
+            return Ok(());
+        }
+        self.file.set_len(0)?;
+        self.file.sync_all()?;
+        Ok(())
+    }
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+/// 32-bit FNV-1a, which is plenty to tell a torn write from a complete one.
+fn checksum(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c_9dc5, |hash, byte| (hash ^ *byte as u32).wrapping_mul(0x0100_0193))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use super::*;
+
+    /// A log in a file of its own, which starts out empty.
+    fn log(name: &str) -> (WriteAheadLog, PathBuf) {
+        let path = std::env::temp_dir().join(format!("wal-{}-{}", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        (WriteAheadLog::open(&path).unwrap(), path)
+    }
+
+    fn page(byte: u8) -> Page {
+        [byte; PAGE_SIZE]
+    }
+
+    fn ids(frames: &[(PageId, Page)]) -> Vec<(PageId, u8)> {
+        frames.iter().map(|(id, page)| (*id, page[0])).collect()
+    }
+
+    #[test]
+    fn recovers_committed_transactions_in_order() {
+        let (mut wal, path) = log("order");
+        assert!(wal.recover().unwrap().is_empty());
+
+        wal.append(&[(1, &page(1)), (2, &page(2))]).unwrap();
+        wal.append(&[]).unwrap();
+        wal.append(&[(1, &page(3))]).unwrap();
+
+        assert_eq!(ids(&wal.recover().unwrap()), vec![(1, 1), (2, 2), (1, 3)]);
+
+        // Reopening reads back the same log
+        let mut reopened = WriteAheadLog::open(&path).unwrap();
+        assert_eq!(ids(&reopened.recover().unwrap()), vec![(1, 1), (2, 2), (1, 3)]);
+
+        wal.truncate().unwrap();
+        assert!(wal.recover().unwrap().is_empty());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn stops_at_the_first_damaged_transaction() {
+        let (mut wal, path) = log("damaged");
+        wal.append(&[(1, &page(1))]).unwrap();
+        wal.append(&[(2, &page(2))]).unwrap();
+        wal.append(&[(3, &page(3))]).unwrap();
+
+        // A flipped byte in the second transaction's page fails its checksum, so neither it nor the
+        // transaction after it are replayed
+        let mut bytes = fs::read(&path).unwrap();
+        bytes[FRAME_SIZE + COMMIT_SIZE + 10] ^= 0xff;
+        fs::write(&path, &bytes).unwrap();
+        assert_eq!(ids(&wal.recover().unwrap()), vec![(1, 1)]);
+
+        // A commit record counting a different number of frames is just as damaged
+        bytes[FRAME_SIZE + COMMIT_SIZE + 10] ^= 0xff;
+        bytes[FRAME_SIZE + 4] = 2;
+        fs::write(&path, &bytes).unwrap();
+        assert!(wal.recover().unwrap().is_empty());
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn ignores_a_transaction_which_never_finished_committing() {
+        let (mut wal, path) = log("torn");
+        wal.append(&[(1, &page(1))]).unwrap();
+        wal.append(&[(2, &page(2)), (3, &page(3))]).unwrap();
+
+        let bytes = fs::read(&path).unwrap();
+        for length in [FRAME_SIZE + COMMIT_SIZE, FRAME_SIZE + COMMIT_SIZE + 3, FRAME_SIZE * 3 + COMMIT_SIZE, bytes.len() - 1] {
+            fs::write(&path, &bytes[..length]).unwrap();
+            assert_eq!(ids(&wal.recover().unwrap()), vec![(1, 1)], "cut at {}", length);
+        }
+
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,266 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::backend::{Cell, QueryResults};
+
+/// The ways query results can be written out.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum OutputFormat {
+    /// An aligned ASCII table for people to read.
+    Table,
+    /// Comma separated values with a header row, quoted as described by RFC 4180.
+    Csv,
+    /// One JSON object per row, keyed by column name.
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = crate::Error;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_lowercase().as_str() {
+            "table" => Ok(OutputFormat::Table),
+            "csv" => Ok(OutputFormat::Csv),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(format!("Unknown output format {:?}, expected one of table, csv or json", name).into()),
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutputFormat::Table => write!(f, "table"),
+            OutputFormat::Csv => write!(f, "csv"),
+            OutputFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+pub fn render(results: &QueryResults, format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Table => table(results),
+        OutputFormat::Csv => csv(results),
+        OutputFormat::Json => json_lines(results),
+    }
+}
+
+/// Renders the results as a table with a border, number columns aligned to the right and everything
+/// else to the left.
+///
+/// ```text
+/// +----+------+
+/// | id | name |
+/// +----+------+
+/// |  1 | Phil |
+/// +----+------+
+/// ```
+pub fn table(results: &QueryResults) -> String {
+    let rows = results.cells().iter()
+        .map(|row| row.iter().map(table_cell).collect::<Vec<String>>())
+        .collect::<Vec<Vec<String>>>();
+
+    let mut widths = results.columns().iter()
+        .map(|column| column.name().chars().count())
+        .collect::<Vec<usize>>();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let border = widths.iter()
+        .map(|width| format!("+{}", "-".repeat(width + 2)))
+        .collect::<String>() + "+\n";
+
+    let mut output = border.clone();
+
+    let header = results.columns().iter().zip(&widths)
+        .map(|(column, width)| format!("| {:<width$} ", column.name(), width = width))
+        .collect::<String>();
+    output.push_str(&header);
+    output.push_str("|\n");
+    output.push_str(&border);
+
+    for row in &rows {
+        for ((cell, width), column) in row.iter().zip(&widths).zip(results.columns()) {
+            if column.column_type().is_numeric() {
+                output.push_str(&format!("| {:>width$} ", cell, width = width))
+            } else {
+                output.push_str(&format!("| {:<width$} ", cell, width = width))
+            }
+        }
+        output.push_str("|\n");
+    }
+
+    if !rows.is_empty() {
+        output.push_str(&border);
+    }
+
+    output
+}
+
+/// Line breaks would tear the table apart, so they are shown escaped instead.
+fn table_cell(cell: &Cell) -> String {
+    cell.to_string().replace('\r', "\\r").replace('\n', "\\n")
+}
+
+/// Renders the results as CSV with a header row, leaving NULL fields empty. Records end with CRLF as
+/// RFC 4180 asks.
+pub fn csv(results: &QueryResults) -> String {
+    let mut output = String::new();
+
+    let header = results.columns().iter()
+        .map(|column| csv_field(column.name()))
+        .collect::<Vec<String>>();
+    output.push_str(&header.join(","));
+    output.push_str("\r\n");
+
+    for row in results.cells() {
+        let fields = row.iter()
+            .map(|cell| match cell {
+                Cell::Null => String::new(),
+                cell => csv_field(&cell.to_string()),
+            })
+            .collect::<Vec<String>>();
+        output.push_str(&fields.join(","));
+        output.push_str("\r\n");
+    }
+
+    output
+}
+
+/// Fields holding a comma, a quote or a line break are wrapped in quotes, with any quotes inside
+/// doubled.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+/// Renders each row as a JSON object on its own line, with the keys in column order.
+pub fn json_lines(results: &QueryResults) -> String {
+    let mut output = String::new();
+
+    for row in results.cells() {
+        let members = results.columns().iter().zip(row)
+            .map(|(column, cell)| format!("{}:{}", json_string(column.name()), json_value(cell)))
+            .collect::<Vec<String>>();
+        output.push('{');
+        output.push_str(&members.join(","));
+        output.push_str("}\n");
+    }
+
+    output
+}
+
+fn json_value(cell: &Cell) -> String {
+    match cell {
+        Cell::Int32(value) => value.to_string(),
+        Cell::Int64(value) => value.to_string(),
+        // Written as a number with every digit, although some readers will round it to a double
+        Cell::Decimal(value) => value.to_string(),
+        // JSON has no way to write NaN or the infinities
+        Cell::Double(value) if !value.is_finite() => "null".to_string(),
+        Cell::Double(value) => format!("{:?}", value),
+        Cell::String(value) => json_string(value),
+        Cell::Boolean(value) => value.to_string(),
+        Cell::Date(_) | Cell::Time(_) | Cell::Timestamp(_) | Cell::Interval(_) => json_string(&cell.to_string()),
+        Cell::Null => "null".to_string(),
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut output = String::with_capacity(value.len() + 2);
+    output.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if (c as u32) < 0x20 => output.push_str(&format!("\\u{:04x}", c as u32)),
+            c => output.push(c),
+        }
+    }
+
+    output.push('"');
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backend::memory::ColumnTypes;
+    use crate::backend::ResultColumn;
+    use crate::types::decimal::Decimal;
+
+    use super::*;
+
+    fn results() -> QueryResults {
+        QueryResults::new(
+            vec![
+                ResultColumn::new("id".to_owned(), None, ColumnTypes::Int32, false),
+                ResultColumn::new("note, \"quoted\"".to_owned(), None, ColumnTypes::String, true),
+                ResultColumn::new("price".to_owned(), None, ColumnTypes::Decimal(5, 2), true),
+            ],
+            vec![
+                vec![Cell::Int32(1), Cell::String("plain".to_owned()), Cell::Decimal(Decimal::new(1250, 2))],
+                vec![Cell::Int32(22), Cell::String("a \"b\", c\nd\\e\tf\u{1}".to_owned()), Cell::Null],
+                vec![Cell::Int32(3), Cell::String(String::new()), Cell::Decimal(Decimal::new(-5, 2))],
+            ],
+        )
+    }
+
+    #[test]
+    fn tables_align_numbers_right_and_escape_line_breaks() {
+        assert_eq!(table(&results()), [
+            "+----+------------------+-------+",
+            "| id | note, \"quoted\"   | price |",
+            "+----+------------------+-------+",
+            "|  1 | plain            | 12.50 |",
+            "| 22 | a \"b\", c\\nd\\e\tf\u{1} |  NULL |",
+            "|  3 |                  | -0.05 |",
+            "+----+------------------+-------+",
+            "",
+        ].join("\n"));
+
+        let empty = QueryResults::new(vec![ResultColumn::new("id".to_owned(), None, ColumnTypes::Int32, false)], Vec::new());
+        assert_eq!(table(&empty), "+----+\n| id |\n+----+\n");
+    }
+
+    #[test]
+    fn csv_quotes_fields_that_need_it() {
+        assert_eq!(csv(&results()), [
+            "id,\"note, \"\"quoted\"\"\",price",
+            "1,plain,12.50",
+            "22,\"a \"\"b\"\", c\nd\\e\tf\u{1}\",",
+            "3,,-0.05",
+            "",
+        ].join("\r\n"));
+    }
+
+    #[test]
+    fn json_escapes_strings_and_writes_nulls() {
+        assert_eq!(json_lines(&results()), [
+            "{\"id\":1,\"note, \\\"quoted\\\"\":\"plain\",\"price\":12.50}",
+            "{\"id\":22,\"note, \\\"quoted\\\"\":\"a \\\"b\\\", c\\nd\\\\e\\tf\\u0001\",\"price\":null}",
+            "{\"id\":3,\"note, \\\"quoted\\\"\":\"\",\"price\":-0.05}",
+            "",
+        ].join("\n"));
+
+        assert_eq!(json_value(&Cell::Double(f64::NAN)), "null");
+        assert_eq!(json_value(&Cell::Double(2.0)), "2.0");
+        assert_eq!(json_value(&Cell::Boolean(false)), "false");
+    }
+
+    #[test]
+    fn formats_are_named_in_any_case() {
+        assert_eq!("CSV".parse::<OutputFormat>().unwrap(), OutputFormat::Csv);
+        assert_eq!("json".parse::<OutputFormat>().unwrap().to_string(), "json");
+        assert!("xml".parse::<OutputFormat>().is_err());
+    }
+}
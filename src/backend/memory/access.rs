@@ -0,0 +1,244 @@
+use std::cmp::Ordering;
+use std::ops::Bound;
+
+use crate::backend::Cell;
+use crate::backend::btree::RowId;
+use crate::backend::memory::{MemoryCell, Table};
+use crate::backend::memory::evaluator;
+use crate::statements::expression::{BinaryOperator, ColumnReference, Expression};
+
+/// How the rows of a table are read to find the ones a WHERE clause can match. Only the rows which
+/// cannot match are left out, so the WHERE clause still has to be checked against the rest.
+#[derive(Debug, PartialEq)]
+pub enum Access {
+    /// Every row of the table.
+    Scan,
+    /// At most one row, as a PRIMARY KEY or UNIQUE column has to equal a value.
+    Lookup(Option<RowId>),
+    /// The rows with ids in a range, as the integer PRIMARY KEY the table is keyed by has to be
+    /// within some bounds.
+    Range(Bound<RowId>, Bound<RowId>),
+}
+
+impl Access {
+    /// Works out the rows of `table` that `predicate` can match from the comparisons between one of
+    /// its columns and a literal which the predicate needs to hold. `column` finds the column of
+    /// the table a reference points at, if it points into the table at all.
+    pub fn plan<F: Fn(&ColumnReference) -> Option<usize>>(table: &Table, predicate: Option<&Expression>, column: F) -> Access {
+        let mut conjuncts = predicate.into_iter().collect::<Vec<&Expression>>();
+        let mut bounds = (Bound::Unbounded, Bound::Unbounded);
+
+        while let Some(expression) = conjuncts.pop() {
+            let (left, operator, right) = match expression {
+                Expression::Binary(left, BinaryOperator::And, right) => {
+                    conjuncts.push(right);
+                    conjuncts.push(left);
+                    continue;
+                }
+                Expression::Binary(left, operator, right) => (left.as_ref(), *operator, right.as_ref()),
+                _ => continue,
+            };
+
+            // Written either way round, as in `id > 5` or `5 < id`
+            let (index, operator, literal) = match (left, right) {
+                (Expression::Column(reference), Expression::Literal(literal)) => (column(reference), operator, literal),
+                (Expression::Literal(literal), Expression::Column(reference)) => match flip(operator) {
+                    Some(operator) => (column(reference), operator, literal),
+                    None => continue,
+                },
+                _ => continue,
+            };
+            let index = match index {
+                Some(index) => index,
+                None => continue,
+            };
+            let value = Cell::from(literal);
+
+            if operator == BinaryOperator::Equal {
+                if let Some(row_id) = lookup(table, index, &value) {
+                    return Access::Lookup(row_id);
+                }
+            }
+
+            if table.key_column() == Some(index) {
+                if let Some(value) = evaluator::as_integer(&value) {
+                    tighten(&mut bounds, operator, row_key(value));
+                }
+            }
+        }
+
+        match bounds {
+            (Bound::Unbounded, Bound::Unbounded) => Access::Scan,
+            (start, end) => Access::Range(start, end),
+        }
+    }
+}
+
+/// The row id of a row whose integer PRIMARY KEY is `value`. The sign bit is flipped so the ids
+/// are in the same order as the values, putting negative values first.
+pub fn row_key(value: i64) -> RowId {
+    (value as RowId) ^ (1 << 63)
+}
+
+/// Finds the row where the PRIMARY KEY or UNIQUE column at `index` equals `value`, giving `None`
+/// when the column has no index or the value would not be stored exactly as it is, like 1.5 in
+/// an INT column, since then the index cannot tell which rows are equal to it.
+fn lookup(table: &Table, index: usize, value: &Cell) -> Option<Option<RowId>> {
+    if *value == Cell::Null || !table.columns()[index].is_unique() {
+        return None;
+    }
+
+    let stored = MemoryCell::from_cell(value.clone(), &table.columns()[index]).ok()?;
+    match evaluator::compare(&Cell::from(&stored), value) {
+        Ok(Ordering::Equal) => Some(table.find_unique(index, &stored)),
+        _ => None,
+    }
+}
+
+/// The operator which gives the same result with its operands swapped.
+fn flip(operator: BinaryOperator) -> Option<BinaryOperator> {
+    match operator {
+        BinaryOperator::Equal => Some(BinaryOperator::Equal),
+        BinaryOperator::LessThan => Some(BinaryOperator::GreaterThan),
+        BinaryOperator::LessThanOrEqual => Some(BinaryOperator::GreaterThanOrEqual),
+        BinaryOperator::GreaterThan => Some(BinaryOperator::LessThan),
+        BinaryOperator::GreaterThanOrEqual => Some(BinaryOperator::LessThanOrEqual),
+        _ => None,
+    }
+}
+
+/// Narrows the range of row ids to the ones where `key operator row_id` holds, keeping the tighter
+/// of the old and new bound on each side.
+fn tighten(bounds: &mut (Bound<RowId>, Bound<RowId>), operator: BinaryOperator, row_id: RowId) {
+    let (start, end) = match operator {
+        BinaryOperator::Equal => (Bound::Included(row_id), Bound::Included(row_id)),
+        BinaryOperator::GreaterThan => (Bound::Excluded(row_id), Bound::Unbounded),
+        BinaryOperator::GreaterThanOrEqual => (Bound::Included(row_id), Bound::Unbounded),
+        BinaryOperator::LessThan => (Bound::Unbounded, Bound::Excluded(row_id)),
+        BinaryOperator::LessThanOrEqual => (Bound::Unbounded, Bound::Included(row_id)),
+        _ => return,
+    };
+
+    if is_tighter(&start, &bounds.0, Ordering::Greater) {
+        bounds.0 = start;
+    }
+    if is_tighter(&end, &bounds.1, Ordering::Less) {
+        bounds.1 = end;
+    }
+}
+
+/// Whether `new` leaves out more row ids than `old`, where `inwards` is the direction the bound
+/// moves in as it gets tighter.
+fn is_tighter(new: &Bound<RowId>, old: &Bound<RowId>, inwards: Ordering) -> bool {
+    match (new, old) {
+        (Bound::Unbounded, _) => false,
+        (_, Bound::Unbounded) => true,
+        (Bound::Included(new) | Bound::Excluded(new), Bound::Included(old) | Bound::Excluded(old)) if new != old => {
+            new.cmp(old) == inwards
+        }
+        (new, _) => matches!(new, Bound::Excluded(_)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backend::memory::tests::{database, query};
+    use crate::statements::compiler::StatementCompiler;
+    use crate::statements::scanner::{Token, TokenIterator};
+    use crate::statements::Statement;
+
+    use super::*;
+
+    #[test]
+    fn row_keys_are_ordered_like_their_values() {
+        let values = [i64::MIN, -5, -1, 0, 1, 5, i64::MAX];
+        for pair in values.windows(2) {
+            assert!(row_key(pair[0]) < row_key(pair[1]), "{} and {}", pair[0], pair[1]);
+        }
+    }
+
+    /// How the rows of a small table are read for the WHERE clause `predicate`.
+    fn plan(predicate: &str) -> Access {
+        let backend = database("CREATE TABLE users (id INT PRIMARY KEY, email TEXT UNIQUE, age INT);
+            INSERT INTO users VALUES (1, 'a', 30), (2, 'b', 40);");
+        let table = backend.table("users").unwrap();
+
+        let sql = format!("SELECT * FROM users WHERE {}", predicate);
+        let tokens = TokenIterator::new_iterator(sql.chars())
+            .filter(|x| !matches!(x, Ok(token) if *token.token() == Token::Space));
+        let statement = match StatementCompiler::new(tokens).next().unwrap().unwrap() {
+            Statement::Select(statement) => statement,
+            statement => panic!("Expected a select but got {:?}", statement),
+        };
+
+        Access::plan(table, statement.predicate(), |reference| table.column_index(reference.name()))
+    }
+
+    #[test]
+    fn plans_lookups_on_unique_columns() {
+        assert_eq!(plan("email = 'b'"), Access::Lookup(Some(row_key(2))));
+        assert_eq!(plan("'b' = email AND age > 100"), Access::Lookup(Some(row_key(2))));
+        assert_eq!(plan("email = 'c'"), Access::Lookup(None));
+        assert_eq!(plan("id = 1"), Access::Lookup(Some(row_key(1))));
+        assert_eq!(plan("id = 1.00"), Access::Lookup(Some(row_key(1))));
+    }
+
+    #[test]
+    fn plans_ranges_on_the_primary_key() {
+        assert_eq!(plan("id > 1"), Access::Range(Bound::Excluded(row_key(1)), Bound::Unbounded));
+        assert_eq!(plan("id >= 1 AND id < 10 AND id <= 5"), Access::Range(Bound::Included(row_key(1)), Bound::Included(row_key(5))));
+        assert_eq!(plan("10 > id AND id < 10"), Access::Range(Bound::Unbounded, Bound::Excluded(row_key(10))));
+        assert_eq!(plan("id >= 3 AND id > 3"), Access::Range(Bound::Excluded(row_key(3)), Bound::Unbounded));
+    }
+
+    #[test]
+    fn scans_when_no_index_helps() {
+        assert_eq!(plan("age = 30"), Access::Scan);
+        assert_eq!(plan("id = 1 OR id = 2"), Access::Scan);
+        assert_eq!(plan("email > 'a'"), Access::Scan);
+        assert_eq!(plan("id = 1.5"), Access::Scan);
+        assert_eq!(plan("id = NULL"), Access::Scan);
+        assert_eq!(plan("NOT id = 1"), Access::Scan);
+    }
+
+    #[test]
+    fn lookups_find_the_same_rows_as_a_scan() {
+        let mut backend = database("CREATE TABLE users (id INT PRIMARY KEY, email TEXT UNIQUE, age INT);
+            INSERT INTO users VALUES (3, 'c', 30), (-1, 'a', 10), (2, 'b', 20), (10, NULL, 40);");
+
+        // Rows are stored in the order of their key
+        assert_eq!(query(&mut backend, "SELECT email FROM users"), vec!["a", "b", "c", "NULL"]);
+        assert_eq!(query(&mut backend, "SELECT id FROM users WHERE id = 2"), vec!["2"]);
+        assert_eq!(query(&mut backend, "SELECT id FROM users WHERE email = 'c' AND age = 30"), vec!["3"]);
+        assert!(query(&mut backend, "SELECT id FROM users WHERE email = 'c' AND age = 20").is_empty());
+        assert_eq!(query(&mut backend, "SELECT id FROM users WHERE id >= 0 AND id < 10"), vec!["2", "3"]);
+        assert_eq!(query(&mut backend, "SELECT id FROM users WHERE id < 3 ORDER BY id DESC"), vec!["2", "-1"]);
+        assert!(query(&mut backend, "SELECT id FROM users WHERE id > 10").is_empty());
+        assert_eq!(query(&mut backend, "SELECT COUNT(*) FROM users WHERE id > -1"), vec!["3"]);
+    }
+
+    #[test]
+    fn updates_and_deletes_use_lookups() {
+        let mut backend = database("CREATE TABLE users (id BIGINT PRIMARY KEY, email TEXT UNIQUE);
+            INSERT INTO users VALUES (1, 'a'), (2, 'b'), (3, 'c'), (4, 'd');
+            UPDATE users SET email = 'z' WHERE email = 'b';
+            UPDATE users SET id = id + 10 WHERE id >= 3;
+            DELETE FROM users WHERE id = 1;");
+
+        assert_eq!(query(&mut backend, "SELECT id, email FROM users"), vec!["2, z", "13, c", "14, d"]);
+        assert_eq!(query(&mut backend, "SELECT email FROM users WHERE id = 13"), vec!["c"]);
+    }
+
+    #[test]
+    fn rows_are_keyed_again_when_the_primary_key_changes() {
+        let mut backend = database("CREATE TABLE users (name TEXT);
+            INSERT INTO users VALUES ('a');
+            ALTER TABLE users ADD COLUMN id INT PRIMARY KEY DEFAULT 7;");
+
+        assert_eq!(query(&mut backend, "SELECT name FROM users WHERE id = 7"), vec!["a"]);
+        assert_eq!(query(&mut backend, "SELECT name FROM users WHERE id > 6"), vec!["a"]);
+
+        query(&mut backend, "ALTER TABLE users DROP COLUMN id; INSERT INTO users VALUES ('b')");
+        assert_eq!(query(&mut backend, "SELECT name FROM users"), vec!["a", "b"]);
+    }
+}
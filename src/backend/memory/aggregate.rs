@@ -0,0 +1,519 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use crate::backend::{Cell, QueryResults};
+use crate::backend::memory::ColumnTypes;
+use crate::backend::memory::evaluator::{self, Scope};
+use crate::backend::memory::relation::{ColumnPosition, JoinedRow, Relation, RelationScope};
+use crate::Result;
+use crate::statements::expression::{Aggregate, AggregateFunction, BinaryOperator, ColumnReference, Expression, Literal, UnaryOperator};
+use crate::statements::select::{self, SelectStatement};
+use crate::types::decimal::{self, Decimal};
+
+/// The running state of one aggregate function over the rows of a group.
+enum Accumulator {
+    Count(u64),
+    Sum(Option<Cell>),
+    Min(Option<Cell>),
+    Max(Option<Cell>),
+    Avg(Option<Cell>, u64),
+}
+
+impl Accumulator {
+    fn new(function: AggregateFunction) -> Self {
+        match function {
+            AggregateFunction::Count => Accumulator::Count(0),
+            AggregateFunction::Sum => Accumulator::Sum(None),
+            AggregateFunction::Min => Accumulator::Min(None),
+            AggregateFunction::Max => Accumulator::Max(None),
+            AggregateFunction::Avg => Accumulator::Avg(None, 0),
+        }
+    }
+
+    /// Counts a row for `COUNT(*)`, which has no argument to look at.
+    fn accumulate_row(&mut self) {
+        if let Accumulator::Count(count) = self {
+            *count += 1;
+        }
+    }
+
+    /// Adds the argument's value for one row. Like every SQL aggregate, NULLs are skipped.
+    fn accumulate(&mut self, value: Cell) -> Result<()> {
+        if value == Cell::Null {
+            return Ok(());
+        }
+
+        match self {
+            Accumulator::Count(count) => *count += 1,
+            Accumulator::Sum(sum) => *sum = Some(add(sum.take(), &value)?),
+            Accumulator::Avg(sum, count) => {
+                // The average of integers is a double, so their total is kept as one and cannot overflow
+                let value = match value {
+                    Cell::Int32(_) | Cell::Int64(_) => Cell::Double(evaluator::as_double(&value).unwrap_or_default()),
+                    value => value,
+                };
+                *sum = Some(add(sum.take(), &value)?);
+                *count += 1;
+            }
+            Accumulator::Min(min) => {
+                if min.as_ref().map_or(Ok(true), |min| evaluator::compare(&value, min).map(|ordering| ordering.is_lt()))? {
+                    *min = Some(value);
+                }
+            }
+            Accumulator::Max(max) => {
+                if max.as_ref().map_or(Ok(true), |max| evaluator::compare(&value, max).map(|ordering| ordering.is_gt()))? {
+                    *max = Some(value);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The aggregate's value, which is NULL for everything but COUNT when there were no values.
+    fn finish(self) -> Result<Cell> {
+        match self {
+            Accumulator::Count(count) => i64::try_from(count)
+                .map(Cell::Int64)
+                .map_err(|_| format!("The result of COUNT is too large for a BIGINT: {}", count).into()),
+            Accumulator::Sum(sum) => Ok(sum.unwrap_or(Cell::Null)),
+            Accumulator::Avg(None, _) => Ok(Cell::Null),
+            Accumulator::Avg(Some(Cell::Decimal(sum)), count) => i64::try_from(count).ok()
+                .and_then(|count| sum.checked_div(&Decimal::from(count)))
+                .map(Cell::Decimal)
+                .ok_or_else(|| "The result of AVG is too large for a DECIMAL".into()),
+            Accumulator::Avg(Some(sum), count) => Ok(Cell::Double(evaluator::as_double(&sum).unwrap_or_default() / count as f64)),
+            Accumulator::Min(value) | Accumulator::Max(value) => Ok(value.unwrap_or(Cell::Null)),
+        }
+    }
+}
+
+/// Adds a value to the running total of SUM or AVG. Integers add up to a BIGINT and decimals to
+/// an exact decimal, and a total too large for either is an error rather than wrapping around.
+/// Once a double is added the total is a double.
+fn add(total: Option<Cell>, value: &Cell) -> Result<Cell> {
+    let total = total.unwrap_or(Cell::Int64(0));
+
+    if let (Some(left), Some(right)) = (evaluator::as_integer(&total), evaluator::as_integer(value)) {
+        return left.checked_add(right)
+            .map(Cell::Int64)
+            .ok_or_else(|| "The result of SUM is too large for a BIGINT".into());
+    }
+
+    if let (Some(left), Some(right)) = (evaluator::as_decimal(&total), evaluator::as_decimal(value)) {
+        return left.checked_add(&right)
+            .map(Cell::Decimal)
+            .ok_or_else(|| "The total of the values is too large for a DECIMAL".into());
+    }
+
+    match (evaluator::as_double(&total), evaluator::as_double(value)) {
+        (Some(left), Some(right)) => Ok(Cell::Double(left + right)),
+        _ => Err(format!("Expected a number to add up but got {:?}", value).into()),
+    }
+}
+
+/// The column type of an aggregate's result and whether it can be NULL.
+fn result_type(aggregate: &Aggregate, relation: &Relation) -> Result<(ColumnTypes, bool)> {
+    let argument_type = match aggregate.argument() {
+        Some(argument) => match expression_type(argument, relation)? {
+            (Some(column_type), _) => Some(column_type),
+            (None, _) => return Err(format!("{} cannot be applied to NULL", aggregate).into()),
+        },
+        None => None,
+    };
+
+    match (aggregate.function(), argument_type) {
+        (AggregateFunction::Count, _) => Ok((ColumnTypes::Int64, false)),
+        (AggregateFunction::Sum, Some(column_type)) if column_type.is_integer() => Ok((ColumnTypes::Int64, true)),
+        (AggregateFunction::Sum, Some(ColumnTypes::Decimal(_, scale))) => Ok((ColumnTypes::Decimal(decimal::MAX_PRECISION, scale), true)),
+        (AggregateFunction::Sum, Some(ColumnTypes::Double)) => Ok((ColumnTypes::Double, true)),
+        (AggregateFunction::Avg, Some(ColumnTypes::Decimal(_, scale))) => {
+            Ok((ColumnTypes::Decimal(decimal::MAX_PRECISION, Decimal::division_scale(scale, 0)), true))
+        }
+        (AggregateFunction::Avg, Some(column_type)) if column_type.is_numeric() => Ok((ColumnTypes::Double, true)),
+        (AggregateFunction::Min, Some(column_type)) | (AggregateFunction::Max, Some(column_type)) => Ok((column_type, true)),
+        (_, Some(column_type)) => Err(format!("{} cannot be applied to {} values", aggregate, column_type).into()),
+        (_, None) => Err(format!("{} needs an argument", aggregate).into()),
+    }
+}
+
+/// Works out the column type of the values `expression` evaluates to, following the same rules as
+/// the evaluator, and whether they can be NULL. A bare NULL has no type of its own, so its type is
+/// `None`, and an operator applied to it takes the type of its other operand.
+pub fn expression_type(expression: &Expression, relation: &Relation) -> Result<(Option<ColumnTypes>, bool)> {
+    match expression {
+        Expression::Column(reference) => {
+            let column = relation.result_column(relation.resolve(reference)?);
+            Ok((Some(*column.column_type()), column.is_nullable()))
+        }
+        Expression::Literal(literal) => {
+            let column_type = match Cell::from(literal) {
+                Cell::Int32(_) => ColumnTypes::Int32,
+                Cell::Int64(_) => ColumnTypes::Int64,
+                Cell::Decimal(value) => ColumnTypes::Decimal(decimal::MAX_PRECISION, value.scale()),
+                Cell::Double(_) => ColumnTypes::Double,
+                Cell::Boolean(_) => ColumnTypes::Boolean,
+                Cell::String(_) => ColumnTypes::String,
+                Cell::Date(_) => ColumnTypes::Date,
+                Cell::Time(_) => ColumnTypes::Time,
+                Cell::Timestamp(_) => ColumnTypes::Timestamp,
+                Cell::Interval(_) => ColumnTypes::Interval,
+                Cell::Null => return Ok((None, true)),
+            };
+            Ok((Some(column_type), false))
+        }
+        Expression::Aggregate(aggregate) => result_type(aggregate, relation).map(|(column_type, nullable)| (Some(column_type), nullable)),
+        Expression::Unary(UnaryOperator::Not, operand) => Ok((Some(ColumnTypes::Boolean), expression_type(operand, relation)?.1)),
+        Expression::Unary(UnaryOperator::Negate, operand) => {
+            let (column_type, nullable) = expression_type(operand, relation)?;
+            match column_type {
+                Some(column_type) if column_type.is_integer() => Ok((Some(ColumnTypes::Int64), nullable)),
+                Some(ColumnTypes::Decimal(..) | ColumnTypes::Double | ColumnTypes::Interval) | None => Ok((column_type, nullable)),
+                Some(column_type) => Err(format!("Unable to negate {} values", column_type).into()),
+            }
+        }
+        Expression::Unary(UnaryOperator::IsNull | UnaryOperator::IsNotNull, _) => Ok((Some(ColumnTypes::Boolean), false)),
+        Expression::Binary(left, operator, right) => {
+            let (left_type, left_nullable) = expression_type(left, relation)?;
+            let (right_type, right_nullable) = expression_type(right, relation)?;
+            let nullable = left_nullable || right_nullable;
+
+            if !operator.is_arithmetic() {
+                return Ok((Some(ColumnTypes::Boolean), nullable));
+            }
+
+            match (left_type, right_type) {
+                (Some(left_type), Some(right_type)) => Ok((Some(arithmetic_type(left_type, *operator, right_type, right)?), nullable)),
+                (known, None) | (None, known) => Ok((known, true)),
+            }
+        }
+    }
+}
+
+/// The column type of the result of an arithmetic operator. Integers give a BIGINT and anything
+/// with a double gives a double, while decimals keep as many places as the evaluator does. Moving
+/// a date by an interval gives a date, unless the interval is written with a time of day, which
+/// makes it a timestamp.
+fn arithmetic_type(left: ColumnTypes, operator: BinaryOperator, right: ColumnTypes, right_expression: &Expression) -> Result<ColumnTypes> {
+    let scale = |column_type: ColumnTypes| match column_type {
+        ColumnTypes::Decimal(_, scale) => Some(scale),
+        column_type if column_type.is_integer() => Some(0),
+        _ => None,
+    };
+    let add = operator == BinaryOperator::Add;
+    let subtract = operator == BinaryOperator::Subtract;
+
+    let result = match (left, right) {
+        (left, right) if left.is_integer() && right.is_integer() => ColumnTypes::Int64,
+        (ColumnTypes::Double, right) if right.is_numeric() => ColumnTypes::Double,
+        (left, ColumnTypes::Double) if left.is_numeric() => ColumnTypes::Double,
+        (left, right) if left.is_numeric() && right.is_numeric() => {
+            let (left, right) = (scale(left).unwrap_or_default(), scale(right).unwrap_or_default());
+            let scale = match operator {
+                BinaryOperator::Multiply => left.saturating_add(right).min(decimal::MAX_PRECISION),
+                BinaryOperator::Divide => Decimal::division_scale(left, right),
+                _ => left.max(right),
+            };
+            ColumnTypes::Decimal(decimal::MAX_PRECISION, scale)
+        }
+        (ColumnTypes::Interval, ColumnTypes::Interval) if add || subtract => ColumnTypes::Interval,
+        (ColumnTypes::Date, ColumnTypes::Interval) if add || subtract => {
+            let time_of_day = matches!(right_expression, Expression::Literal(Literal::Interval(interval)) if interval.micros() != 0);
+            if time_of_day { ColumnTypes::Timestamp } else { ColumnTypes::Date }
+        }
+        (ColumnTypes::Interval, ColumnTypes::Date) if add => ColumnTypes::Date,
+        (value @ (ColumnTypes::Time | ColumnTypes::Timestamp), ColumnTypes::Interval) if add || subtract => value,
+        (ColumnTypes::Interval, value @ (ColumnTypes::Time | ColumnTypes::Timestamp)) if add => value,
+        (ColumnTypes::Date | ColumnTypes::Timestamp, ColumnTypes::Date | ColumnTypes::Timestamp) if subtract => ColumnTypes::Interval,
+        (ColumnTypes::Time, ColumnTypes::Time) if subtract => ColumnTypes::Interval,
+        _ => return Err(format!("Unable to apply {} to {:?} and {:?}", operator, left, right).into()),
+    };
+
+    Ok(result)
+}
+
+/// The values of one group: its GROUP BY key and the result of every aggregate.
+struct Group {
+    key: Vec<Cell>,
+    values: Vec<Cell>,
+}
+
+/// The GROUP BY clause of a statement, along with where each of its plain columns is found.
+struct Grouping<'s> {
+    group_by: &'s [Expression],
+    columns: Vec<Option<ColumnPosition>>,
+}
+
+impl<'s> Grouping<'s> {
+    fn new(group_by: &'s [Expression], relation: &Relation) -> Result<Self> {
+        let columns = group_by.iter()
+            .map(|expression| match expression {
+                Expression::Column(reference) => relation.resolve(reference).map(Some),
+                _ => Ok(None),
+            })
+            .collect::<Result<Vec<Option<ColumnPosition>>>>()?;
+
+        Ok(Grouping { group_by, columns })
+    }
+
+    /// Finds the GROUP BY expression which `expression` stands for. Columns match wherever they
+    /// point at the same column, so `u.dept` matches `GROUP BY dept`.
+    fn position(&self, expression: &Expression, relation: &Relation) -> Option<usize> {
+        if let Some(index) = self.group_by.iter().position(|group_by| group_by == expression) {
+            return Some(index);
+        }
+
+        match expression {
+            Expression::Column(reference) => {
+                let position = relation.resolve(reference).ok()?;
+                self.columns.iter().position(|column| *column == Some(position))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Evaluates expressions against a group, where the GROUP BY expressions and aggregates are known
+/// but the individual rows are not.
+struct GroupScope<'a> {
+    relation: &'a Relation<'a>,
+    grouping: &'a Grouping<'a>,
+    aggregates: &'a [Aggregate],
+    group: &'a Group,
+}
+
+impl Scope for GroupScope<'_> {
+    fn lookup(&self, expression: &Expression) -> Option<Result<Cell>> {
+        if let Some(index) = self.grouping.position(expression, self.relation) {
+            return Some(Ok(self.group.key[index].clone()));
+        }
+
+        match expression {
+            Expression::Aggregate(aggregate) => self.aggregates.iter()
+                .position(|known| known == aggregate)
+                .map(|index| Ok(self.group.values[index].clone())),
+            Expression::Column(reference) => Some(Err(not_grouped(reference))),
+            _ => None,
+        }
+    }
+}
+
+/// Makes sure a value returned for a group only reads columns the group has a single value for,
+/// which are the ones grouped by and the ones inside aggregates.
+fn check_grouped(expression: &Expression, grouping: &Grouping, relation: &Relation) -> Result<()> {
+    if grouping.position(expression, relation).is_some() {
+        return Ok(());
+    }
+
+    match expression {
+        Expression::Literal(_) | Expression::Aggregate(_) => Ok(()),
+        Expression::Column(reference) => {
+            relation.resolve(reference)?;
+            Err(not_grouped(reference))
+        }
+        Expression::Unary(_, operand) => check_grouped(operand, grouping, relation),
+        Expression::Binary(left, _, right) => {
+            check_grouped(left, grouping, relation)?;
+            check_grouped(right, grouping, relation)
+        }
+    }
+}
+
+fn not_grouped(reference: &ColumnReference) -> crate::Error {
+    format!("Column {:?} must appear in the GROUP BY clause or be used in an aggregate function", reference.to_string()).into()
+}
+
+/// Splits the rows matching `predicate` into groups with a hash table, in the order each group is
+/// first seen, and computes every aggregate for each of them.
+fn group<'a, I: Iterator<Item=JoinedRow<'a>>>(
+    rows: I,
+    relation: &Relation<'a>,
+    predicate: Option<&Expression>,
+    group_by: &[Expression],
+    aggregates: &[Aggregate],
+) -> Result<Vec<Group>> {
+    let new_accumulators = || aggregates.iter()
+        .map(|aggregate| Accumulator::new(aggregate.function()))
+        .collect::<Vec<Accumulator>>();
+
+    let mut groups = Vec::new();
+    let mut positions = HashMap::new();
+
+    for row in rows {
+        let scope = RelationScope::new(relation, &row);
+
+        if let Some(predicate) = predicate {
+            if !evaluator::evaluate_predicate_in(predicate, &scope)? {
+                continue;
+            }
+        }
+
+        let key = group_by.iter()
+            .map(|expression| evaluator::evaluate_in(expression, &scope))
+            .collect::<Result<Vec<Cell>>>()?;
+
+        let position = match positions.get(&key) {
+            Some(position) => *position,
+            None => {
+                groups.push((key.clone(), new_accumulators()));
+                positions.insert(key, groups.len() - 1);
+                groups.len() - 1
+            }
+        };
+
+        for (accumulator, aggregate) in groups[position].1.iter_mut().zip(aggregates) {
+            match aggregate.argument() {
+                Some(argument) => accumulator.accumulate(evaluator::evaluate_in(argument, &scope)?)?,
+                None => accumulator.accumulate_row(),
+            }
+        }
+    }
+
+    // Without GROUP BY the whole table is one group, which exists even when it has no rows
+    if group_by.is_empty() && groups.is_empty() {
+        groups.push((Vec::new(), new_accumulators()));
+    }
+
+    groups.into_iter()
+        .map(|(key, accumulators)| {
+            let values = accumulators.into_iter()
+                .map(Accumulator::finish)
+                .collect::<Result<Vec<Cell>>>()?;
+            Ok(Group { key, values })
+        })
+        .collect()
+}
+
+/// Runs a SELECT which collapses rows into groups, so each result row describes a group.
+pub fn select_grouped(relation: &Relation, stmt: &SelectStatement) -> Result<QueryResults> {
+    let grouping = Grouping::new(stmt.group_by(), relation)?;
+
+    let mut aggregates: Vec<Aggregate> = Vec::new();
+    let mut columns = Vec::new();
+    let mut projection = Vec::new();
+
+    for item in stmt.expression() {
+        match item {
+            select::Expression::All | select::Expression::AllOf(_) => {
+                return Err("SELECT * cannot be used with GROUP BY or aggregate functions".into());
+            }
+            select::Expression::Value(expression, alias) => {
+                check_grouped(expression, &grouping, relation)?;
+
+                columns.push(relation.value_column(expression, alias.as_deref())?);
+                projection.push(expression.clone());
+            }
+        }
+    }
+
+    // Every aggregate the statement mentions is worked out once per group
+    for expression in projection.iter().chain(stmt.having()).chain(stmt.order_by().iter().map(|item| item.expression())) {
+        expression.visit_aggregates(&mut |aggregate| {
+            if !aggregates.contains(aggregate) {
+                aggregates.push(aggregate.clone());
+            }
+        });
+    }
+
+    let groups = group(relation.rows(stmt.predicate())?, relation, stmt.predicate(), stmt.group_by(), &aggregates)?;
+
+    let mut keyed_results = Vec::new();
+
+    for group in &groups {
+        let scope = GroupScope { relation, grouping: &grouping, aggregates: &aggregates, group };
+
+        if let Some(having) = stmt.having() {
+            if !evaluator::evaluate_predicate_in(having, &scope)? {
+                continue;
+            }
+        }
+
+        let keys = stmt.order_by().iter()
+            .map(|item| evaluator::evaluate_in(item.expression(), &scope))
+            .collect::<Result<Vec<Cell>>>()?;
+
+        let row = projection.iter()
+            .map(|expression| evaluator::evaluate_in(expression, &scope))
+            .collect::<Result<Vec<Cell>>>()?;
+
+        keyed_results.push((keys, row));
+    }
+
+    Ok(QueryResults::new(columns, super::sort_and_limit(stmt, keyed_results)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backend::memory::InMemoryBackend;
+    use crate::backend::memory::tests::{database, error, query};
+
+    fn orders() -> InMemoryBackend {
+        database("CREATE TABLE orders (customer TEXT, price DECIMAL(10, 2), qty INT, discount DOUBLE, placed DATE, shipped DATE);
+            INSERT INTO orders VALUES
+                ('ann', 2.50, 4, 0.5, '2024-01-01', '2024-01-03'),
+                ('ann', 1.25, 2, NULL, '2024-01-05', NULL),
+                ('bob', 10.00, 1, 1.0, '2024-02-01', '2024-02-11'),
+                ('cat', NULL, 3, 0.0, '2024-03-01', '2024-03-02');")
+    }
+
+    #[test]
+    fn aggregates_apply_to_expressions() {
+        let mut backend = orders();
+
+        assert_eq!(query(&mut backend, "SELECT SUM(price * qty) FROM orders"), vec!["22.50"]);
+        assert_eq!(query(&mut backend, "SELECT AVG(qty + discount) FROM orders"), vec!["3.1666666666666665"]);
+        assert_eq!(query(&mut backend, "SELECT MAX(shipped - placed), MIN(price / qty) FROM orders"), vec!["10 days, 0.625000"]);
+        assert_eq!(query(&mut backend, "SELECT SUM(qty * 2 - 1), COUNT(price + 1) FROM orders"), vec!["16, 3"]);
+        assert_eq!(query(&mut backend, "SELECT MIN(placed + INTERVAL '1' MONTH) FROM orders"), vec!["2024-02-01"]);
+    }
+
+    #[test]
+    fn aggregates_over_expressions_have_result_types() {
+        let mut backend = orders();
+
+        query(&mut backend, "CREATE TABLE totals (total DECIMAL(20, 4), mean DOUBLE);
+            INSERT INTO totals SELECT SUM(price * qty), AVG(qty + discount) FROM orders;");
+        assert_eq!(query(&mut backend, "SELECT * FROM totals"), vec!["22.5000, 3.1666666666666665"]);
+
+        assert_eq!(error(&mut backend, "SELECT SUM(placed + 1) FROM orders"), "Unable to apply + to Date and Int64");
+        assert_eq!(error(&mut backend, "SELECT SUM(NULL) FROM orders"), "SUM(NULL) cannot be applied to NULL");
+        assert_eq!(error(&mut backend, "SELECT SUM(shipped - placed) FROM orders"), "SUM(shipped - placed) cannot be applied to INTERVAL values");
+    }
+
+    #[test]
+    fn groups_are_filtered_by_having() {
+        let mut backend = orders();
+
+        assert_eq!(
+            query(&mut backend, "SELECT customer, COUNT(*), SUM(qty) FROM orders GROUP BY customer"),
+            vec!["ann, 2, 6", "bob, 1, 1", "cat, 1, 3"],
+        );
+        assert_eq!(
+            query(&mut backend, "SELECT customer FROM orders GROUP BY customer HAVING SUM(qty) > 2 ORDER BY COUNT(*) DESC, customer"),
+            vec!["ann", "cat"],
+        );
+        assert_eq!(
+            query(&mut backend, "SELECT customer, MAX(price) FROM orders WHERE qty < 4 GROUP BY customer ORDER BY customer"),
+            vec!["ann, 1.25", "bob, 10.00", "cat, NULL"],
+        );
+    }
+
+    #[test]
+    fn aggregates_without_rows_make_one_group() {
+        let mut backend = orders();
+
+        assert_eq!(query(&mut backend, "SELECT COUNT(*), SUM(qty), AVG(price) FROM orders WHERE qty > 10"), vec!["0, NULL, NULL"]);
+        assert!(query(&mut backend, "SELECT customer, COUNT(*) FROM orders WHERE qty > 10 GROUP BY customer").is_empty());
+    }
+
+    #[test]
+    fn ungrouped_columns_are_an_error() {
+        let mut backend = orders();
+
+        assert_eq!(
+            error(&mut backend, "SELECT customer, COUNT(*) FROM orders"),
+            "Column \"customer\" must appear in the GROUP BY clause or be used in an aggregate function",
+        );
+        assert_eq!(error(&mut backend, "SELECT * FROM orders GROUP BY customer"), "SELECT * cannot be used with GROUP BY or aggregate functions");
+    }
+}
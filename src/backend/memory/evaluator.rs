@@ -0,0 +1,420 @@
+use std::cmp::Ordering;
+
+use crate::backend::Cell;
+use crate::backend::memory::{Column, MemoryCell};
+use crate::Result;
+use crate::statements::expression::{BinaryOperator, Expression, UnaryOperator};
+use crate::statements::select::{OrderBy, SortDirection};
+use crate::types::decimal::Decimal;
+use crate::types::temporal::{Date, Interval, Time, Timestamp};
+
+/// Resolves the parts of an expression which depend on where it is evaluated.
+pub trait Scope {
+    /// Returns the value of `expression` when the scope knows it directly, like a column of the
+    /// current row, or `None` to work it out from its parts.
+    fn lookup(&self, expression: &Expression) -> Option<Result<Cell>>;
+}
+
+/// Evaluates expressions against a single row of the table called `table`.
+pub struct RowScope<'a> {
+    table: &'a str,
+    columns: &'a [Column],
+    row: &'a [MemoryCell],
+}
+
+impl<'a> RowScope<'a> {
+    pub fn new(table: &'a str, columns: &'a [Column], row: &'a [MemoryCell]) -> Self {
+        RowScope { table, columns, row }
+    }
+}
+
+impl Scope for RowScope<'_> {
+    fn lookup(&self, expression: &Expression) -> Option<Result<Cell>> {
+        match expression {
+            Expression::Column(reference) if matches!(reference.table(), Some(table) if table != self.table) => None,
+            Expression::Column(reference) => self.columns.iter()
+                .position(|column| column.name() == reference.name())
+                .map(|index| Ok(Cell::from(&self.row[index]))),
+            _ => None,
+        }
+    }
+}
+
+pub fn evaluate(expression: &Expression, table: &str, columns: &[Column], row: &[MemoryCell]) -> Result<Cell> {
+    evaluate_in(expression, &RowScope::new(table, columns, row))
+}
+
+/// Evaluates a WHERE clause. A predicate which is unknown, because it evaluated to NULL, does not
+/// match the row, just as if it were false.
+pub fn evaluate_predicate(expression: &Expression, table: &str, columns: &[Column], row: &[MemoryCell]) -> Result<bool> {
+    evaluate_predicate_in(expression, &RowScope::new(table, columns, row))
+}
+
+pub fn evaluate_in(expression: &Expression, scope: &dyn Scope) -> Result<Cell> {
+    if let Some(value) = scope.lookup(expression) {
+        return value;
+    }
+
+    match expression {
+        Expression::Literal(literal) => Ok(Cell::from(literal)),
+        Expression::Column(reference) => Err(format!("Column {:?} is not found", reference.to_string()).into()),
+        Expression::Aggregate(aggregate) => Err(format!("Aggregate function {} is not allowed here", aggregate).into()),
+        Expression::Unary(UnaryOperator::Not, operand) => {
+            match evaluate_in(operand, scope)? {
+                Cell::Boolean(value) => Ok(Cell::Boolean(!value)),
+                Cell::Null => Ok(Cell::Null),
+                value => Err(format!("Expected a Boolean operand for NOT but got {:?}", value).into()),
+            }
+        }
+        Expression::Unary(UnaryOperator::Negate, operand) => negate(evaluate_in(operand, scope)?),
+        Expression::Unary(UnaryOperator::IsNull, operand) => {
+            Ok(Cell::Boolean(evaluate_in(operand, scope)? == Cell::Null))
+        }
+        Expression::Unary(UnaryOperator::IsNotNull, operand) => {
+            Ok(Cell::Boolean(evaluate_in(operand, scope)? != Cell::Null))
+        }
+        Expression::Binary(left, operator, right) => {
+            let left = evaluate_in(left, scope)?;
+            let right = evaluate_in(right, scope)?;
+            evaluate_binary(left, *operator, right)
+        }
+    }
+}
+
+pub fn evaluate_predicate_in(expression: &Expression, scope: &dyn Scope) -> Result<bool> {
+    match evaluate_in(expression, scope)? {
+        Cell::Boolean(value) => Ok(value),
+        Cell::Null => Ok(false),
+        value => Err(format!("Expected the predicate to evaluate to a Boolean but got {:?}", value).into()),
+    }
+}
+
+/// Applies SQL's three-valued logic, where NULL stands for an unknown value. Comparing with NULL is
+/// unknown, while AND and OR are only unknown when the known operands do not already decide them.
+fn evaluate_binary(left: Cell, operator: BinaryOperator, right: Cell) -> Result<Cell> {
+    let value = match operator {
+        BinaryOperator::And => {
+            match (as_boolean(left, operator)?, as_boolean(right, operator)?) {
+                (Some(false), _) | (_, Some(false)) => Some(false),
+                (Some(true), Some(true)) => Some(true),
+                _ => None,
+            }
+        }
+        BinaryOperator::Or => {
+            match (as_boolean(left, operator)?, as_boolean(right, operator)?) {
+                (Some(true), _) | (_, Some(true)) => Some(true),
+                (Some(false), Some(false)) => Some(false),
+                _ => None,
+            }
+        }
+        _ if left == Cell::Null || right == Cell::Null => None,
+        BinaryOperator::Add | BinaryOperator::Subtract | BinaryOperator::Multiply | BinaryOperator::Divide => {
+            return evaluate_arithmetic(left, operator, right);
+        }
+        BinaryOperator::Equal => Some(compare(&left, &right)?.is_eq()),
+        BinaryOperator::NotEqual => Some(compare(&left, &right)?.is_ne()),
+        BinaryOperator::LessThan => Some(compare(&left, &right)?.is_lt()),
+        BinaryOperator::LessThanOrEqual => Some(compare(&left, &right)?.is_le()),
+        BinaryOperator::GreaterThan => Some(compare(&left, &right)?.is_gt()),
+        BinaryOperator::GreaterThanOrEqual => Some(compare(&left, &right)?.is_ge()),
+    };
+    Ok(value.map_or(Cell::Null, Cell::Boolean))
+}
+
+/// Applies an arithmetic operator to two values which are not NULL. Numbers are worked out by
+/// `evaluate_numeric`, while intervals move dates and times, and the difference between two dates
+/// or times is an interval.
+fn evaluate_arithmetic(left: Cell, operator: BinaryOperator, right: Cell) -> Result<Cell> {
+    if let Some(result) = evaluate_numeric(&left, operator, &right) {
+        return result;
+    }
+
+    if !matches!(operator, BinaryOperator::Add | BinaryOperator::Subtract) {
+        return Err(format!("Unable to apply {} to {:?} and {:?}", operator, left, right).into());
+    }
+
+    let subtract = operator == BinaryOperator::Subtract;
+    let out_of_range = || -> crate::Error { format!("The result of {} {} {} is out of range", left, operator, right).into() };
+
+    let (value, interval) = match (&left, &right) {
+        (value, Cell::Interval(interval)) => {
+            let interval = if subtract { interval.checked_neg().ok_or_else(out_of_range)? } else { *interval };
+            (value, interval)
+        }
+        (Cell::Interval(interval), value) if !subtract => (value, *interval),
+        (Cell::Date(left), Cell::Date(right)) if subtract => return Ok(Cell::Interval(left.since(right))),
+        (Cell::Time(left), Cell::Time(right)) if subtract => return Ok(Cell::Interval(left.since(right))),
+        (Cell::Timestamp(left), Cell::Timestamp(right)) if subtract => return Ok(Cell::Interval(left.since(right))),
+        (Cell::Date(left), Cell::Timestamp(right)) if subtract => return Ok(Cell::Interval(Timestamp::from(*left).since(right))),
+        (Cell::Timestamp(left), Cell::Date(right)) if subtract => return Ok(Cell::Interval(left.since(&Timestamp::from(*right)))),
+        _ => return Err(format!("Unable to apply {} to {:?} and {:?}", operator, left, right).into()),
+    };
+
+    match value {
+        Cell::Interval(value) => value.checked_add(&interval).map(Cell::Interval).ok_or_else(out_of_range),
+        // A date stays a date unless the interval has a time of day in it
+        Cell::Date(date) if interval.micros() == 0 => date.checked_add(&interval).map(Cell::Date).ok_or_else(out_of_range),
+        Cell::Date(date) => Timestamp::from(*date).checked_add(&interval).map(Cell::Timestamp).ok_or_else(out_of_range),
+        Cell::Timestamp(timestamp) => timestamp.checked_add(&interval).map(Cell::Timestamp).ok_or_else(out_of_range),
+        Cell::Time(_) if interval.months() != 0 || interval.days() != 0 => {
+            Err(format!("Unable to move the TIME {} by {}, as a time has no days or months", value, interval).into())
+        }
+        Cell::Time(time) => Ok(Cell::Time(time.wrapping_add(interval.micros()))),
+        value => Err(format!("Unable to apply {} to {:?} and {:?}", operator, value, interval).into()),
+    }
+}
+
+/// Applies an arithmetic operator to two numbers, or returns `None` when either is not a number.
+/// Integers are worked out as BIGINTs, with division dropping any remainder, and decimals are kept
+/// exact, so a result too large for either is an error. Anything with a double is a double.
+pub fn evaluate_numeric(left: &Cell, operator: BinaryOperator, right: &Cell) -> Option<Result<Cell>> {
+    let too_large = |type_name: &str| -> crate::Error {
+        format!("The result of {} {} {} is too large for a {}", left, operator, right, type_name).into()
+    };
+    let division_by_zero = || -> crate::Error { format!("Unable to divide {} by zero", left).into() };
+
+    if let (Some(left), Some(right)) = (as_integer(left), as_integer(right)) {
+        let result = match operator {
+            BinaryOperator::Add => left.checked_add(right),
+            BinaryOperator::Subtract => left.checked_sub(right),
+            BinaryOperator::Multiply => left.checked_mul(right),
+            BinaryOperator::Divide if right == 0 => return Some(Err(division_by_zero())),
+            BinaryOperator::Divide => left.checked_div(right),
+            _ => return None,
+        };
+        return Some(result.map(Cell::Int64).ok_or_else(|| too_large("BIGINT")));
+    }
+
+    if let (Some(left), Some(right)) = (as_decimal(left), as_decimal(right)) {
+        let result = match operator {
+            BinaryOperator::Add => left.checked_add(&right),
+            BinaryOperator::Subtract => left.checked_sub(&right),
+            BinaryOperator::Multiply => left.checked_mul(&right),
+            BinaryOperator::Divide if right.value() == 0 => return Some(Err(division_by_zero())),
+            BinaryOperator::Divide => left.checked_div(&right),
+            _ => return None,
+        };
+        return Some(result.map(Cell::Decimal).ok_or_else(|| too_large("DECIMAL")));
+    }
+
+    let (left, right) = (as_double(left)?, as_double(right)?);
+    let result = match operator {
+        BinaryOperator::Add => left + right,
+        BinaryOperator::Subtract => left - right,
+        BinaryOperator::Multiply => left * right,
+        BinaryOperator::Divide if right == 0.0 => return Some(Err(division_by_zero())),
+        BinaryOperator::Divide => left / right,
+        _ => return None,
+    };
+    Some(Ok(Cell::Double(result)))
+}
+
+/// Negates a number or an interval. Integers are negated as BIGINTs, like the other arithmetic, so
+/// only the smallest BIGINT has no negation.
+fn negate(value: Cell) -> Result<Cell> {
+    let out_of_range = |type_name: &str| -> crate::Error { format!("The result of -{} is too large for a {}", value, type_name).into() };
+
+    match &value {
+        Cell::Int32(value) => Ok(Cell::Int64(-i64::from(*value))),
+        Cell::Int64(integer) => integer.checked_neg().map(Cell::Int64).ok_or_else(|| out_of_range("BIGINT")),
+        Cell::Decimal(decimal) => Decimal::from(0).checked_sub(decimal).map(Cell::Decimal).ok_or_else(|| out_of_range("DECIMAL")),
+        Cell::Double(double) => Ok(Cell::Double(-double)),
+        Cell::Interval(interval) => interval.checked_neg().map(Cell::Interval).ok_or_else(|| out_of_range("INTERVAL")),
+        Cell::Null => Ok(Cell::Null),
+        _ => Err(format!("Unable to negate {:?}", value).into()),
+    }
+}
+
+/// Reads an operand of AND or OR, where `None` is NULL.
+fn as_boolean(value: Cell, operator: BinaryOperator) -> Result<Option<bool>> {
+    match value {
+        Cell::Boolean(value) => Ok(Some(value)),
+        Cell::Null => Ok(None),
+        value => Err(format!("Expected a Boolean operand for {:?} but got {:?}", operator, value).into()),
+    }
+}
+
+/// Compares two values. Numbers of any type compare with each other by value, as do dates with
+/// timestamps, while other values only compare with values of their own type. A string compared
+/// with a date or time is read as one, so `at > '2024-01-31'` works without a typed literal.
+pub fn compare(left: &Cell, right: &Cell) -> Result<Ordering> {
+    if let Some(ordering) = compare_values(left, right) {
+        return Ok(ordering);
+    }
+
+    match (left, right) {
+        (value, Cell::String(text)) if is_temporal(value) => compare(left, &parse_like(value, text)?),
+        (Cell::String(text), value) if is_temporal(value) => compare(&parse_like(value, text)?, right),
+        _ => Err(format!("Unable to compare {:?} with {:?}", left, right).into()),
+    }
+}
+
+/// Compares two values which can be compared, or returns `None` when they cannot.
+fn compare_values(left: &Cell, right: &Cell) -> Option<Ordering> {
+    match (left, right) {
+        (Cell::String(left), Cell::String(right)) => Some(left.cmp(right)),
+        (Cell::Boolean(left), Cell::Boolean(right)) => Some(left.cmp(right)),
+        (Cell::Date(left), Cell::Date(right)) => Some(left.cmp(right)),
+        (Cell::Time(left), Cell::Time(right)) => Some(left.cmp(right)),
+        (Cell::Timestamp(left), Cell::Timestamp(right)) => Some(left.cmp(right)),
+        (Cell::Date(left), Cell::Timestamp(right)) => Some(Timestamp::from(*left).cmp(right)),
+        (Cell::Timestamp(left), Cell::Date(right)) => Some(left.cmp(&Timestamp::from(*right))),
+        (Cell::Interval(left), Cell::Interval(right)) => Some(left.approximate_micros().cmp(&right.approximate_micros())),
+        (left, right) => compare_numbers(left, right),
+    }
+}
+
+fn is_temporal(cell: &Cell) -> bool {
+    matches!(cell, Cell::Date(_) | Cell::Time(_) | Cell::Timestamp(_) | Cell::Interval(_))
+}
+
+/// Reads `text` as a value of the same type as `cell`.
+fn parse_like(cell: &Cell, text: &str) -> Result<Cell> {
+    match cell {
+        Cell::Date(_) => Ok(Cell::Date(Date::parse(text)?)),
+        Cell::Time(_) => Ok(Cell::Time(Time::parse(text)?)),
+        Cell::Timestamp(_) => Ok(Cell::Timestamp(Timestamp::parse(text)?)),
+        Cell::Interval(_) => Ok(Cell::Interval(Interval::parse(text)?)),
+        cell => Err(format!("Unable to read {:?} as a {:?}", text, cell).into()),
+    }
+}
+
+/// Compares two numbers, or returns `None` when either is not a number. Integers and decimals are
+/// compared exactly, and only converted to doubles when the other side is a double.
+fn compare_numbers(left: &Cell, right: &Cell) -> Option<Ordering> {
+    if let (Some(left), Some(right)) = (as_integer(left), as_integer(right)) {
+        return Some(left.cmp(&right));
+    }
+
+    if let (Some(left), Some(right)) = (as_decimal(left), as_decimal(right)) {
+        return Some(left.cmp(&right));
+    }
+
+    let (left, right) = (as_double(left)?, as_double(right)?);
+    // NaN is not ordered against anything, so it falls back to the total order which puts it last
+    Some(left.partial_cmp(&right).unwrap_or_else(|| left.total_cmp(&right)))
+}
+
+pub fn as_integer(cell: &Cell) -> Option<i64> {
+    match cell {
+        Cell::Int32(value) => Some((*value).into()),
+        Cell::Int64(value) => Some(*value),
+        _ => None,
+    }
+}
+
+/// Reads an integer or a decimal as a decimal.
+pub fn as_decimal(cell: &Cell) -> Option<Decimal> {
+    match cell {
+        Cell::Decimal(value) => Some(*value),
+        cell => as_integer(cell).map(Decimal::from),
+    }
+}
+
+pub fn as_double(cell: &Cell) -> Option<f64> {
+    match cell {
+        Cell::Int32(value) => Some((*value).into()),
+        Cell::Int64(value) => Some(*value as f64),
+        Cell::Decimal(value) => Some(value.to_f64()),
+        Cell::Double(value) => Some(*value),
+        _ => None,
+    }
+}
+
+/// Compares the ORDER BY keys of two rows. Values of different types are ordered by type, booleans
+/// before numbers before dates and times before strings, so any mix of values sorts the same way
+/// every time.
+pub fn compare_sort_keys(order_by: &[OrderBy], left: &[Cell], right: &[Cell]) -> Ordering {
+    for ((item, left), right) in order_by.iter().zip(left).zip(right) {
+        let nulls = if item.nulls_first() { Ordering::Less } else { Ordering::Greater };
+
+        let ordering = match (left, right) {
+            (Cell::Null, Cell::Null) => Ordering::Equal,
+            (Cell::Null, _) => nulls,
+            (_, Cell::Null) => nulls.reverse(),
+            (left, right) => match item.direction() {
+                SortDirection::Ascending => sort_order(left, right),
+                SortDirection::Descending => sort_order(left, right).reverse(),
+            },
+        };
+
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    Ordering::Equal
+}
+
+fn sort_order(left: &Cell, right: &Cell) -> Ordering {
+    compare_values(left, right).unwrap_or_else(|| type_rank(left).cmp(&type_rank(right)))
+}
+
+fn type_rank(cell: &Cell) -> u8 {
+    match cell {
+        Cell::Null => 0,
+        Cell::Boolean(_) => 1,
+        Cell::Int32(_) | Cell::Int64(_) | Cell::Decimal(_) | Cell::Double(_) => 2,
+        Cell::Date(_) | Cell::Timestamp(_) => 3,
+        Cell::Time(_) => 4,
+        Cell::Interval(_) => 5,
+        Cell::String(_) => 6,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backend::memory::InMemoryBackend;
+    use crate::backend::memory::tests::{database, error, query};
+
+    /// A table holding every pair of TRUE, FALSE and NULL.
+    fn truth() -> InMemoryBackend {
+        database("CREATE TABLE truth (a BOOLEAN, b BOOLEAN, n INT);
+            INSERT INTO truth VALUES
+                (TRUE, TRUE, 1), (TRUE, FALSE, 2), (TRUE, NULL, 3),
+                (FALSE, TRUE, 4), (FALSE, FALSE, 5), (FALSE, NULL, 6),
+                (NULL, TRUE, 7), (NULL, FALSE, NULL), (NULL, NULL, NULL);")
+    }
+
+    #[test]
+    fn and_or_and_not_follow_three_valued_logic() {
+        let mut backend = truth();
+
+        assert_eq!(query(&mut backend, "SELECT a AND b, a OR b, NOT a FROM truth"), vec![
+            "true, true, false",
+            "false, true, false",
+            "NULL, true, false",
+            "false, true, true",
+            "false, false, true",
+            "false, NULL, true",
+            "NULL, true, NULL",
+            "false, NULL, NULL",
+            "NULL, NULL, NULL",
+        ]);
+    }
+
+    #[test]
+    fn comparisons_with_null_are_unknown() {
+        let mut backend = truth();
+
+        assert_eq!(query(&mut backend, "SELECT n = NULL, NULL <> NULL, n + NULL, n IS NULL, n IS NOT NULL FROM truth WHERE n = 1"),
+            vec!["NULL, NULL, NULL, false, true"]);
+        assert_eq!(query(&mut backend, "SELECT n FROM truth WHERE n > 5"), vec!["6", "7"]);
+        // NOT of an unknown condition is still unknown, so rows with a NULL are in neither result
+        assert_eq!(query(&mut backend, "SELECT n FROM truth WHERE NOT n > 5"), vec!["1", "2", "3", "4", "5"]);
+        assert_eq!(query(&mut backend, "SELECT COUNT(*) FROM truth WHERE n = NULL OR NOT n = NULL"), vec!["0"]);
+        assert_eq!(query(&mut backend, "SELECT COUNT(*) FROM truth WHERE n IS NULL"), vec!["2"]);
+        // An unknown OR something true is true, and an unknown AND something false is false
+        assert_eq!(query(&mut backend, "SELECT COUNT(*) FROM truth WHERE n > 5 OR a"), vec!["5"]);
+        assert_eq!(query(&mut backend, "SELECT COUNT(*) FROM truth WHERE NOT (n > 5 AND b)"), vec!["6"]);
+    }
+
+    #[test]
+    fn logic_needs_boolean_operands() {
+        let mut backend = truth();
+
+        assert!(error(&mut backend, "SELECT n AND a FROM truth").starts_with("Expected a Boolean operand for And"));
+        assert!(error(&mut backend, "SELECT NOT n FROM truth WHERE n = 1").starts_with("Expected a Boolean operand for NOT"));
+        assert!(error(&mut backend, "SELECT n FROM truth WHERE n + 1").starts_with("Expected the predicate to evaluate to a Boolean"));
+    }
+}
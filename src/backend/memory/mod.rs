@@ -1,49 +1,351 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::RangeBounds;
 
-use crate::backend::{Backend, Cell, QueryResults};
+use crate::backend::{Backend, Cell, double_bits, QueryResults, ResultColumn};
+use crate::backend::btree::{BPlusTree, RowId};
+use crate::backend::memory::access::Access;
+use crate::backend::memory::relation::{ColumnPosition, Relation, RelationScope};
 use crate::Result;
 use crate::statements::{insert, select};
-use crate::statements::create::{CreateTableStatement, DataType};
-use crate::statements::insert::{InsertStatement, Literal};
+use crate::statements::alter::{AlterTableAction, AlterTableStatement};
+use crate::statements::create::{ColumnConstraint, ColumnDefinition, CreateTableStatement, DataType};
+use crate::statements::delete::DeleteStatement;
+use crate::statements::describe::DescribeStatement;
+use crate::statements::drop::DropTableStatement;
+use crate::statements::expression::{self, Expression};
+use crate::statements::insert::{InsertSource, InsertStatement};
 use crate::statements::select::SelectStatement;
+use crate::statements::truncate::TruncateTableStatement;
+use crate::statements::update::UpdateStatement;
+use crate::types::decimal::Decimal;
+use crate::types::temporal::{Date, Time, Timestamp};
 
-#[derive(Debug, PartialEq, Eq)]
+mod access;
+mod aggregate;
+mod evaluator;
+mod relation;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum ColumnTypes {
     Int32,
+    Int64,
+    /// An exact number with a precision and a scale, as in `DataType::Decimal`.
+    Decimal(u8, u8),
+    Double,
+    Boolean,
     String,
+    Date,
+    Time,
+    Timestamp,
+    /// Only ever the type of an expression's result, as no column holds intervals.
+    Interval,
+}
+
+impl ColumnTypes {
+    pub fn is_integer(&self) -> bool {
+        matches!(self, ColumnTypes::Int32 | ColumnTypes::Int64)
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        self.is_integer() || matches!(self, ColumnTypes::Decimal(..) | ColumnTypes::Double)
+    }
+
+    /// Whether values of type `other` can be written to a column of this type. Integers go into any
+    /// numeric column, decimals into any decimal or double column and decimals without a fraction
+    /// into integer columns too, although a value with too many digits is still an error when it is
+    /// written, and dates go into timestamps as midnight.
+    fn accepts(&self, other: &ColumnTypes) -> bool {
+        self == other
+            || (other.is_integer() && self.is_numeric())
+            || (matches!(other, ColumnTypes::Decimal(..)) && matches!(self, ColumnTypes::Decimal(..) | ColumnTypes::Double))
+            || (matches!(other, ColumnTypes::Decimal(_, 0)) && self.is_integer())
+            || (*other == ColumnTypes::Date && *self == ColumnTypes::Timestamp)
+    }
 }
 
+/// Renders the type as it is written in SQL.
+impl fmt::Display for ColumnTypes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColumnTypes::Int32 => write!(f, "INT"),
+            ColumnTypes::Int64 => write!(f, "BIGINT"),
+            ColumnTypes::Decimal(precision, scale) => write!(f, "DECIMAL({}, {})", precision, scale),
+            ColumnTypes::Double => write!(f, "DOUBLE"),
+            ColumnTypes::Boolean => write!(f, "BOOLEAN"),
+            ColumnTypes::String => write!(f, "TEXT"),
+            ColumnTypes::Date => write!(f, "DATE"),
+            ColumnTypes::Time => write!(f, "TIME"),
+            ColumnTypes::Timestamp => write!(f, "TIMESTAMP"),
+            ColumnTypes::Interval => write!(f, "INTERVAL"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub enum MemoryCell {
-    U32(u32),
+    Int32(i32),
+    Int64(i64),
+    Decimal(Decimal),
+    Double(f64),
+    Boolean(bool),
     String(String),
+    Date(Date),
+    Time(Time),
+    Timestamp(Timestamp),
+    Null,
 }
 
-pub struct Rows {
-    stride: usize,
-    data: Vec<MemoryCell>,
+impl MemoryCell {
+    /// Converts a value to be stored in `column`. Integers are widened to fit the column, while one
+    /// which does not fit is an error rather than being wrapped around. Whole decimals are stored
+    /// in integer columns in the same way. Decimals are rounded to the
+    /// scale of the column, and are an error when that leaves more digits than its precision.
+    /// Strings are read as dates and times when the column holds them, so they can be written
+    /// without a typed literal.
+    fn from_cell(cell: Cell, column: &Column) -> Result<MemoryCell> {
+        let out_of_range = |value: &dyn std::fmt::Display| -> crate::Error {
+            format!("Value {} is out of range for column {:?} of type {}", value, column.name(), column.data_type()).into()
+        };
+        let to_decimal = |value: Decimal, precision: u8, scale: u8| {
+            value.rescale(scale)
+                .filter(|decimal| decimal.fits(precision))
+                .map(MemoryCell::Decimal)
+                .ok_or_else(|| out_of_range(&value))
+        };
+
+        match (cell, *column.column_type()) {
+            (Cell::Int32(value), ColumnTypes::Int32) => Ok(MemoryCell::Int32(value)),
+            (Cell::Int64(value), ColumnTypes::Int32) => i32::try_from(value).map(MemoryCell::Int32).map_err(|_| out_of_range(&value)),
+            (Cell::Int32(value), ColumnTypes::Int64) => Ok(MemoryCell::Int64(value.into())),
+            (Cell::Int64(value), ColumnTypes::Int64) => Ok(MemoryCell::Int64(value)),
+            // Decimals which are whole numbers, like 2.00 or the result of `price * 2`, are stored as
+            // integers when they fit, while any with a fraction are the wrong type rather than rounded
+            (Cell::Decimal(value), ColumnTypes::Int32) if value.to_integer().is_some() => value.to_integer()
+                .and_then(|integer| i32::try_from(integer).ok())
+                .map(MemoryCell::Int32)
+                .ok_or_else(|| out_of_range(&value)),
+            (Cell::Decimal(value), ColumnTypes::Int64) if value.to_integer().is_some() => value.to_integer()
+                .and_then(|integer| i64::try_from(integer).ok())
+                .map(MemoryCell::Int64)
+                .ok_or_else(|| out_of_range(&value)),
+            (Cell::Decimal(value), ColumnTypes::Int32 | ColumnTypes::Int64) => {
+                Err(format!("Expected a whole number for column {:?} of type {} but got {}", column.name(), column.data_type(), value).into())
+            }
+            (Cell::Int32(value), ColumnTypes::Decimal(precision, scale)) => to_decimal(i64::from(value).into(), precision, scale),
+            (Cell::Int64(value), ColumnTypes::Decimal(precision, scale)) => to_decimal(value.into(), precision, scale),
+            (Cell::Decimal(value), ColumnTypes::Decimal(precision, scale)) => to_decimal(value, precision, scale),
+            (Cell::Int32(value), ColumnTypes::Double) => Ok(MemoryCell::Double(value.into())),
+            (Cell::Int64(value), ColumnTypes::Double) => Ok(MemoryCell::Double(value as f64)),
+            (Cell::Decimal(value), ColumnTypes::Double) => Ok(MemoryCell::Double(value.to_f64())),
+            (Cell::Double(value), ColumnTypes::Double) => Ok(MemoryCell::Double(value)),
+            (Cell::Boolean(value), ColumnTypes::Boolean) => Ok(MemoryCell::Boolean(value)),
+            (Cell::String(value), ColumnTypes::String) => Ok(MemoryCell::String(value)),
+            (Cell::Date(value), ColumnTypes::Date) => Ok(MemoryCell::Date(value)),
+            (Cell::Date(value), ColumnTypes::Timestamp) => Ok(MemoryCell::Timestamp(value.into())),
+            (Cell::Time(value), ColumnTypes::Time) => Ok(MemoryCell::Time(value)),
+            (Cell::Timestamp(value), ColumnTypes::Timestamp) => Ok(MemoryCell::Timestamp(value)),
+            (Cell::String(value), ColumnTypes::Date) => Ok(MemoryCell::Date(Date::parse(&value)?)),
+            (Cell::String(value), ColumnTypes::Time) => Ok(MemoryCell::Time(Time::parse(&value)?)),
+            (Cell::String(value), ColumnTypes::Timestamp) => Ok(MemoryCell::Timestamp(Timestamp::parse(&value)?)),
+            (Cell::Null, _) => Ok(MemoryCell::Null),
+            (cell, column_type) => Err(format!("Expected {} for column {:?} but got {:?}", column_type, column.name(), cell).into()),
+        }
+    }
+}
+
+impl PartialEq for MemoryCell {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (MemoryCell::Int32(left), MemoryCell::Int32(right)) => left == right,
+            (MemoryCell::Int64(left), MemoryCell::Int64(right)) => left == right,
+            (MemoryCell::Decimal(left), MemoryCell::Decimal(right)) => left == right,
+            (MemoryCell::Double(left), MemoryCell::Double(right)) => double_bits(*left) == double_bits(*right),
+            (MemoryCell::Boolean(left), MemoryCell::Boolean(right)) => left == right,
+            (MemoryCell::String(left), MemoryCell::String(right)) => left == right,
+            (MemoryCell::Date(left), MemoryCell::Date(right)) => left == right,
+            (MemoryCell::Time(left), MemoryCell::Time(right)) => left == right,
+            (MemoryCell::Timestamp(left), MemoryCell::Timestamp(right)) => left == right,
+            (MemoryCell::Null, MemoryCell::Null) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for MemoryCell {}
+
+impl Hash for MemoryCell {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            MemoryCell::Int32(value) => value.hash(state),
+            MemoryCell::Int64(value) => value.hash(state),
+            MemoryCell::Decimal(value) => value.hash(state),
+            MemoryCell::Double(value) => double_bits(*value).hash(state),
+            MemoryCell::Boolean(value) => value.hash(state),
+            MemoryCell::String(value) => value.hash(state),
+            MemoryCell::Date(value) => value.hash(state),
+            MemoryCell::Time(value) => value.hash(state),
+            MemoryCell::Timestamp(value) => value.hash(state),
+            MemoryCell::Null => {}
+        }
+    }
 }
 
-impl Extend<MemoryCell> for Rows {
-    fn extend<T: IntoIterator<Item=MemoryCell>>(&mut self, iter: T) {
-        self.data.extend(iter)
+impl From<&MemoryCell> for Cell {
+    fn from(memory_cell: &MemoryCell) -> Self {
+        match memory_cell {
+            MemoryCell::Int32(value) => Cell::Int32(*value),
+            MemoryCell::Int64(value) => Cell::Int64(*value),
+            MemoryCell::Decimal(value) => Cell::Decimal(*value),
+            MemoryCell::Double(value) => Cell::Double(*value),
+            MemoryCell::Boolean(value) => Cell::Boolean(*value),
+            MemoryCell::Date(value) => Cell::Date(*value),
+            MemoryCell::Time(value) => Cell::Time(*value),
+            MemoryCell::Timestamp(value) => Cell::Timestamp(*value),
+            MemoryCell::String(value) => Cell::String(value.to_string()),
+            MemoryCell::Null => Cell::Null,
+        }
     }
 }
 
+pub type RowTree = BPlusTree<Vec<MemoryCell>>;
+
+/// The rows of a table, each `stride` cells wide, stored in a B+tree keyed by a row id. The row id
+/// is the value of the table's integer PRIMARY KEY when it has one, and otherwise an internal id
+/// given out in the order the rows are inserted.
+pub struct Rows {
+    stride: usize,
+    tree: RowTree,
+    next_row_id: RowId,
+}
+
 impl Rows {
-    pub fn new(stride: usize, data: Vec<MemoryCell>) -> Self {
-        Rows { stride, data }
+    pub fn new(stride: usize) -> Self {
+        Rows::from_tree(stride, BPlusTree::new())
+    }
+
+    pub fn from_tree(stride: usize, tree: RowTree) -> Self {
+        let next_row_id = tree.last_key().map_or(0, |row_id| row_id.saturating_add(1));
+        Rows { stride, tree, next_row_id }
+    }
+
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=&[MemoryCell]> {
+        self.tree.iter().map(|(_, row)| row.as_slice())
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item=(RowId, &[MemoryCell])> {
+        self.tree.iter().map(|(row_id, row)| (row_id, row.as_slice()))
+    }
+
+    pub fn range<R: RangeBounds<RowId>>(&self, range: R) -> impl Iterator<Item=(RowId, &[MemoryCell])> {
+        self.tree.range(range).map(|(row_id, row)| (row_id, row.as_slice()))
+    }
+
+    pub fn get(&self, row_id: RowId) -> Option<&[MemoryCell]> {
+        self.tree.get(row_id).map(|row| row.as_slice())
+    }
+
+    pub fn get_mut(&mut self, row_id: RowId) -> Option<&mut [MemoryCell]> {
+        self.tree.get_mut(row_id).map(|row| row.as_mut_slice())
+    }
+
+    pub fn push(&mut self, row: Vec<MemoryCell>) -> RowId {
+        debug_assert_eq!(row.len(), self.stride);
+
+        let row_id = self.next_row_id;
+        self.next_row_id += 1;
+        self.tree.insert(row_id, row);
+        row_id
+    }
+
+    /// Adds a row under a row id of its own, which must not be taken yet.
+    pub fn insert(&mut self, row_id: RowId, row: Vec<MemoryCell>) {
+        debug_assert_eq!(row.len(), self.stride);
+
+        let replaced = self.tree.insert(row_id, row);
+        debug_assert!(replaced.is_none(), "row id {} is already taken", row_id);
+        self.next_row_id = self.next_row_id.max(row_id.saturating_add(1));
+    }
+
+    pub fn remove(&mut self, row_id: RowId) -> Option<Vec<MemoryCell>> {
+        self.tree.remove(row_id)
+    }
+
+    pub fn clear(&mut self) {
+        self.tree.clear();
+        self.next_row_id = 0;
+    }
+
+    /// Changes the number of cells in each row, letting `f` rewrite every row to be `stride` cells wide.
+    pub fn restride<F: FnMut(&mut Vec<MemoryCell>)>(&mut self, stride: usize, mut f: F) {
+        self.tree.update_values(|row| {
+            f(row);
+            debug_assert_eq!(row.len(), stride);
+        });
+        self.stride = stride;
+    }
+
+    pub fn tree(&self) -> &RowTree {
+        &self.tree
+    }
+
+    pub fn tree_mut(&mut self) -> &mut RowTree {
+        &mut self.tree
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq)]
 pub struct Column {
     name: String,
     _type: ColumnTypes,
+    constraints: Vec<ColumnConstraint>,
 }
 
 impl Column {
-    pub fn new(name: String, _type: ColumnTypes) -> Self {
-        Column { name, _type }
+    pub fn new(name: String, _type: ColumnTypes, constraints: Vec<ColumnConstraint>) -> Self {
+        Column { name, _type, constraints }
+    }
+
+    /// Builds a column from its definition in a statement, checking its DEFAULT fits the column.
+    fn from_definition(definition: &ColumnDefinition) -> Result<Self> {
+        let column = Column::new(
+            definition.name().to_owned(),
+            match definition.data_type() {
+                DataType::Int32 => ColumnTypes::Int32,
+                DataType::Int64 => ColumnTypes::Int64,
+                DataType::Decimal(precision, scale) => ColumnTypes::Decimal(*precision, *scale),
+                DataType::Double => ColumnTypes::Double,
+                DataType::Boolean => ColumnTypes::Boolean,
+                DataType::String => ColumnTypes::String,
+                DataType::Date => ColumnTypes::Date,
+                DataType::Time => ColumnTypes::Time,
+                DataType::Timestamp => ColumnTypes::Timestamp,
+            },
+            definition.constraints().to_vec());
+
+        if column.default_value().is_some() {
+            let default = MemoryCell::from_cell(column.default_cell(), &column)?;
+            if default == MemoryCell::Null && !column.is_nullable() {
+                return Err(format!("Column {:?} cannot default to NULL because it is NOT NULL", column.name()).into());
+            }
+        }
+
+        Ok(column)
     }
 
     pub fn name(&self) -> &str {
@@ -53,14 +355,89 @@ impl Column {
     pub fn column_type(&self) -> &ColumnTypes {
         &self._type
     }
+
+    /// The column's type as it is written in SQL.
+    pub fn data_type(&self) -> DataType {
+        match self._type {
+            ColumnTypes::Int32 => DataType::Int32,
+            ColumnTypes::Int64 => DataType::Int64,
+            ColumnTypes::Decimal(precision, scale) => DataType::Decimal(precision, scale),
+            ColumnTypes::Double => DataType::Double,
+            ColumnTypes::Boolean => DataType::Boolean,
+            ColumnTypes::String => DataType::String,
+            ColumnTypes::Date => DataType::Date,
+            ColumnTypes::Time => DataType::Time,
+            ColumnTypes::Timestamp => DataType::Timestamp,
+            ColumnTypes::Interval => unreachable!("no column holds intervals"),
+        }
+    }
+
+    pub fn constraints(&self) -> &[ColumnConstraint] {
+        self.constraints.as_ref()
+    }
+
+    pub fn is_primary_key(&self) -> bool {
+        self.constraints.contains(&ColumnConstraint::PrimaryKey)
+    }
+
+    /// A primary key can never be NULL, even without an explicit NOT NULL.
+    pub fn is_nullable(&self) -> bool {
+        !self.is_primary_key() && !self.constraints.contains(&ColumnConstraint::NotNull)
+    }
+
+    pub fn is_unique(&self) -> bool {
+        self.is_primary_key() || self.constraints.contains(&ColumnConstraint::Unique)
+    }
+
+    pub fn default_value(&self) -> Option<&expression::Literal> {
+        self.constraints.iter().find_map(|constraint| match constraint {
+            ColumnConstraint::Default(value) => Some(value),
+            _ => None,
+        })
+    }
+
+    /// The value used when a row leaves the column out, which is NULL unless the column says otherwise.
+    pub fn default_cell(&self) -> Cell {
+        self.default_value().map_or(Cell::Null, Cell::from)
+    }
+}
+
+/// Finds the row holding a value of a PRIMARY KEY or UNIQUE column without reading the whole
+/// table. NULLs are left out, as they never clash with each other.
+struct UniqueIndex {
+    column: usize,
+    rows: HashMap<MemoryCell, RowId>,
+}
+
+impl UniqueIndex {
+    fn add(&mut self, row_id: RowId, row: &[MemoryCell]) {
+        if row[self.column] != MemoryCell::Null {
+            self.rows.insert(row[self.column].clone(), row_id);
+        }
+    }
+
+    /// Forgets the value of a row, unless another row has taken it over since, as happens when an
+    /// UPDATE swaps the values of two rows.
+    fn remove(&mut self, row_id: RowId, row: &[MemoryCell]) {
+        if self.rows.get(&row[self.column]) == Some(&row_id) {
+            self.rows.remove(&row[self.column]);
+        }
+    }
 }
 
 pub struct Table {
     columns: Vec<Column>,
     rows: Rows,
+    indexes: Vec<UniqueIndex>,
 }
 
 impl Table {
+    pub fn new(columns: Vec<Column>, rows: Rows) -> Self {
+        let mut table = Table { columns, rows, indexes: Vec::new() };
+        table.reindex();
+        table
+    }
+
     pub fn columns(&self) -> &[Column] {
         self.columns.as_ref()
     }
@@ -68,6 +445,170 @@ impl Table {
     pub fn rows(&self) -> &Rows {
         &self.rows
     }
+
+    /// Gives access to the storage of the rows, which must not be used to change them as that would
+    /// leave the unique indexes behind.
+    pub fn rows_mut(&mut self) -> &mut Rows {
+        &mut self.rows
+    }
+
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        self.columns.iter().position(|column| column.name() == name)
+    }
+
+    /// Finds the row where the PRIMARY KEY or UNIQUE column at `column` holds `value`.
+    pub fn find_unique(&self, column: usize, value: &MemoryCell) -> Option<RowId> {
+        self.indexes.iter()
+            .find(|index| index.column == column)
+            .and_then(|index| index.rows.get(value).copied())
+    }
+
+    /// The integer PRIMARY KEY column, whose values are used as the row ids.
+    pub fn key_column(&self) -> Option<usize> {
+        self.columns.iter().position(|column| column.is_primary_key() && column.column_type().is_integer())
+    }
+
+    /// The row id a row is stored under when the table is keyed by its PRIMARY KEY.
+    fn row_key(&self, row: &[MemoryCell]) -> Option<RowId> {
+        match row[self.key_column()?] {
+            MemoryCell::Int32(value) => Some(access::row_key(value.into())),
+            MemoryCell::Int64(value) => Some(access::row_key(value)),
+            _ => None,
+        }
+    }
+
+    /// The rows `access` says a WHERE clause can match, along with their row ids.
+    fn scan(&self, access: &Access) -> Box<dyn Iterator<Item=(RowId, &[MemoryCell])> + '_> {
+        match access {
+            Access::Scan => Box::new(self.rows.entries()),
+            Access::Lookup(row_id) => Box::new(row_id.and_then(|row_id| self.rows.get(row_id).map(|row| (row_id, row))).into_iter()),
+            Access::Range(start, end) => Box::new(self.rows.range((*start, *end))),
+        }
+    }
+
+    /// Stores every row again under the row id it should have, for when the PRIMARY KEY the rows are
+    /// keyed by has been added or dropped.
+    fn rekey(&mut self) {
+        let rows = self.rows.iter().map(<[MemoryCell]>::to_vec).collect::<Vec<Vec<MemoryCell>>>();
+        self.rows.clear();
+        for row in rows {
+            match self.row_key(&row) {
+                Some(row_id) => self.rows.insert(row_id, row),
+                None => { self.rows.push(row); }
+            }
+        }
+        self.reindex();
+    }
+
+    /// Rebuilds the unique indexes from the rows, for when the columns have changed.
+    fn reindex(&mut self) {
+        self.indexes = self.columns.iter().enumerate()
+            .filter(|(_, column)| column.is_unique())
+            .map(|(column, _)| UniqueIndex { column, rows: HashMap::new() })
+            .collect();
+
+        for (row_id, row) in self.rows.entries() {
+            for index in self.indexes.iter_mut() {
+                index.add(row_id, row);
+            }
+        }
+    }
+
+    fn insert_row(&mut self, row: Vec<MemoryCell>) -> RowId {
+        let row_id = match self.row_key(&row) {
+            Some(row_id) => {
+                self.rows.insert(row_id, row);
+                row_id
+            }
+            None => self.rows.push(row),
+        };
+        if let Some(row) = self.rows.get(row_id) {
+            for index in self.indexes.iter_mut() {
+                index.add(row_id, row);
+            }
+        }
+        row_id
+    }
+
+    /// Overwrites rows with their updated values. A row whose PRIMARY KEY has changed moves to its
+    /// new row id, once every row that is moving has left so they cannot collide.
+    fn replace_rows(&mut self, updates: Vec<(RowId, Vec<MemoryCell>)>) {
+        let mut moved = Vec::new();
+
+        for (row_id, updated_row) in updates {
+            match self.row_key(&updated_row) {
+                Some(key) if key != row_id => {
+                    self.remove_row(row_id);
+                    moved.push(updated_row);
+                }
+                _ => self.replace_row(row_id, &updated_row),
+            }
+        }
+
+        for row in moved {
+            self.insert_row(row);
+        }
+    }
+
+    fn replace_row(&mut self, row_id: RowId, updated_row: &[MemoryCell]) {
+        if let Some(row) = self.rows.get_mut(row_id) {
+            for index in self.indexes.iter_mut() {
+                index.remove(row_id, row);
+                index.add(row_id, updated_row);
+            }
+            row.clone_from_slice(updated_row);
+        }
+    }
+
+    fn remove_row(&mut self, row_id: RowId) {
+        if let Some(row) = self.rows.remove(row_id) {
+            for index in self.indexes.iter_mut() {
+                index.remove(row_id, &row);
+            }
+        }
+    }
+
+    fn clear_rows(&mut self) {
+        self.rows.clear();
+        for index in self.indexes.iter_mut() {
+            index.rows.clear();
+        }
+    }
+
+    /// Checks rows which are about to be written against the column constraints. The rows with ids
+    /// in `replacing` are the ones being overwritten, so their current values do not count as
+    /// duplicates.
+    fn check_constraints(&self, table_name: &str, rows: &[Vec<MemoryCell>], replacing: &HashSet<RowId>) -> Result<()> {
+        for (index, column) in self.columns.iter().enumerate() {
+            if !column.is_nullable() && rows.iter().any(|row| row[index] == MemoryCell::Null) {
+                return Err(format!("NOT NULL constraint failed: column {:?} of table {:?} cannot be NULL", column.name(), table_name).into());
+            }
+        }
+
+        for unique in &self.indexes {
+            let column = &self.columns[unique.column];
+
+            // The values of the rows being written, which must not clash with each other either
+            let mut seen = HashSet::new();
+
+            for row in rows {
+                let value = &row[unique.column];
+
+                // Like NULL = NULL, two NULLs are never equal so they do not break uniqueness
+                if *value == MemoryCell::Null {
+                    continue;
+                }
+
+                let stored = unique.rows.get(value).is_some_and(|row_id| !replacing.contains(row_id));
+                if stored || !seen.insert(value) {
+                    let constraint = if column.is_primary_key() { "PRIMARY KEY" } else { "UNIQUE" };
+                    return Err(format!("{} constraint failed: table {:?} already has a row where {:?} is {}", constraint, table_name, column.name(), Cell::from(value)).into());
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 pub struct InMemoryBackend {
@@ -78,125 +619,1007 @@ impl InMemoryBackend {
     pub fn new(tables: HashMap<String, Table>) -> Self {
         InMemoryBackend { tables }
     }
+
+    pub fn table(&self, name: &str) -> Option<&Table> {
+        self.tables.get(name)
+    }
+
+    pub fn table_mut(&mut self, name: &str) -> Option<&mut Table> {
+        self.tables.get_mut(name)
+    }
+
+    pub fn tables(&self) -> impl Iterator<Item=(&String, &Table)> {
+        self.tables.iter()
+    }
+}
+
+/// How a SELECT works out one of the values it returns for a row.
+enum Projected<'a> {
+    Column(ColumnPosition),
+    Value(&'a Expression),
+}
+
+/// Sorts rows by their ORDER BY keys and applies the LIMIT and OFFSET.
+fn sort_and_limit(stmt: &SelectStatement, mut keyed_results: Vec<(Vec<Cell>, Vec<Cell>)>) -> Vec<Vec<Cell>> {
+    // A stable sort, so rows with equal keys stay in the order they were stored
+    if !stmt.order_by().is_empty() {
+        keyed_results.sort_by(|(left, _), (right, _)| evaluator::compare_sort_keys(stmt.order_by(), left, right));
+    }
+
+    keyed_results.into_iter()
+        .skip(stmt.offset())
+        .take(stmt.limit().unwrap_or(usize::MAX))
+        .map(|(_, row)| row)
+        .collect::<Vec<Vec<Cell>>>()
 }
 
 impl Backend for InMemoryBackend {
-    fn create_table(&mut self, stmt: &CreateTableStatement) -> Result<()> {
+    fn create_table(&mut self, stmt: &CreateTableStatement) -> Result<bool> {
         if self.tables.contains_key(stmt.table_name()) {
+            if stmt.if_not_exists() {
+                return Ok(false);
+            }
             return Err(format!("Table {:#?} already exists", stmt.table_name()).into());
         }
 
         let columns = stmt.columns();
 
-        let metadata = columns.iter().map(|column| {
-            Column::new(
-                column.name().to_owned(),
-                match column.data_type() {
-                    DataType::Int32 => ColumnTypes::Int32,
-                    DataType::String => ColumnTypes::String,
-                })
-        }).collect::<Vec<Column>>();
-
-        let table = Table {
-            columns: metadata,
-            rows: Rows::new(
-                columns.len(),
-                Vec::new(),
-            ),
-        };
+        let metadata = columns.iter()
+            .map(Column::from_definition)
+            .collect::<Result<Vec<Column>>>()?;
+
+        if metadata.iter().filter(|column| column.is_primary_key()).count() > 1 {
+            return Err(format!("Table {:?} can only have one PRIMARY KEY column", stmt.table_name()).into());
+        }
+
+        let table = Table::new(
+            metadata,
+            Rows::new(columns.len()),
+        );
 
         self.tables.insert(stmt.table_name().to_owned(), table);
 
-        Ok(())
+        Ok(true)
     }
 
-    fn insert(&mut self, stmt: &InsertStatement) -> Result<()> {
-        return match self.tables.get_mut(stmt.table_name()) {
+    fn insert(&mut self, stmt: &InsertStatement) -> Result<usize> {
+        // Run the query up front as it may read from the very table being inserted into
+        let selected = match stmt.source() {
+            InsertSource::Values(_) => None,
+            InsertSource::Select(select) => Some(self.select(select)?),
+        };
+
+        match self.tables.get_mut(stmt.table_name()) {
             None => Err(format!("Table {:#?} not found", stmt.table_name()).into()),
             Some(table) => {
-                let values = stmt.values();
-                if values.len() != table.columns().len() {
-                    return Err(format!("Incorrect number of column. Expected {:?} but found {:?}", table.columns.len(), values.len()).into());
-                }
-
-                let mut row: Vec<MemoryCell> = Vec::with_capacity(values.len());
-
-                for (expression, column_type) in stmt.values().iter().zip(table.columns()) {
-                    match expression {
-                        insert::Expression::Literal(literal) => {
-                            match literal {
-                                Literal::U32(value) => {
-                                    if *column_type.column_type() != ColumnTypes::Int32 {
-                                        return Err(format!("Expected Int32 but got {:?}", column_type).into());
-                                    }
-                                    row.push(MemoryCell::U32(*value))
+                // Where each value of a row goes, which is every column in order unless a list was given
+                let indexes = match stmt.columns() {
+                    None => (0..table.columns.len()).collect::<Vec<usize>>(),
+                    Some(names) => {
+                        let mut indexes = Vec::with_capacity(names.len());
+                        for name in names {
+                            match table.column_index(name) {
+                                Some(index) if indexes.contains(&index) => {
+                                    return Err(format!("Column {:?} is listed more than once", name).into());
                                 }
-                                Literal::String(value) => {
-                                    if *column_type.column_type() != ColumnTypes::String {
-                                        return Err(format!("Expected String but got {:?}", column_type).into());
-                                    }
-                                    row.push(MemoryCell::String(value.to_string()))
+                                Some(index) => indexes.push(index),
+                                None => {
+                                    return Err(format!("Column {:?} is not found in the Table {:?}", name, stmt.table_name()).into());
                                 }
                             }
                         }
+                        indexes
+                    }
+                };
+
+                let values = match (stmt.source(), selected) {
+                    (InsertSource::Values(rows), _) => {
+                        let mut values = Vec::with_capacity(rows.len());
+                        for row in rows {
+                            if row.len() != indexes.len() {
+                                return Err(format!("Incorrect number of column. Expected {:?} but found {:?}", indexes.len(), row.len()).into());
+                            }
+
+                            values.push(row.iter().zip(&indexes).map(|(expression, index)| match expression {
+                                insert::Expression::Literal(literal) => Cell::from(literal),
+                                insert::Expression::Default => table.columns[*index].default_cell(),
+                            }).collect::<Vec<Cell>>());
+                        }
+                        values
+                    }
+                    (InsertSource::Select(_), Some(results)) => {
+                        if results.columns().len() != indexes.len() {
+                            return Err(format!("Incorrect number of column. Expected {:?} but the SELECT returns {:?}", indexes.len(), results.columns().len()).into());
+                        }
+
+                        for (result_column, index) in results.columns().iter().zip(&indexes) {
+                            let column = &table.columns[*index];
+                            if !column.column_type().accepts(result_column.column_type()) {
+                                return Err(format!("Expected {} for column {:?} but the SELECT returns {} for {:?}", column.column_type(), column.name(), result_column.column_type(), result_column.name()).into());
+                            }
+                        }
+
+                        results.into_cells()
+                    }
+                    (InsertSource::Select(_), None) => unreachable!("the SELECT runs before the table is borrowed"),
+                };
+
+                let mut rows = Vec::with_capacity(values.len());
+
+                for row in values {
+                    let mut cells = table.columns().iter().map(Column::default_cell).collect::<Vec<Cell>>();
+
+                    for (cell, index) in row.into_iter().zip(&indexes) {
+                        cells[*index] = cell;
                     }
+
+                    let row = cells.into_iter().zip(table.columns())
+                        .map(|(cell, column)| MemoryCell::from_cell(cell, column))
+                        .collect::<Result<Vec<MemoryCell>>>()?;
+                    rows.push(row);
                 }
 
-                table.rows.extend(row);
+                // Every row is checked before any is added so the statement inserts all of them or none
+                table.check_constraints(stmt.table_name(), &rows, &HashSet::new())?;
+
+                let inserted = rows.len();
+                for row in rows {
+                    table.insert_row(row);
+                }
 
-                Ok(())
+                Ok(inserted)
             }
-        };
+        }
     }
 
     fn select(&mut self, stmt: &SelectStatement) -> Result<QueryResults> {
-        return match self.tables.get_mut(stmt.table_name()) {
+        let relation = Relation::new(&self.tables, stmt.from())?;
+
+        if stmt.is_grouped() {
+            return aggregate::select_grouped(&relation, stmt);
+        }
+
+        // Columns are read straight from the row, while any other value is worked out for each row
+        let mut projection = Vec::new();
+        let mut columns = Vec::new();
+
+        for item in stmt.expression() {
+            match item {
+                select::Expression::Value(Expression::Column(reference), alias) => {
+                    let position = relation.resolve(reference)?;
+                    columns.push(relation.value_column(&Expression::Column(reference.clone()), alias.as_deref())?);
+                    projection.push(Projected::Column(position));
+                }
+                select::Expression::Value(expression, alias) => {
+                    columns.push(relation.value_column(expression, alias.as_deref())?);
+                    projection.push(Projected::Value(expression));
+                }
+                select::Expression::All | select::Expression::AllOf(_) => {
+                    let positions = match item {
+                        select::Expression::AllOf(table) => relation.columns_of(table)?,
+                        _ => relation.all_columns(),
+                    };
+                    for position in positions {
+                        columns.push(relation.result_column(position));
+                        projection.push(Projected::Column(position));
+                    }
+                }
+            }
+        }
+
+        // Rows are paired with their ORDER BY keys, which may use columns that are not selected
+        let mut keyed_results = Vec::new();
+
+        let order_by = stmt.order_by();
+        let needed = stmt.limit().map(|limit| limit.saturating_add(stmt.offset()));
+
+        for row in relation.rows(stmt.predicate())? {
+            // Without ORDER BY the first rows found are the ones returned, so stop once there are enough
+            if order_by.is_empty() && Some(keyed_results.len()) == needed {
+                break;
+            }
+
+            let scope = RelationScope::new(&relation, &row);
+
+            if let Some(predicate) = stmt.predicate() {
+                if !evaluator::evaluate_predicate_in(predicate, &scope)? {
+                    continue;
+                }
+            }
+
+            let keys = order_by.iter()
+                .map(|item| evaluator::evaluate_in(item.expression(), &scope))
+                .collect::<Result<Vec<Cell>>>()?;
+
+            let row = projection.iter()
+                .map(|projected| match projected {
+                    Projected::Column(position) => relation.value(&row, *position),
+                    Projected::Value(expression) => evaluator::evaluate_in(expression, &scope),
+                })
+                .collect::<Result<Vec<Cell>>>()?;
+            keyed_results.push((keys, row));
+
+            // Sorting needs every row, but only the best rows seen so far can make it into the
+            // results, so the rest are dropped now and then to keep memory bounded
+            if let Some(needed) = needed {
+                if !order_by.is_empty() && keyed_results.len() >= needed.saturating_mul(2).max(1024) {
+                    keyed_results.sort_by(|(left, _), (right, _)| evaluator::compare_sort_keys(order_by, left, right));
+                    keyed_results.truncate(needed);
+                }
+            }
+        }
+
+        let results = sort_and_limit(stmt, keyed_results);
+
+        Ok(QueryResults::new(columns, results))
+    }
+
+    fn update(&mut self, stmt: &UpdateStatement) -> Result<usize> {
+        match self.tables.get_mut(stmt.table_name()) {
             None => Err(format!("Table {:#?} not found", stmt.table_name()).into()),
             Some(table) => {
-                let mut results = Vec::new();
-                let mut indexes = Vec::new();
-
-                for expression in stmt.expression() {
-                    let x = match expression {
-                        select::Expression::Column(column_name) => {
-                            let maybe_column = table.columns.iter().enumerate().find(|(index, column)| column.name() == column_name);
-                            match maybe_column {
-                                Some((index, column)) => {
-                                    indexes.push(index)
-                                }
-                                None => {
-                                    return Err(format!("Column {:?} is not found in the Table {:?}", column_name, stmt.table_name()).into());
-                                }
-                            }
+                let mut assignments = Vec::with_capacity(stmt.assignments().len());
+
+                for assignment in stmt.assignments() {
+                    match table.column_index(assignment.column()) {
+                        Some(index) => assignments.push((index, assignment.value())),
+                        None => {
+                            return Err(format!("Column {:?} is not found in the Table {:?}", assignment.column(), stmt.table_name()).into());
                         }
-                        select::Expression::All => {
-                            indexes.extend(
-                                table.columns.iter().enumerate().map(|(index, column)| index)
-                            );
+                    }
+                }
+
+                let mut row_ids = Vec::new();
+                let mut updated_rows = Vec::new();
+
+                let access = Access::plan(table, stmt.predicate(), |reference| {
+                    reference.table().is_none_or(|name| name == stmt.table_name())
+                        .then(|| table.column_index(reference.name()))
+                        .flatten()
+                });
+
+                for (row_id, row) in table.scan(&access) {
+                    if let Some(predicate) = stmt.predicate() {
+                        if !evaluator::evaluate_predicate(predicate, stmt.table_name(), table.columns(), row)? {
+                            continue;
                         }
+                    }
+
+                    let mut updated_row = row.to_vec();
+                    for (index, expression) in &assignments {
+                        let cell = evaluator::evaluate(expression, stmt.table_name(), table.columns(), row)?;
+                        updated_row[*index] = MemoryCell::from_cell(cell, &table.columns[*index])?;
+                    }
+                    row_ids.push(row_id);
+                    updated_rows.push(updated_row);
+                }
+
+                // Only apply once every row has been checked so a failure leaves the table untouched
+                let replacing = row_ids.iter().copied().collect::<HashSet<RowId>>();
+                table.check_constraints(stmt.table_name(), &updated_rows, &replacing)?;
+
+                let updated = row_ids.len();
+                table.replace_rows(row_ids.into_iter().zip(updated_rows).collect());
+
+                Ok(updated)
+            }
+        }
+    }
+
+    fn delete(&mut self, stmt: &DeleteStatement) -> Result<usize> {
+        match self.tables.get_mut(stmt.table_name()) {
+            None => Err(format!("Table {:#?} not found", stmt.table_name()).into()),
+            Some(table) => {
+                let mut matching = Vec::new();
+
+                let access = Access::plan(table, stmt.predicate(), |reference| {
+                    reference.table().is_none_or(|name| name == stmt.table_name())
+                        .then(|| table.column_index(reference.name()))
+                        .flatten()
+                });
+
+                for (row_id, row) in table.scan(&access) {
+                    let matches = match stmt.predicate() {
+                        Some(predicate) => evaluator::evaluate_predicate(predicate, stmt.table_name(), table.columns(), row)?,
+                        None => true,
                     };
-                };
+                    if matches {
+                        matching.push(row_id);
+                    }
+                }
 
-                let rows = table.rows();
-                let data = &table.rows.data;
+                for row_id in &matching {
+                    table.remove_row(*row_id);
+                }
 
-                for row in (0..rows.data.len()).step_by(rows.stride) {
-                    let row = indexes.iter()
-                        .map(|index| &data[index + row])
-                        .map(|memory_cell| {
-                            match memory_cell {
-                                MemoryCell::U32(value) => Cell::U32(*value),
-                                MemoryCell::String(value) => Cell::String(value.to_string()),
-                            }
-                        })
-                        .collect::<Vec<Cell>>();
-                    results.push(row)
+                let affected = matching.len();
+
+                Ok(affected)
+            }
+        }
+    }
+
+    fn drop_table(&mut self, stmt: &DropTableStatement) -> Result<bool> {
+        match self.tables.remove(stmt.table_name()) {
+            Some(_) => Ok(true),
+            None if stmt.if_exists() => Ok(false),
+            None => Err(format!("Table {:#?} not found", stmt.table_name()).into()),
+        }
+    }
+
+    fn truncate(&mut self, stmt: &TruncateTableStatement) -> Result<usize> {
+        match self.tables.get_mut(stmt.table_name()) {
+            None => Err(format!("Table {:#?} not found", stmt.table_name()).into()),
+            Some(table) => {
+                let removed = table.rows.len();
+                table.clear_rows();
+                Ok(removed)
+            }
+        }
+    }
+
+    fn alter_table(&mut self, stmt: &AlterTableStatement) -> Result<()> {
+        if let AlterTableAction::RenameTable(name) = stmt.action() {
+            if self.tables.contains_key(name) {
+                return Err(format!("Table {:#?} already exists", name).into());
+            }
+
+            return match self.tables.remove(stmt.table_name()) {
+                Some(table) => {
+                    self.tables.insert(name.to_owned(), table);
+                    Ok(())
                 }
+                None => Err(format!("Table {:#?} not found", stmt.table_name()).into()),
+            };
+        }
+
+        let table = match self.tables.get_mut(stmt.table_name()) {
+            Some(table) => table,
+            None => return Err(format!("Table {:#?} not found", stmt.table_name()).into()),
+        };
+
+        let column_index = |table: &Table, name: &str| table.column_index(name)
+            .ok_or_else(|| format!("Column {:?} is not found in the Table {:?}", name, stmt.table_name()));
 
-                let query_results = QueryResults::new(results);
+        match stmt.action() {
+            AlterTableAction::AddColumn(definition) => {
+                let column = Column::from_definition(definition)?;
 
-                return Ok(query_results);
+                if table.column_index(column.name()).is_some() {
+                    return Err(format!("Column {:?} already exists in the Table {:?}", column.name(), stmt.table_name()).into());
+                }
+                if column.is_primary_key() && table.columns.iter().any(Column::is_primary_key) {
+                    return Err(format!("Table {:?} can only have one PRIMARY KEY column", stmt.table_name()).into());
+                }
+
+                // Every existing row takes the default, which has to satisfy the column's constraints
+                let default = MemoryCell::from_cell(column.default_cell(), &column)?;
+                if !table.rows.is_empty() && default == MemoryCell::Null && !column.is_nullable() {
+                    return Err(format!("Column {:?} needs a DEFAULT to be added as NOT NULL to a table with rows", column.name()).into());
+                }
+                if table.rows.len() > 1 && default != MemoryCell::Null && column.is_unique() {
+                    return Err(format!("Column {:?} cannot be unique as every existing row would have the same DEFAULT", column.name()).into());
+                }
+
+                let rekey = column.is_primary_key() && column.column_type().is_integer();
+
+                table.columns.push(column);
+                table.rows.restride(table.columns.len(), |row| row.push(default.clone()));
+                if rekey {
+                    table.rekey();
+                } else {
+                    table.reindex();
+                }
             }
+            AlterTableAction::DropColumn(name) => {
+                let index = column_index(table, name)?;
+
+                if table.columns.len() == 1 {
+                    return Err(format!("Column {:?} cannot be dropped as it is the only column of the Table {:?}", name, stmt.table_name()).into());
+                }
+
+                let rekey = table.key_column() == Some(index);
+
+                table.columns.remove(index);
+                table.rows.restride(table.columns.len(), |row| {
+                    row.remove(index);
+                });
+                if rekey {
+                    table.rekey();
+                } else {
+                    table.reindex();
+                }
+            }
+            AlterTableAction::RenameColumn(name, new_name) => {
+                let index = column_index(table, name)?;
+
+                if table.column_index(new_name).is_some() {
+                    return Err(format!("Column {:?} already exists in the Table {:?}", new_name, stmt.table_name()).into());
+                }
+
+                table.columns[index].name = new_name.to_owned();
+            }
+            AlterTableAction::RenameTable(_) => unreachable!("renaming the table is handled above"),
+        }
+
+        Ok(())
+    }
+
+    fn table_names(&self) -> Vec<String> {
+        let mut names = self.tables.keys().cloned().collect::<Vec<String>>();
+        names.sort();
+        names
+    }
+
+    fn table_schema(&self, table: &str) -> Result<CreateTableStatement> {
+        match self.tables.get(table) {
+            None => Err(format!("Table {:#?} not found", table).into()),
+            Some(found) => {
+                let columns = found.columns().iter().map(|column| {
+                    ColumnDefinition::new(
+                        column.name().to_owned(),
+                        column.data_type(),
+                        column.constraints().to_vec())
+                }).collect::<Vec<ColumnDefinition>>();
+
+                Ok(CreateTableStatement::new(table.to_owned(), columns, false))
+            }
+        }
+    }
+
+    fn show_tables(&self) -> Result<QueryResults> {
+        let columns = vec![ResultColumn::new("name".to_owned(), None, ColumnTypes::String, false)];
+
+        let rows = self.table_names().into_iter()
+            .map(|name| vec![Cell::String(name)])
+            .collect::<Vec<Vec<Cell>>>();
+
+        Ok(QueryResults::new(columns, rows))
+    }
+
+    fn describe(&self, stmt: &DescribeStatement) -> Result<QueryResults> {
+        match self.tables.get(stmt.table_name()) {
+            None => Err(format!("Table {:#?} not found", stmt.table_name()).into()),
+            Some(table) => {
+                let columns = vec![
+                    ResultColumn::new("name".to_owned(), None, ColumnTypes::String, false),
+                    ResultColumn::new("type".to_owned(), None, ColumnTypes::String, false),
+                    ResultColumn::new("nullable".to_owned(), None, ColumnTypes::String, false),
+                    ResultColumn::new("key".to_owned(), None, ColumnTypes::String, true),
+                    ResultColumn::new("default".to_owned(), None, ColumnTypes::String, true),
+                ];
+
+                let rows = table.columns().iter()
+                    .map(|column| {
+                        let key = if column.is_primary_key() {
+                            Cell::String("PRIMARY KEY".to_owned())
+                        } else if column.is_unique() {
+                            Cell::String("UNIQUE".to_owned())
+                        } else {
+                            Cell::Null
+                        };
+
+                        vec![
+                            Cell::String(column.name().to_owned()),
+                            Cell::String(column.data_type().to_string()),
+                            Cell::String(if column.is_nullable() { "YES" } else { "NO" }.to_owned()),
+                            key,
+                            // The default is written as SQL, so text defaults keep their quotes
+                            column.default_value().map_or(Cell::Null, |value| Cell::String(value.to_string())),
+                        ]
+                    })
+                    .collect::<Vec<Vec<Cell>>>();
+
+                Ok(QueryResults::new(columns, rows))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use std::collections::HashMap;
+
+    use crate::backend::Backend;
+    use crate::backend::memory::{ColumnTypes, InMemoryBackend};
+    use crate::Result;
+    use crate::statements::compiler::StatementCompiler;
+    use crate::statements::scanner::{Token, TokenIterator};
+    use crate::statements::Statement;
+
+    /// Runs every statement in `sql` one after the other. Queries give back their rows, each written
+    /// as its cells separated by commas, while the other statements give back nothing.
+    pub(in crate::backend) fn run(backend: &mut dyn Backend, sql: &str) -> Result<Vec<String>> {
+        let tokens = TokenIterator::new_iterator(sql.chars())
+            .filter(|x| !matches!(x, Ok(token) if matches!(token.token(), Token::Space | Token::NewLine)));
+
+        let mut rows = Vec::new();
+
+        for statement in StatementCompiler::new(tokens) {
+            let results = match statement? {
+                Statement::Create(statement) => { backend.create_table(&statement)?; None }
+                Statement::Insert(statement) => { backend.insert(&statement)?; None }
+                Statement::Update(statement) => { backend.update(&statement)?; None }
+                Statement::Delete(statement) => { backend.delete(&statement)?; None }
+                Statement::Drop(statement) => { backend.drop_table(&statement)?; None }
+                Statement::Truncate(statement) => { backend.truncate(&statement)?; None }
+                Statement::Alter(statement) => { backend.alter_table(&statement)?; None }
+                Statement::Select(statement) => Some(backend.select(&statement)?),
+                Statement::ShowTables => Some(backend.show_tables()?),
+                Statement::Describe(statement) => Some(backend.describe(&statement)?),
+            };
+
+            rows = results.map_or_else(Vec::new, |results| {
+                results.cells().iter()
+                    .map(|row| row.iter().map(|cell| cell.to_string()).collect::<Vec<String>>().join(", "))
+                    .collect()
+            });
+        }
+
+        Ok(rows)
+    }
+
+    /// A database made by running `sql`, which must succeed.
+    pub(in crate::backend) fn database(sql: &str) -> InMemoryBackend {
+        let mut backend = InMemoryBackend::new(HashMap::new());
+        run(&mut backend, sql).unwrap();
+        backend
+    }
+
+    /// The rows the query in `sql` returns, which must succeed.
+    pub(in crate::backend) fn query(backend: &mut dyn Backend, sql: &str) -> Vec<String> {
+        run(backend, sql).unwrap()
+    }
+
+    /// The message of the error running `sql` fails with.
+    pub(in crate::backend) fn error(backend: &mut dyn Backend, sql: &str) -> String {
+        match run(backend, sql) {
+            Ok(rows) => panic!("Expected {:?} to fail but it returned {:?}", sql, rows),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    /// The number of rows the UPDATE or DELETE in `sql` changes, which must succeed.
+    fn affected(backend: &mut InMemoryBackend, sql: &str) -> usize {
+        let tokens = TokenIterator::new_iterator(sql.chars())
+            .filter(|x| !matches!(x, Ok(token) if *token.token() == Token::Space));
+
+        match StatementCompiler::new(tokens).next().unwrap().unwrap() {
+            Statement::Update(statement) => backend.update(&statement).unwrap(),
+            Statement::Delete(statement) => backend.delete(&statement).unwrap(),
+            statement => panic!("Expected an update or a delete but got {:?}", statement),
+        }
+    }
+
+    fn staff() -> InMemoryBackend {
+        database("CREATE TABLE staff (id INT, name TEXT, age INT);
+            INSERT INTO staff VALUES (1, 'ann', 30), (2, 'bob', 25), (3, 'cat', 41), (4, 'dan', 25);")
+    }
+
+    #[test]
+    fn where_clauses_compare_columns_with_values() {
+        let mut backend = staff();
+
+        assert_eq!(query(&mut backend, "SELECT name FROM staff WHERE age = 25"), vec!["bob", "dan"]);
+        assert_eq!(query(&mut backend, "SELECT name FROM staff WHERE age <> 25"), vec!["ann", "cat"]);
+        assert_eq!(query(&mut backend, "SELECT name FROM staff WHERE age != 25"), vec!["ann", "cat"]);
+        assert_eq!(query(&mut backend, "SELECT name FROM staff WHERE age < 30"), vec!["bob", "dan"]);
+        assert_eq!(query(&mut backend, "SELECT name FROM staff WHERE age <= 30"), vec!["ann", "bob", "dan"]);
+        assert_eq!(query(&mut backend, "SELECT name FROM staff WHERE age > 30"), vec!["cat"]);
+        assert_eq!(query(&mut backend, "SELECT name FROM staff WHERE 30 >= age"), vec!["ann", "bob", "dan"]);
+        assert_eq!(query(&mut backend, "SELECT id FROM staff WHERE name = 'cat'"), vec!["3"]);
+        assert_eq!(query(&mut backend, "SELECT id FROM staff WHERE name > 'bob'"), vec!["3", "4"]);
+        assert_eq!(query(&mut backend, "SELECT id FROM staff WHERE id = age"), Vec::<String>::new());
+        assert!(query(&mut backend, "SELECT * FROM staff WHERE age > 100").is_empty());
+
+        assert!(error(&mut backend, "SELECT * FROM staff WHERE age = 'old'").contains("compare"));
+        assert!(error(&mut backend, "SELECT * FROM staff WHERE height = 1").contains("height"));
+    }
+
+    #[test]
+    fn where_clauses_combine_with_and_or_and_not() {
+        let mut backend = staff();
+
+        // AND binds tighter than OR, and NOT tighter than both
+        assert_eq!(query(&mut backend, "SELECT id FROM staff WHERE id = 1 OR id = 2 AND age = 30"), vec!["1"]);
+        assert_eq!(query(&mut backend, "SELECT id FROM staff WHERE (id = 1 OR id = 2) AND age = 25"), vec!["2"]);
+        assert_eq!(query(&mut backend, "SELECT id FROM staff WHERE NOT age = 25 AND id > 1"), vec!["3"]);
+        assert_eq!(query(&mut backend, "SELECT id FROM staff WHERE NOT (age = 25 AND id > 1)"), vec!["1", "3"]);
+        assert_eq!(query(&mut backend, "SELECT id FROM staff WHERE NOT NOT id = 4 OR id = 1"), vec!["1", "4"]);
+        assert_eq!(query(&mut backend, "SELECT id FROM staff WHERE ((age > 20) AND ((name < 'c')))"), vec!["1", "2"]);
+        assert!(query(&mut backend, "SELECT id FROM staff WHERE age = 25 AND age = 30").is_empty());
+
+        assert!(error(&mut backend, "SELECT id FROM staff WHERE (id = 1").contains("Expected a token but got nothing"));
+    }
+
+    #[test]
+    fn updates_change_the_matching_rows() {
+        let mut backend = staff();
+
+        assert_eq!(affected(&mut backend, "UPDATE staff SET age = age + 1 WHERE age = 25"), 2);
+        assert_eq!(query(&mut backend, "SELECT id, age FROM staff"), vec!["1, 30", "2, 26", "3, 41", "4, 26"]);
+
+        assert_eq!(affected(&mut backend, "UPDATE staff SET age = age + 1"), 4);
+        assert_eq!(query(&mut backend, "SELECT id, age FROM staff"), vec!["1, 31", "2, 27", "3, 42", "4, 27"]);
+
+        // Every assignment reads the row as it was before the update
+        assert_eq!(affected(&mut backend, "UPDATE staff SET name = 'eve', id = id * 10, age = id WHERE name = 'ann'"), 1);
+        assert_eq!(query(&mut backend, "SELECT * FROM staff WHERE name = 'eve'"), vec!["10, eve, 1"]);
+
+        assert_eq!(affected(&mut backend, "UPDATE staff SET age = 1 WHERE id = 99"), 0);
+
+        assert_eq!(error(&mut backend, "UPDATE staff SET age = 'old'"), "Expected INT for column \"age\" but got String(\"old\")");
+        assert!(error(&mut backend, "UPDATE staff SET height = 1").starts_with("Column \"height\" is not found"));
+        assert!(error(&mut backend, "UPDATE missing SET age = 1").contains("not found"));
+        assert_eq!(query(&mut backend, "SELECT age FROM staff WHERE id = 2"), vec!["27"]);
+    }
+
+    #[test]
+    fn deletes_remove_the_matching_rows() {
+        let mut backend = staff();
+
+        assert_eq!(affected(&mut backend, "DELETE FROM staff WHERE age = 25"), 2);
+        assert_eq!(query(&mut backend, "SELECT * FROM staff"), vec!["1, ann, 30", "3, cat, 41"]);
+        assert_eq!(affected(&mut backend, "DELETE FROM staff WHERE age = 25"), 0);
+        assert_eq!(query(&mut backend, "SELECT name FROM staff WHERE id > 1"), vec!["cat"]);
+
+        assert_eq!(affected(&mut backend, "DELETE FROM staff"), 2);
+        assert!(query(&mut backend, "SELECT * FROM staff").is_empty());
+
+        run(&mut backend, "INSERT INTO staff VALUES (5, 'eve', 50); INSERT INTO staff VALUES (6, 'fay', 60);").unwrap();
+        assert_eq!(query(&mut backend, "SELECT * FROM staff"), vec!["5, eve, 50", "6, fay, 60"]);
+        assert_eq!(affected(&mut backend, "DELETE FROM staff WHERE name = 'eve'"), 1);
+        assert_eq!(query(&mut backend, "SELECT * FROM staff"), vec!["6, fay, 60"]);
+
+        assert!(error(&mut backend, "DELETE FROM missing").contains("not found"));
+    }
+
+    #[test]
+    fn unique_columns_reject_duplicates() {
+        let mut backend = database("CREATE TABLE users (id INT PRIMARY KEY, email TEXT UNIQUE);
+            INSERT INTO users VALUES (1, 'a'), (2, 'b');");
+
+        assert!(error(&mut backend, "INSERT INTO users VALUES (1, 'c')").starts_with("PRIMARY KEY constraint failed"));
+        assert!(error(&mut backend, "INSERT INTO users VALUES (3, 'b')").starts_with("UNIQUE constraint failed"));
+        assert!(error(&mut backend, "INSERT INTO users VALUES (3, 'c'), (4, 'c')").starts_with("UNIQUE constraint failed"));
+        assert!(error(&mut backend, "UPDATE users SET email = 'a' WHERE id = 2").starts_with("UNIQUE constraint failed"));
+        assert_eq!(query(&mut backend, "SELECT id, email FROM users"), vec!["1, a", "2, b"]);
+    }
+
+    #[test]
+    fn unique_columns_allow_many_nulls() {
+        let mut backend = database("CREATE TABLE users (id INT PRIMARY KEY, email TEXT UNIQUE);
+            INSERT INTO users VALUES (1, NULL), (2, NULL);");
+
+        assert_eq!(query(&mut backend, "SELECT id FROM users WHERE email IS NULL"), vec!["1", "2"]);
+        assert!(error(&mut backend, "INSERT INTO users VALUES (NULL, 'a')").starts_with("NOT NULL constraint failed"));
+    }
+
+    #[test]
+    fn unique_index_follows_writes() {
+        let mut backend = database("CREATE TABLE users (id INT PRIMARY KEY, email TEXT UNIQUE);
+            INSERT INTO users VALUES (1, 'a'), (2, 'b'), (3, 'c');");
+
+        // Swapping values between rows is fine as long as they are unique once every row is written
+        run(&mut backend, "UPDATE users SET id = 3 - id WHERE id < 3").unwrap();
+        assert_eq!(query(&mut backend, "SELECT id, email FROM users ORDER BY id"), vec!["1, b", "2, a", "3, c"]);
+        assert!(error(&mut backend, "INSERT INTO users VALUES (2, 'd')").starts_with("PRIMARY KEY constraint failed"));
+
+        // Values are free again once their row is deleted or the table is emptied
+        run(&mut backend, "DELETE FROM users WHERE email = 'c'; INSERT INTO users VALUES (3, 'c2')").unwrap();
+        assert!(error(&mut backend, "INSERT INTO users VALUES (4, 'c2')").starts_with("UNIQUE constraint failed"));
+        run(&mut backend, "TRUNCATE users; INSERT INTO users VALUES (1, 'a')").unwrap();
+        assert_eq!(query(&mut backend, "SELECT id, email FROM users"), vec!["1, a"]);
+    }
+
+    #[test]
+    fn unique_index_follows_altered_columns() {
+        let mut backend = database("CREATE TABLE users (name TEXT, id INT PRIMARY KEY);
+            INSERT INTO users VALUES ('a', 1);
+            ALTER TABLE users DROP COLUMN name;
+            ALTER TABLE users ADD COLUMN email TEXT UNIQUE;");
+
+        assert!(error(&mut backend, "INSERT INTO users VALUES (1, 'a')").starts_with("PRIMARY KEY constraint failed"));
+        run(&mut backend, "INSERT INTO users VALUES (2, 'a')").unwrap();
+        assert!(error(&mut backend, "INSERT INTO users VALUES (3, 'a')").starts_with("UNIQUE constraint failed"));
+    }
+
+    /// The name, type and nullability of each column the query in `sql` returns.
+    fn columns(backend: &mut InMemoryBackend, sql: &str) -> Vec<(String, ColumnTypes, bool)> {
+        let tokens = TokenIterator::new_iterator(sql.chars())
+            .filter(|x| !matches!(x, Ok(token) if *token.token() == Token::Space));
+        let statement = match StatementCompiler::new(tokens).next().unwrap().unwrap() {
+            Statement::Select(statement) => statement,
+            statement => panic!("Expected a select but got {:?}", statement),
         };
+
+        backend.select(&statement).unwrap().columns().iter()
+            .map(|column| (column.name().to_owned(), *column.column_type(), column.is_nullable()))
+            .collect()
     }
-}
\ No newline at end of file
+
+    fn orders() -> InMemoryBackend {
+        database("CREATE TABLE orders (id INT NOT NULL, price DECIMAL(10, 2), placed DATE NOT NULL);
+            INSERT INTO orders VALUES (1, 2.50, '2024-01-31'), (2, NULL, '2024-02-29');")
+    }
+
+    #[test]
+    fn any_expression_can_be_selected() {
+        let mut backend = orders();
+
+        assert_eq!(query(&mut backend, "SELECT 1 FROM orders"), vec!["1", "1"]);
+        assert_eq!(query(&mut backend, "SELECT price * 2 FROM orders"), vec!["5.00", "NULL"]);
+        assert_eq!(query(&mut backend, "SELECT placed + INTERVAL '1' DAY, id FROM orders"), vec!["2024-02-01, 1", "2024-03-01, 2"]);
+        assert_eq!(query(&mut backend, "SELECT id > 1, price IS NULL FROM orders WHERE id = 2"), vec!["true, true"]);
+        assert_eq!(query(&mut backend, "SELECT o.*, o.id * 10 FROM orders o WHERE id = 1"), vec!["1, 2.50, 2024-01-31, 10"]);
+    }
+
+    #[test]
+    fn selected_expressions_are_named_and_typed() {
+        let mut backend = orders();
+
+        assert_eq!(columns(&mut backend, "SELECT id, price * 2, NULL, (id + 1) * 2 FROM orders"), vec![
+            ("id".to_owned(), ColumnTypes::Int32, false),
+            ("price * 2".to_owned(), ColumnTypes::Decimal(38, 2), true),
+            ("NULL".to_owned(), ColumnTypes::String, true),
+            ("(id + 1) * 2".to_owned(), ColumnTypes::Int64, false),
+        ]);
+        assert_eq!(columns(&mut backend, "SELECT id AS number, placed dated, price / 4 AS share FROM orders"), vec![
+            ("number".to_owned(), ColumnTypes::Int32, false),
+            ("dated".to_owned(), ColumnTypes::Date, false),
+            ("share".to_owned(), ColumnTypes::Decimal(38, 6), true),
+        ]);
+        assert_eq!(columns(&mut backend, "SELECT COUNT(*) AS n, MAX(id) + 1 FROM orders"), vec![
+            ("n".to_owned(), ColumnTypes::Int64, false),
+            ("MAX(id) + 1".to_owned(), ColumnTypes::Int64, true),
+        ]);
+    }
+
+    #[test]
+    fn grouped_selects_return_expressions_of_their_groups() {
+        let mut backend = orders();
+
+        assert_eq!(query(&mut backend, "SELECT id * 10, COUNT(*) + 1, 'x' FROM orders GROUP BY id"), vec!["10, 2, x", "20, 2, x"]);
+        assert_eq!(query(&mut backend, "SELECT id + 1 AS next FROM orders GROUP BY id + 1"), vec!["2", "3"]);
+        assert!(error(&mut backend, "SELECT id + price FROM orders GROUP BY id").contains("must appear in the GROUP BY clause"));
+        assert!(error(&mut backend, "SELECT * FROM orders GROUP BY id").starts_with("SELECT * cannot be used"));
+        assert!(error(&mut backend, "SELECT missing + 1 FROM orders").contains("missing"));
+    }
+
+    #[test]
+    fn dates_and_times_move_by_intervals() {
+        let mut backend = database("CREATE TABLE events (date DATE, time TIME, timestamp TIMESTAMP);
+            INSERT INTO events VALUES (DATE '2024-01-31', TIME '23:30:00', TIMESTAMP '2024-02-28 23:30:00');");
+
+        assert_eq!(
+            query(&mut backend, "SELECT date + INTERVAL '1' MONTH, time + INTERVAL '1' HOUR, timestamp + INTERVAL '1' HOUR FROM events"),
+            vec!["2024-02-29, 00:30:00, 2024-02-29 00:30:00"],
+        );
+        assert_eq!(
+            query(&mut backend, "SELECT timestamp - INTERVAL '2' DAY, date - INTERVAL '1' YEAR, DATE '2024-02-29' + INTERVAL '1' YEAR FROM events"),
+            vec!["2024-02-26 23:30:00, 2023-01-31, 2025-02-28"],
+        );
+        assert_eq!(query(&mut backend, "SELECT date FROM events WHERE date <= DATE '2024-02-01' + INTERVAL '-1' DAY"), vec!["2024-01-31"]);
+
+        assert!(error(&mut backend, "SELECT DATE '2023-02-29' FROM events").contains("Invalid DATE"));
+        assert!(error(&mut backend, "INSERT INTO events VALUES (NULL, TIME '24:00:00', NULL)").contains("Invalid TIME"));
+        assert!(error(&mut backend, "SELECT date + INTERVAL '1 day' MONTH FROM events").contains("Invalid INTERVAL"));
+    }
+
+    #[test]
+    fn whole_decimals_are_stored_in_integer_columns() {
+        let mut backend = database("CREATE TABLE items (qty INT, total BIGINT, price DECIMAL);
+            INSERT INTO items VALUES (2.00, 30.0, 12.5);
+            INSERT INTO items SELECT price, price, price FROM items;");
+
+        assert_eq!(query(&mut backend, "SELECT qty, total, price FROM items"), vec!["2, 30, 13", "13, 13, 13"]);
+        assert_eq!(query(&mut backend, "DESCRIBE items")[2], "price, DECIMAL(18, 0), YES, NULL, NULL");
+
+        assert!(error(&mut backend, "INSERT INTO items VALUES (2.5, 1, 1)").starts_with("Expected a whole number for column \"qty\""));
+        assert!(error(&mut backend, "INSERT INTO items VALUES (3000000000.0, 1, 1)").starts_with("Value 3000000000.0 is out of range"));
+        assert!(error(&mut backend, "INSERT INTO items VALUES (1, 1, 1000000000000000000)").contains("out of range"));
+        assert!(error(&mut backend, "INSERT INTO items SELECT qty * 1.5, total, price FROM items").contains("returns DECIMAL(38, 1)"));
+    }
+
+    #[test]
+    fn inserts_name_their_columns_and_several_rows() {
+        let mut backend = database("CREATE TABLE users (id INT NOT NULL, name TEXT DEFAULT 'anon', age INT);
+            INSERT INTO users (age, id) VALUES (30, 1), (40, 2);
+            INSERT INTO users VALUES (3, DEFAULT, NULL), (4, 'dee', 20);
+            INSERT INTO users (id, name) VALUES (5, 'eve');");
+
+        assert_eq!(query(&mut backend, "SELECT id, name, age FROM users"), vec![
+            "1, anon, 30", "2, anon, 40", "3, anon, NULL", "4, dee, 20", "5, eve, NULL",
+        ]);
+
+        assert!(error(&mut backend, "INSERT INTO users (id, id) VALUES (6, 6)").starts_with("Column \"id\" is listed more than once"));
+        assert!(error(&mut backend, "INSERT INTO users (id, email) VALUES (6, 'x')").starts_with("Column \"email\" is not found"));
+        assert!(error(&mut backend, "INSERT INTO users (id, name) VALUES (6, 'x'), (7)").starts_with("Incorrect number of column"));
+        assert!(error(&mut backend, "INSERT INTO users (name) VALUES ('x')").contains("NOT NULL"));
+
+        // A row which fails leaves out every row of its statement
+        assert!(error(&mut backend, "INSERT INTO users VALUES (6, 'f', 1), (NULL, 'g', 2)").contains("NOT NULL"));
+        assert_eq!(query(&mut backend, "SELECT COUNT(*) FROM users"), vec!["5"]);
+    }
+
+    #[test]
+    fn inserts_copy_the_rows_of_a_select() {
+        let mut backend = database("CREATE TABLE users (id INT, name TEXT, age INT);
+            INSERT INTO users VALUES (1, 'ann', 30), (2, 'bob', 40), (3, 'cat', NULL);
+            CREATE TABLE names (name TEXT, id BIGINT DEFAULT 0);
+            INSERT INTO names (id, name) SELECT id, name FROM users WHERE age IS NOT NULL ORDER BY id DESC;
+            INSERT INTO names (name) SELECT name FROM users WHERE id = 3;");
+
+        assert_eq!(query(&mut backend, "SELECT name, id FROM names"), vec!["bob, 2", "ann, 1", "cat, 0"]);
+
+        // Reading from the table being written to only sees the rows from before the statement
+        query(&mut backend, "INSERT INTO names SELECT name, id + 10 FROM names");
+        assert_eq!(query(&mut backend, "SELECT COUNT(*), MAX(id) FROM names"), vec!["6, 12"]);
+
+        assert!(error(&mut backend, "INSERT INTO names SELECT name FROM users").starts_with("Incorrect number of column"));
+        assert!(error(&mut backend, "INSERT INTO names SELECT age, name FROM users").contains("but the SELECT returns"));
+        assert!(error(&mut backend, "INSERT INTO names SELECT name, id FROM missing").contains("missing"));
+    }
+
+    fn people() -> InMemoryBackend {
+        database("CREATE TABLE people (id INT, name TEXT, age INT);
+            INSERT INTO people VALUES (1, 'ann', 30), (2, 'bob', NULL), (3, 'cat', 25), (4, 'dan', 30), (5, 'eve', NULL);")
+    }
+
+    #[test]
+    fn rows_are_ordered_by_each_key_in_turn() {
+        let mut backend = people();
+
+        assert_eq!(query(&mut backend, "SELECT name FROM people ORDER BY age, id DESC"), vec!["cat", "dan", "ann", "eve", "bob"]);
+        assert_eq!(query(&mut backend, "SELECT name FROM people ORDER BY age DESC, name ASC"), vec!["bob", "eve", "ann", "dan", "cat"]);
+        assert_eq!(query(&mut backend, "SELECT name FROM people ORDER BY age NULLS FIRST, id"), vec!["bob", "eve", "cat", "ann", "dan"]);
+        assert_eq!(query(&mut backend, "SELECT name FROM people ORDER BY age DESC NULLS LAST, id"), vec!["ann", "dan", "cat", "bob", "eve"]);
+
+        // Keys may be expressions and columns which are not selected
+        assert_eq!(query(&mut backend, "SELECT name FROM people WHERE age IS NOT NULL ORDER BY age * -1, name DESC"), vec!["dan", "ann", "cat"]);
+        assert_eq!(query(&mut backend, "SELECT age, COUNT(*) FROM people GROUP BY age ORDER BY COUNT(*) DESC, age"), vec!["30, 2", "NULL, 2", "25, 1"]);
+    }
+
+    #[test]
+    fn limit_and_offset_pick_a_window_of_rows() {
+        let mut backend = people();
+
+        assert_eq!(query(&mut backend, "SELECT name FROM people ORDER BY id LIMIT 2"), vec!["ann", "bob"]);
+        assert_eq!(query(&mut backend, "SELECT name FROM people ORDER BY id DESC LIMIT 2 OFFSET 1"), vec!["dan", "cat"]);
+        assert_eq!(query(&mut backend, "SELECT name FROM people ORDER BY id LIMIT 10 OFFSET 3"), vec!["dan", "eve"]);
+        assert_eq!(query(&mut backend, "SELECT name FROM people LIMIT 3").len(), 3);
+        assert!(query(&mut backend, "SELECT name FROM people LIMIT 0").is_empty());
+        assert!(query(&mut backend, "SELECT name FROM people ORDER BY id LIMIT 2 OFFSET 5").is_empty());
+        assert_eq!(query(&mut backend, "SELECT age, COUNT(*) FROM people GROUP BY age ORDER BY age LIMIT 1 OFFSET 1"), vec!["30, 2"]);
+
+        assert!(!error(&mut backend, "SELECT name FROM people LIMIT -1").is_empty());
+    }
+
+    #[test]
+    fn limit_keeps_the_best_rows_of_a_large_sort() {
+        // Enough rows for the sort to drop the ones which cannot make it into the results as it goes
+        let mut backend = database("CREATE TABLE numbers (n INT); INSERT INTO numbers VALUES (1), (2);");
+        for count in (1..12).map(|doublings| 1 << doublings) {
+            query(&mut backend, &format!("INSERT INTO numbers SELECT n + {} FROM numbers", count));
+        }
+
+        assert_eq!(query(&mut backend, "SELECT COUNT(*), MIN(n), MAX(n) FROM numbers"), vec!["4096, 1, 4096"]);
+        assert_eq!(query(&mut backend, "SELECT n FROM numbers ORDER BY n DESC LIMIT 3 OFFSET 1000"), vec!["3096", "3095", "3094"]);
+        assert_eq!(query(&mut backend, "SELECT n FROM numbers ORDER BY n > 2048, n DESC LIMIT 3 OFFSET 4"), vec!["2044", "2043", "2042"]);
+    }
+
+    #[test]
+    fn altered_tables_keep_their_rows() {
+        let mut backend = database("CREATE TABLE users (id INT PRIMARY KEY, name TEXT);
+            INSERT INTO users VALUES (2, 'bob'), (1, 'ann');
+            ALTER TABLE users ADD COLUMN age INT DEFAULT 20;
+            ALTER TABLE users ADD email TEXT;");
+
+        assert_eq!(query(&mut backend, "SELECT * FROM users"), vec!["1, ann, 20, NULL", "2, bob, 20, NULL"]);
+
+        run(&mut backend, "ALTER TABLE users RENAME COLUMN name TO nick; ALTER TABLE users DROP COLUMN email;").unwrap();
+        assert_eq!(query(&mut backend, "SELECT nick, age FROM users WHERE id = 2"), vec!["bob, 20"]);
+
+        // The rows are no longer keyed by id once it is dropped, but can still be found by the other columns
+        run(&mut backend, "ALTER TABLE users DROP COLUMN id; ALTER TABLE users RENAME TO people;").unwrap();
+        assert_eq!(query(&mut backend, "SELECT nick FROM people WHERE age = 20 ORDER BY nick"), vec!["ann", "bob"]);
+        assert!(error(&mut backend, "SELECT * FROM users").contains("not found"));
+
+        assert!(error(&mut backend, "ALTER TABLE people ADD COLUMN code TEXT NOT NULL").contains("needs a DEFAULT"));
+        assert!(error(&mut backend, "ALTER TABLE people ADD COLUMN code TEXT UNIQUE DEFAULT 'x'").contains("cannot be unique"));
+        assert!(error(&mut backend, "ALTER TABLE people ADD COLUMN age INT").contains("already exists"));
+        assert!(error(&mut backend, "ALTER TABLE people RENAME COLUMN nick TO age").contains("already exists"));
+        assert!(error(&mut backend, "ALTER TABLE people DROP COLUMN name").contains("not found"));
+        assert!(error(&mut backend, "ALTER TABLE missing ADD COLUMN code TEXT").contains("not found"));
+
+        run(&mut backend, "ALTER TABLE people DROP COLUMN age").unwrap();
+        assert!(error(&mut backend, "ALTER TABLE people DROP COLUMN nick").contains("only column"));
+    }
+
+    #[test]
+    fn tables_are_emptied_and_dropped() {
+        let mut backend = database("CREATE TABLE users (id INT PRIMARY KEY, name TEXT UNIQUE);
+            INSERT INTO users VALUES (1, 'ann'), (2, 'bob');
+            CREATE TABLE orders (id INT);");
+
+        run(&mut backend, "TRUNCATE TABLE users").unwrap();
+        assert!(query(&mut backend, "SELECT * FROM users").is_empty());
+
+        // The indexes are emptied with the rows
+        run(&mut backend, "INSERT INTO users VALUES (1, 'ann')").unwrap();
+        assert_eq!(query(&mut backend, "SELECT name FROM users WHERE id = 1"), vec!["ann"]);
+
+        run(&mut backend, "DROP TABLE users; DROP TABLE IF EXISTS users;").unwrap();
+        assert_eq!(query(&mut backend, "SHOW TABLES"), vec!["orders"]);
+        assert!(error(&mut backend, "DROP TABLE users").contains("not found"));
+        assert!(error(&mut backend, "TRUNCATE users").contains("not found"));
+
+        run(&mut backend, "CREATE TABLE users (id INT)").unwrap();
+        assert!(query(&mut backend, "SELECT * FROM users").is_empty());
+    }
+
+    #[test]
+    fn tables_describe_themselves() {
+        let mut backend = database("CREATE TABLE users (id INT PRIMARY KEY, name TEXT NOT NULL DEFAULT 'anon', email TEXT UNIQUE, score DOUBLE DEFAULT 1.5);
+            CREATE TABLE IF NOT EXISTS users (other INT);
+            CREATE TABLE accounts (id BIGINT);");
+
+        assert_eq!(query(&mut backend, "SHOW TABLES"), vec!["accounts", "users"]);
+        assert_eq!(query(&mut backend, "DESCRIBE users"), vec![
+            "id, INT, NO, PRIMARY KEY, NULL",
+            "name, TEXT, NO, NULL, 'anon'",
+            "email, TEXT, YES, UNIQUE, NULL",
+            "score, DOUBLE, YES, NULL, 1.5",
+        ]);
+
+        assert!(error(&mut backend, "CREATE TABLE users (other INT)").contains("already exists"));
+        assert!(error(&mut backend, "DESCRIBE missing").contains("not found"));
+
+        run(&mut backend, "DROP TABLE accounts; DROP TABLE users").unwrap();
+        assert!(query(&mut backend, "SHOW TABLES").is_empty());
+    }
+
+    #[test]
+    fn numbers_and_booleans_keep_their_types() {
+        let mut backend = database("CREATE TABLE readings (small INT, big BIGINT, ratio DOUBLE, active BOOLEAN);
+            INSERT INTO readings VALUES (-2147483648, 9223372036854775807, -0.5, TRUE), (2147483647, -9223372036854775808, 2.5e3, FALSE);");
+
+        assert_eq!(query(&mut backend, "SELECT * FROM readings ORDER BY small"), vec![
+            "-2147483648, 9223372036854775807, -0.5, true",
+            "2147483647, -9223372036854775808, 2500.0, false",
+        ]);
+        assert_eq!(query(&mut backend, "SELECT small + 1, big / 2, ratio * 2 FROM readings WHERE active"),
+            vec!["-2147483647, 4611686018427387903, -1.0"]);
+        assert_eq!(query(&mut backend, "SELECT NOT active, active = FALSE FROM readings WHERE ratio > 0"), vec!["true, true"]);
+
+        // INT arithmetic is done as BIGINT, so it only fails when it leaves BIGINT's range
+        assert_eq!(query(&mut backend, "SELECT small * 2 FROM readings WHERE active"), vec!["-4294967296"]);
+        assert!(error(&mut backend, "SELECT big + 1 FROM readings WHERE active").contains("too large for a BIGINT"));
+        assert!(error(&mut backend, "SELECT big * -1 FROM readings WHERE NOT active").contains("too large for a BIGINT"));
+        assert!(error(&mut backend, "INSERT INTO readings (small) VALUES (2147483648)").contains("out of range"));
+        assert!(error(&mut backend, "INSERT INTO readings (small) SELECT small - 1 FROM readings").contains("out of range"));
+
+        assert!(error(&mut backend, "SELECT small / 0 FROM readings").contains("divide"));
+        assert!(error(&mut backend, "SELECT ratio / 0.0 FROM readings").contains("divide"));
+        assert!(error(&mut backend, "SELECT * FROM readings WHERE small").contains("Boolean"));
+        assert!(error(&mut backend, "INSERT INTO readings (active) VALUES (1)").contains("Expected BOOLEAN"));
+    }
+
+    #[test]
+    fn any_number_can_be_negated() {
+        let mut backend = database("CREATE TABLE m (id INT, big BIGINT, price DECIMAL(5, 2), ratio DOUBLE);
+            INSERT INTO m VALUES (2, -9223372036854775807, 1.50, 0.25), (NULL, -9223372036854775808, NULL, NULL);");
+
+        assert_eq!(query(&mut backend, "SELECT -id, -(id + 1) * 2, -price, -ratio, -INTERVAL '1' DAY, - -id FROM m WHERE id > -id"),
+            vec!["-2, -6, -1.50, -0.25, -1 day, 2"]);
+        assert_eq!(query(&mut backend, "SELECT -big FROM m WHERE id = 2"), vec!["9223372036854775807"]);
+        assert_eq!(query(&mut backend, "SELECT -id, -price FROM m WHERE id IS NULL"), vec!["NULL, NULL"]);
+        assert_eq!(columns(&mut backend, "SELECT -id, -price FROM m"), vec![
+            ("-id".to_owned(), ColumnTypes::Int64, true),
+            ("-price".to_owned(), ColumnTypes::Decimal(5, 2), true),
+        ]);
+
+        assert!(error(&mut backend, "SELECT -big FROM m WHERE id IS NULL").contains("too large for a BIGINT"));
+        assert!(error(&mut backend, "SELECT -'text' FROM m").contains("negate"));
+    }
+}
@@ -0,0 +1,372 @@
+use std::collections::HashMap;
+
+use crate::backend::{Cell, ResultColumn};
+use crate::backend::memory::{aggregate, Column, ColumnTypes, MemoryCell, Table};
+use crate::backend::memory::access::Access;
+use crate::backend::memory::evaluator::{self, Scope};
+use crate::Result;
+use crate::statements::expression::{BinaryOperator, ColumnReference, Expression};
+use crate::statements::select::{FromClause, Join, JoinKind};
+
+/// A row of a relation, made of one row from each of its tables. A table's row is `None` when a
+/// LEFT JOIN found nothing to match, so all of its columns read as NULL.
+pub type JoinedRow<'a> = Vec<Option<&'a [MemoryCell]>>;
+
+/// Where a column is found in a relation: the index of its table and the index of the column in it.
+pub type ColumnPosition = (usize, usize);
+
+struct BoundTable<'a> {
+    binding: &'a str,
+    name: &'a str,
+    table: &'a Table,
+    /// Whether the table is on the right of a LEFT JOIN, which makes every one of its columns nullable.
+    outer: bool,
+}
+
+/// The tables a SELECT reads from, joined into rows which expressions can refer into.
+pub struct Relation<'a> {
+    tables: Vec<BoundTable<'a>>,
+    joins: &'a [Join],
+}
+
+impl<'a> Relation<'a> {
+    pub fn new(tables: &'a HashMap<String, Table>, from: &'a FromClause) -> Result<Self> {
+        let mut bound: Vec<BoundTable> = Vec::with_capacity(from.joins().len() + 1);
+
+        let references = std::iter::once((from.table(), false))
+            .chain(from.joins().iter().map(|join| (join.table(), join.kind() == JoinKind::Left)));
+
+        for (reference, outer) in references {
+            let table = tables.get(reference.name())
+                .ok_or_else(|| format!("Table {:#?} not found", reference.name()))?;
+
+            if bound.iter().any(|other| other.binding == reference.binding()) {
+                return Err(format!("Table {:?} is used more than once, give each of them a different alias", reference.binding()).into());
+            }
+
+            bound.push(BoundTable { binding: reference.binding(), name: reference.name(), table, outer });
+        }
+
+        Ok(Relation { tables: bound, joins: from.joins() })
+    }
+
+    /// Finds the column a reference points at. Unqualified names are looked up in every table, so
+    /// they must not be found in more than one.
+    pub fn resolve(&self, reference: &ColumnReference) -> Result<ColumnPosition> {
+        let mut found = None;
+
+        for (table_index, bound) in self.tables.iter().enumerate() {
+            if matches!(reference.table(), Some(table) if table != bound.binding) {
+                continue;
+            }
+
+            if let Some(column_index) = bound.table.column_index(reference.name()) {
+                if found.is_some() {
+                    return Err(format!("Column {:?} is ambiguous, qualify it with the name of its table", reference.name()).into());
+                }
+                found = Some((table_index, column_index));
+            }
+        }
+
+        match (found, reference.table()) {
+            (Some(position), _) => Ok(position),
+            (None, Some(table)) if self.tables.iter().all(|bound| bound.binding != table) => {
+                Err(format!("Table {:?} is not part of the query", table).into())
+            }
+            (None, Some(table)) => Err(format!("Column {:?} is not found in the Table {:?}", reference.name(), table).into()),
+            (None, None) if self.tables.len() == 1 => {
+                Err(format!("Column {:?} is not found in the Table {:?}", reference.name(), self.tables[0].name).into())
+            }
+            (None, None) => Err(format!("Column {:?} is not found in any of the tables", reference.name()).into()),
+        }
+    }
+
+    pub fn column(&self, (table, column): ColumnPosition) -> &Column {
+        &self.tables[table].table.columns()[column]
+    }
+
+    /// Describes a column for the results of the query.
+    pub fn result_column(&self, position: ColumnPosition) -> ResultColumn {
+        let bound = &self.tables[position.0];
+        let column = self.column(position);
+
+        ResultColumn::new(
+            column.name().to_owned(),
+            Some(bound.name.to_owned()),
+            *column.column_type(),
+            column.is_nullable() || bound.outer,
+        )
+    }
+
+    /// Describes a value a SELECT returns. A plain column keeps its name and the table it comes from,
+    /// while any other expression is named as it is written, unless it is given an alias. A bare
+    /// NULL has no type of its own, so it is returned as text.
+    pub fn value_column(&self, expression: &Expression, alias: Option<&str>) -> Result<ResultColumn> {
+        let column = match expression {
+            Expression::Column(reference) => self.result_column(self.resolve(reference)?),
+            expression => {
+                let (column_type, nullable) = aggregate::expression_type(expression, self)?;
+                ResultColumn::new(expression.to_string(), None, column_type.unwrap_or(ColumnTypes::String), nullable)
+            }
+        };
+
+        Ok(match alias {
+            Some(alias) => ResultColumn::new(alias.to_owned(), column.table().map(str::to_owned), *column.column_type(), column.is_nullable()),
+            None => column,
+        })
+    }
+
+    /// Every column of every table, as `SELECT *` returns them.
+    pub fn all_columns(&self) -> Vec<ColumnPosition> {
+        self.tables.iter().enumerate()
+            .flat_map(|(table, bound)| (0..bound.table.columns().len()).map(move |column| (table, column)))
+            .collect()
+    }
+
+    /// Every column of the table known as `binding`, as `SELECT binding.*` returns them.
+    pub fn columns_of(&self, binding: &str) -> Result<Vec<ColumnPosition>> {
+        match self.tables.iter().position(|bound| bound.binding == binding) {
+            Some(table) => Ok((0..self.tables[table].table.columns().len()).map(|column| (table, column)).collect()),
+            None => Err(format!("Table {:?} is not part of the query", binding).into()),
+        }
+    }
+
+    /// Reads a column from a row which may not hold every table yet, as happens while joining.
+    pub fn value(&self, row: &[Option<&[MemoryCell]>], (table, column): ColumnPosition) -> Result<Cell> {
+        match row.get(table) {
+            Some(Some(cells)) => Ok(Cell::from(&cells[column])),
+            Some(None) => Ok(Cell::Null),
+            None => Err(format!("Table {:?} cannot be used before it is joined", self.tables[table].binding).into()),
+        }
+    }
+
+    /// Produces the rows of the relation, leaving out rows of the first table which the indexes show
+    /// cannot match `predicate`. The rest still have to be checked against it. A single table is
+    /// read lazily, so a query can stop early, while joins are worked out one table at a time.
+    pub fn rows(&self, predicate: Option<&Expression>) -> Result<Box<dyn Iterator<Item=JoinedRow<'a>> + 'a>> {
+        let table = self.tables[0].table;
+        let access = Access::plan(table, predicate, |reference| match self.resolve(reference) {
+            Ok((0, column)) => Some(column),
+            _ => None,
+        });
+        let first = table.scan(&access).map(|(_, row)| vec![Some(row)]);
+
+        if self.joins.is_empty() {
+            return Ok(Box::new(first));
+        }
+
+        let mut rows = first.collect::<Vec<JoinedRow>>();
+        for (index, join) in self.joins.iter().enumerate() {
+            rows = self.join(rows, index + 1, join)?;
+        }
+
+        Ok(Box::new(rows.into_iter()))
+    }
+
+    /// Joins the table at `index` onto rows holding the tables before it. When the ON clause needs
+    /// columns on both sides to be equal, the new table is hashed on those columns so each row only
+    /// meets the rows it could match. Otherwise each row is tried against every row of the table.
+    fn join(&self, rows: Vec<JoinedRow<'a>>, index: usize, join: &Join) -> Result<Vec<JoinedRow<'a>>> {
+        let table = self.tables[index].table;
+        let keys = self.equality_keys(join.on(), index);
+
+        let hashed = if keys.is_empty() {
+            None
+        } else {
+            let mut hashed: HashMap<Vec<Cell>, Vec<&'a [MemoryCell]>> = HashMap::new();
+            for row in table.rows().iter() {
+                let key = keys.iter()
+                    .map(|(_, (_, column))| Cell::from(&row[*column]))
+                    .collect::<Vec<Cell>>();
+
+                // NULL is never equal to anything, so these rows can never match
+                if !key.contains(&Cell::Null) {
+                    hashed.entry(key).or_default().push(row);
+                }
+            }
+            Some(hashed)
+        };
+
+        let mut joined = Vec::new();
+
+        for mut row in rows {
+            let candidates: Box<dyn Iterator<Item=&'a [MemoryCell]> + '_> = match &hashed {
+                Some(hashed) => {
+                    let key = keys.iter()
+                        .map(|(position, _)| self.value(&row, *position))
+                        .collect::<Result<Vec<Cell>>>()?;
+                    Box::new(hashed.get(&key).into_iter().flatten().copied())
+                }
+                None => Box::new(table.rows().iter()),
+            };
+
+            row.push(None);
+            let mut matched = false;
+
+            for candidate in candidates {
+                row[index] = Some(candidate);
+                if evaluator::evaluate_predicate_in(join.on(), &RelationScope::new(self, &row))? {
+                    joined.push(row.clone());
+                    matched = true;
+                }
+            }
+
+            if !matched && join.kind() == JoinKind::Left {
+                row[index] = None;
+                joined.push(row);
+            }
+        }
+
+        Ok(joined)
+    }
+
+    /// Finds the `earlier = new` comparisons which the ON clause joining the table at `index` needs to
+    /// hold, pairing a column of an earlier table with one of the new table. Columns of different
+    /// types are left out as comparing them is an error the full ON clause reports.
+    fn equality_keys(&self, on: &Expression, index: usize) -> Vec<(ColumnPosition, ColumnPosition)> {
+        let mut keys = Vec::new();
+
+        let mut conjuncts = vec![on];
+        while let Some(expression) = conjuncts.pop() {
+            let (left, right) = match expression {
+                Expression::Binary(left, BinaryOperator::And, right) => {
+                    conjuncts.push(right);
+                    conjuncts.push(left);
+                    continue;
+                }
+                Expression::Binary(left, BinaryOperator::Equal, right) => (left, right),
+                _ => continue,
+            };
+
+            let (left, right) = match (left.as_ref(), right.as_ref()) {
+                (Expression::Column(left), Expression::Column(right)) => match (self.resolve(left), self.resolve(right)) {
+                    (Ok(left), Ok(right)) => (left, right),
+                    _ => continue,
+                },
+                _ => continue,
+            };
+
+            let key = match (left, right) {
+                (earlier, new) | (new, earlier) if earlier.0 < index && new.0 == index => (earlier, new),
+                _ => continue,
+            };
+
+            if self.column(key.0).column_type() == self.column(key.1).column_type() {
+                keys.push(key);
+            }
+        }
+
+        keys
+    }
+}
+
+/// Evaluates expressions against a row of a relation.
+pub struct RelationScope<'r, 'a> {
+    relation: &'r Relation<'a>,
+    row: &'r [Option<&'a [MemoryCell]>],
+}
+
+impl<'r, 'a> RelationScope<'r, 'a> {
+    pub fn new(relation: &'r Relation<'a>, row: &'r [Option<&'a [MemoryCell]>]) -> Self {
+        RelationScope { relation, row }
+    }
+}
+
+impl Scope for RelationScope<'_, '_> {
+    fn lookup(&self, expression: &Expression) -> Option<Result<Cell>> {
+        match expression {
+            Expression::Column(reference) => Some(
+                self.relation.resolve(reference).and_then(|position| self.relation.value(self.row, position))
+            ),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backend::memory::InMemoryBackend;
+    use crate::backend::memory::tests::{database, error, query};
+    use crate::statements::compiler::StatementCompiler;
+    use crate::statements::scanner::{Token, TokenIterator};
+    use crate::statements::Statement;
+
+    use super::*;
+
+    fn shop() -> InMemoryBackend {
+        database("CREATE TABLE users (id INT, name TEXT);
+            INSERT INTO users VALUES (1, 'ann'), (2, 'bob'), (3, 'cat'), (NULL, 'dan');
+            CREATE TABLE orders (id INT, owner INT, total INT, note TEXT);
+            INSERT INTO orders VALUES (10, 1, 5, 'a'), (11, 1, 7, 'b'), (12, 3, 2, NULL), (13, NULL, 9, 'c');
+            CREATE TABLE notes (note TEXT, body TEXT);
+            INSERT INTO notes VALUES ('a', 'first'), ('b', 'second'), ('c', 'third');")
+    }
+
+    /// The `earlier = new` column pairs the join onto `orders` is hashed on for the ON clause `on`.
+    fn keys(on: &str) -> Vec<(ColumnPosition, ColumnPosition)> {
+        let backend = shop();
+
+        let sql = format!("SELECT * FROM users JOIN orders ON {}", on);
+        let tokens = TokenIterator::new_iterator(sql.chars())
+            .filter(|x| !matches!(x, Ok(token) if *token.token() == Token::Space));
+        let statement = match StatementCompiler::new(tokens).next().unwrap().unwrap() {
+            Statement::Select(statement) => statement,
+            statement => panic!("Expected a select but got {:?}", statement),
+        };
+
+        let relation = Relation::new(&backend.tables, statement.from()).unwrap();
+        relation.equality_keys(statement.from().joins()[0].on(), 1)
+    }
+
+    #[test]
+    fn joins_are_hashed_on_the_columns_they_need_equal() {
+        assert_eq!(keys("users.id = orders.owner"), vec![((0, 0), (1, 1))]);
+        assert_eq!(keys("owner = users.id"), vec![((0, 0), (1, 1))]);
+        assert_eq!(keys("users.id = owner AND total > 1 AND orders.id = users.id"), vec![((0, 0), (1, 1)), ((0, 0), (1, 0))]);
+
+        // Nothing to hash on, so every pair of rows is tried
+        assert_eq!(keys("users.id = orders.owner OR total > 1"), vec![]);
+        assert_eq!(keys("users.id < orders.owner"), vec![]);
+        assert_eq!(keys("orders.id = orders.owner"), vec![]);
+        assert_eq!(keys("users.name = orders.owner"), vec![]);
+    }
+
+    #[test]
+    fn hashed_and_nested_loop_joins_agree() {
+        let mut backend = shop();
+
+        let expected = vec!["ann, 10", "ann, 11", "cat, 12"];
+        assert_eq!(query(&mut backend, "SELECT name, o.id FROM users u JOIN orders o ON u.id = o.owner ORDER BY o.id"), expected);
+        assert_eq!(query(&mut backend, "SELECT name, o.id FROM users u JOIN orders o ON u.id <= o.owner AND u.id >= o.owner ORDER BY o.id"), expected);
+        assert_eq!(query(&mut backend, "SELECT name, o.id FROM users u JOIN orders o ON u.id = o.owner AND total > 3 ORDER BY o.id"), vec!["ann, 10", "ann, 11"]);
+        assert_eq!(query(&mut backend, "SELECT COUNT(*) FROM users JOIN orders ON total > 6"), vec!["8"]);
+    }
+
+    #[test]
+    fn left_joins_fill_unmatched_rows_with_nulls() {
+        let mut backend = shop();
+
+        assert_eq!(query(&mut backend, "SELECT name, o.id FROM users u LEFT JOIN orders o ON u.id = o.owner ORDER BY name, o.id"),
+            vec!["ann, 10", "ann, 11", "bob, NULL", "cat, 12", "dan, NULL"]);
+        assert_eq!(query(&mut backend, "SELECT name FROM users u LEFT OUTER JOIN orders o ON u.id = o.owner WHERE o.id IS NULL ORDER BY name"),
+            vec!["bob", "dan"]);
+
+        // A table after a LEFT JOIN only meets the rows which found a match
+        assert_eq!(query(&mut backend, "SELECT name, o.id, body FROM users u LEFT JOIN orders o ON u.id = o.owner
+            LEFT JOIN notes n ON o.note = n.note ORDER BY name, o.id"),
+            vec!["ann, 10, first", "ann, 11, second", "bob, NULL, NULL", "cat, 12, NULL", "dan, NULL, NULL"]);
+        assert_eq!(query(&mut backend, "SELECT name, body FROM users u JOIN orders o ON u.id = o.owner
+            INNER JOIN notes n ON o.note = n.note ORDER BY body"), vec!["ann, first", "ann, second"]);
+    }
+
+    #[test]
+    fn columns_are_found_by_table() {
+        let mut backend = shop();
+
+        assert_eq!(query(&mut backend, "SELECT users.*, total FROM users JOIN orders ON users.id = owner WHERE total = 2"), vec!["3, cat, 2"]);
+        assert!(error(&mut backend, "SELECT id FROM users JOIN orders ON users.id = owner").contains("ambiguous"));
+        assert!(error(&mut backend, "SELECT u.id FROM users u JOIN orders ON users.id = owner").contains("users"));
+        assert!(error(&mut backend, "SELECT * FROM users JOIN users ON 1 = 1").contains("more than once"));
+        assert_eq!(query(&mut backend, "SELECT COUNT(*) FROM users a JOIN users b ON a.id = b.id"), vec!["3"]);
+    }
+}
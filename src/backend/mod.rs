@@ -1,29 +1,304 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use crate::backend::memory::ColumnTypes;
 use crate::Result;
+use crate::statements::alter::AlterTableStatement;
 use crate::statements::create::CreateTableStatement;
+use crate::statements::delete::DeleteStatement;
+use crate::statements::describe::DescribeStatement;
+use crate::statements::drop::DropTableStatement;
+use crate::statements::expression::Literal;
 use crate::statements::insert::InsertStatement;
 use crate::statements::select::SelectStatement;
+use crate::statements::truncate::TruncateTableStatement;
+use crate::statements::update::UpdateStatement;
+use crate::types::decimal::Decimal;
+use crate::types::temporal::{Date, Interval, Time, Timestamp};
 
+pub mod btree;
+pub mod file;
+pub mod format;
 pub mod memory;
 
+/// Describes one column of a result set.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ResultColumn {
+    name: String,
+    table: Option<String>,
+    column_type: ColumnTypes,
+    nullable: bool,
+}
+
+impl ResultColumn {
+    pub fn new(name: String, table: Option<String>, column_type: ColumnTypes, nullable: bool) -> Self {
+        ResultColumn { name, table, column_type, nullable }
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// The table the values were read from, or `None` when they were computed.
+    pub fn table(&self) -> Option<&str> {
+        self.table.as_deref()
+    }
+
+    pub fn column_type(&self) -> &ColumnTypes {
+        &self.column_type
+    }
+
+    pub fn is_nullable(&self) -> bool {
+        self.nullable
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct QueryResults {
-    cells: Vec<Vec<Cell>>
+    columns: Vec<ResultColumn>,
+    cells: Vec<Vec<Cell>>,
 }
 
 impl QueryResults {
-    pub fn new(cells: Vec<Vec<Cell>>) -> Self {
-        QueryResults { cells }
+    pub fn new(columns: Vec<ResultColumn>, cells: Vec<Vec<Cell>>) -> Self {
+        QueryResults { columns, cells }
+    }
+
+    pub fn columns(&self) -> &[ResultColumn] {
+        &self.columns
+    }
+
+    /// Finds a column by name. When several columns share the name the first one wins.
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        self.columns.iter().position(|column| column.name() == name)
+    }
+
+    pub fn cells(&self) -> &[Vec<Cell>] {
+        &self.cells
+    }
+
+    pub fn into_cells(self) -> Vec<Vec<Cell>> {
+        self.cells
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn row(&self, index: usize) -> Option<Row<'_>> {
+        self.cells.get(index).map(|cells| Row::new(&self.columns, cells))
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item=Row<'_>> {
+        self.cells.iter().map(move |cells| Row::new(&self.columns, cells))
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+/// A single row of `QueryResults`, which can look its cells up by position or column name.
+#[derive(Debug, Clone, Copy)]
+pub struct Row<'a> {
+    columns: &'a [ResultColumn],
+    cells: &'a [Cell],
+}
+
+impl<'a> Row<'a> {
+    pub fn new(columns: &'a [ResultColumn], cells: &'a [Cell]) -> Self {
+        Row { columns, cells }
+    }
+
+    pub fn cells(&self) -> &'a [Cell] {
+        self.cells
+    }
+
+    pub fn get(&self, index: usize) -> Option<&'a Cell> {
+        self.cells.get(index)
+    }
+
+    pub fn get_by_name(&self, name: &str) -> Option<&'a Cell> {
+        self.columns.iter()
+            .position(|column| column.name() == name)
+            .and_then(|index| self.cells.get(index))
+    }
+}
+
+#[derive(Debug, Clone)]
 pub enum Cell {
-    U32(u32),
+    Int32(i32),
+    Int64(i64),
+    Decimal(Decimal),
+    Double(f64),
     String(String),
+    Boolean(bool),
+    Date(Date),
+    Time(Time),
+    Timestamp(Timestamp),
+    /// Only ever the result of an expression, as no column holds intervals.
+    Interval(Interval),
+    Null,
+}
+
+/// The bits a double is compared and hashed by, so cells can be used as keys. Both zeros are the
+/// same value, as are all NaNs.
+pub(crate) fn double_bits(value: f64) -> u64 {
+    if value == 0.0 {
+        0
+    } else if value.is_nan() {
+        f64::NAN.to_bits()
+    } else {
+        value.to_bits()
+    }
+}
+
+impl PartialEq for Cell {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Cell::Int32(left), Cell::Int32(right)) => left == right,
+            (Cell::Int64(left), Cell::Int64(right)) => left == right,
+            (Cell::Decimal(left), Cell::Decimal(right)) => left == right,
+            (Cell::Double(left), Cell::Double(right)) => double_bits(*left) == double_bits(*right),
+            (Cell::String(left), Cell::String(right)) => left == right,
+            (Cell::Boolean(left), Cell::Boolean(right)) => left == right,
+            (Cell::Date(left), Cell::Date(right)) => left == right,
+            (Cell::Time(left), Cell::Time(right)) => left == right,
+            (Cell::Timestamp(left), Cell::Timestamp(right)) => left == right,
+            (Cell::Interval(left), Cell::Interval(right)) => left == right,
+            (Cell::Null, Cell::Null) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Cell {}
+
+impl Hash for Cell {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Cell::Int32(value) => value.hash(state),
+            Cell::Int64(value) => value.hash(state),
+            Cell::Decimal(value) => value.hash(state),
+            Cell::Double(value) => double_bits(*value).hash(state),
+            Cell::String(value) => value.hash(state),
+            Cell::Boolean(value) => value.hash(state),
+            Cell::Date(value) => value.hash(state),
+            Cell::Time(value) => value.hash(state),
+            Cell::Timestamp(value) => value.hash(state),
+            Cell::Interval(value) => value.hash(state),
+            Cell::Null => {}
+        }
+    }
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Cell::Int32(value) => write!(f, "{}", value),
+            Cell::Int64(value) => write!(f, "{}", value),
+            Cell::Decimal(value) => write!(f, "{}", value),
+            // Debug keeps the decimal point of whole numbers, so 2.0 does not read as an integer
+            Cell::Double(value) => write!(f, "{:?}", value),
+            Cell::String(value) => write!(f, "{}", value),
+            Cell::Boolean(value) => write!(f, "{}", value),
+            Cell::Date(value) => write!(f, "{}", value),
+            Cell::Time(value) => write!(f, "{}", value),
+            Cell::Timestamp(value) => write!(f, "{}", value),
+            Cell::Interval(value) => write!(f, "{}", value),
+            Cell::Null => write!(f, "NULL"),
+        }
+    }
+}
+
+impl From<&Literal> for Cell {
+    fn from(literal: &Literal) -> Self {
+        match literal {
+            Literal::Integer(value) => Cell::Int64(*value),
+            Literal::Decimal(value) => Cell::Decimal(*value),
+            Literal::Double(value) => Cell::Double(*value),
+            Literal::String(value) => Cell::String(value.to_string()),
+            Literal::Boolean(value) => Cell::Boolean(*value),
+            Literal::Date(value) => Cell::Date(*value),
+            Literal::Time(value) => Cell::Time(*value),
+            Literal::Timestamp(value) => Cell::Timestamp(*value),
+            Literal::Interval(value) => Cell::Interval(*value),
+            Literal::Null => Cell::Null,
+        }
+    }
 }
 
 pub trait Backend {
-    fn create_table(&mut self, stmt: &CreateTableStatement) -> Result<()>;
-    fn insert(&mut self, stmt: &InsertStatement) -> Result<()>;
+    /// Creates a table, returning whether it was created rather than skipped by IF NOT EXISTS.
+    fn create_table(&mut self, stmt: &CreateTableStatement) -> Result<bool>;
+    fn insert(&mut self, stmt: &InsertStatement) -> Result<usize>;
     fn select(&mut self, stmt: &SelectStatement) -> Result<QueryResults>;
-}
\ No newline at end of file
+    fn update(&mut self, stmt: &UpdateStatement) -> Result<usize>;
+    fn delete(&mut self, stmt: &DeleteStatement) -> Result<usize>;
+
+    /// Removes a table and its rows, returning whether there was a table to remove.
+    fn drop_table(&mut self, stmt: &DropTableStatement) -> Result<bool>;
+
+    /// Removes every row of a table, returning how many there were.
+    fn truncate(&mut self, stmt: &TruncateTableStatement) -> Result<usize>;
+
+    fn alter_table(&mut self, stmt: &AlterTableStatement) -> Result<()>;
+
+    /// The names of every table, in alphabetical order.
+    fn table_names(&self) -> Vec<String>;
+
+    /// Describes a table as the statement which would create it.
+    fn table_schema(&self, table: &str) -> Result<CreateTableStatement>;
+
+    /// Lists every table by name, in alphabetical order.
+    fn show_tables(&self) -> Result<QueryResults>;
+
+    /// Lists the columns of a table with their type and constraints, one row per column.
+    fn describe(&self, stmt: &DescribeStatement) -> Result<QueryResults>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results() -> QueryResults {
+        let columns = vec![
+            ResultColumn::new("id".to_owned(), Some("users".to_owned()), ColumnTypes::Int32, false),
+            ResultColumn::new("name".to_owned(), Some("users".to_owned()), ColumnTypes::String, true),
+            ResultColumn::new("id".to_owned(), Some("orders".to_owned()), ColumnTypes::Int32, true),
+        ];
+        QueryResults::new(columns, vec![
+            vec![Cell::Int32(1), Cell::String("ann".to_owned()), Cell::Int32(10)],
+            vec![Cell::Int32(2), Cell::Null, Cell::Null],
+        ])
+    }
+
+    #[test]
+    fn columns_are_found_by_name() {
+        let results = results();
+
+        assert_eq!(results.column_index("name"), Some(1));
+        assert_eq!(results.column_index("id"), Some(0));
+        assert_eq!(results.column_index("missing"), None);
+        assert_eq!(results.columns()[2].table(), Some("orders"));
+    }
+
+    #[test]
+    fn cells_are_found_by_position_or_name() {
+        let results = results();
+        assert_eq!(results.len(), 2);
+
+        let row = results.row(0).unwrap();
+        assert_eq!(row.get(1), Some(&Cell::String("ann".to_owned())));
+        assert_eq!(row.get(2), Some(&Cell::Int32(10)));
+        assert_eq!(row.get(3), None);
+        assert_eq!(row.get_by_name("name"), Some(&Cell::String("ann".to_owned())));
+        assert_eq!(row.get_by_name("id"), Some(&Cell::Int32(1)));
+        assert_eq!(row.get_by_name("missing"), None);
+
+        let names = results.rows().map(|row| row.get_by_name("name").unwrap().to_string()).collect::<Vec<String>>();
+        assert_eq!(names, vec!["ann", "NULL"]);
+        assert!(results.row(2).is_none());
+    }
+}
@@ -1,54 +1,285 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
 
-use log::info;
+use rustyline::Editor;
+use rustyline::error::ReadlineError;
 
-use learn_to_write_a_database::backend::Backend;
+use learn_to_write_a_database::backend::{Backend, QueryResults};
+use learn_to_write_a_database::backend::file::FileBackend;
+use learn_to_write_a_database::backend::format::{self, OutputFormat};
 use learn_to_write_a_database::backend::memory::InMemoryBackend;
-use learn_to_write_a_database::statements::compiler::StatementCompiler;
-use learn_to_write_a_database::statements::scanner::{Token, TokenIterator};
+use learn_to_write_a_database::statements::compiler::{CompilerError, StatementCompiler};
+use learn_to_write_a_database::statements::scanner::{ScannerError, Token, TokenIterator};
 use learn_to_write_a_database::statements::Statement;
 
+const PROMPT: &str = "db> ";
+const CONTINUATION_PROMPT: &str = "..> ";
+
+const USAGE: &str = "Usage: learn-to-write-a-database [--format table|csv|json] [DATABASE]";
+
+const HELP: &str = r#".help              Show this message
+.tables            List every table
+.schema [TABLE]    Show the statement which creates TABLE, or every table
+.mode [FORMAT]     Show or change how results are printed: table, csv or json
+.quit              Exit the shell"#;
+
+enum MetaCommandResult {
+    Continue,
+    Quit,
+}
+
 fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
     env_logger::builder().init();
 
-    let mut backend = InMemoryBackend::new(
-        HashMap::new()
-    );
+    let (path, mut format) = match parse_args() {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{}\n{}", err, USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    // Pass a path to keep the database in a file, otherwise everything is lost on exit
+    let mut backend: Box<dyn Backend> = match path {
+        Some(path) => Box::new(FileBackend::open(path)?),
+        None => Box::new(InMemoryBackend::new(HashMap::new())),
+    };
+
+    let mut editor = Editor::<()>::new();
+    let history = history_path();
+    if let Some(path) = &history {
+        // There is no history the first time the shell is started
+        let _ = editor.load_history(path);
+    }
+
+    let mut buffer = String::new();
+
+    loop {
+        let prompt = if buffer.is_empty() { PROMPT } else { CONTINUATION_PROMPT };
+
+        let line = match editor.readline(prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => {
+                // Ctrl-C abandons the statement being typed rather than the whole shell
+                buffer.clear();
+                continue;
+            }
+            Err(ReadlineError::Eof) => break,
+            Err(err) => return Err(err.into()),
+        };
+
+        if buffer.is_empty() {
+            let command = line.trim();
+            if command.is_empty() {
+                continue;
+            }
+
+            if command.starts_with('.') {
+                editor.add_history_entry(command);
+                match run_meta_command(backend.as_mut(), &mut format, command) {
+                    Ok(MetaCommandResult::Continue) => continue,
+                    Ok(MetaCommandResult::Quit) => break,
+                    Err(err) => {
+                        eprintln!("Error: {}", err);
+                        continue;
+                    }
+                }
+            }
+        }
+
+        buffer.push_str(&line);
+        buffer.push('\n');
+
+        if !is_complete(&buffer) {
+            continue;
+        }
+
+        editor.add_history_entry(buffer.trim_end());
+        run(backend.as_mut(), format, &buffer);
+        buffer.clear();
+    }
+
+    if let Some(path) = &history {
+        editor.save_history(path)?;
+    }
+
+    Ok(())
+}
+
+fn parse_args() -> std::result::Result<(Option<String>, OutputFormat), Box<dyn std::error::Error>> {
+    let mut path = None;
+    let mut format = OutputFormat::Table;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => match args.next() {
+                Some(name) => format = name.parse()?,
+                None => return Err("--format needs a value".into()),
+            },
+            _ if arg.starts_with('-') => return Err(format!("Unknown option {}", arg).into()),
+            _ if path.is_some() => return Err(format!("Unexpected argument {}", arg).into()),
+            _ => path = Some(arg),
+        }
+    }
+
+    Ok((path, format))
+}
+
+fn history_path() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".learn_to_write_a_database_history"))
+}
 
-    let query_1 = r#"
-    CREATE TABLE users (id INT, name TEXT);
-    INSERT INTO users VALUES (1, 'Phil');
-    INSERT INTO users VALUES (2, 'Kate');
-    SELECT name FROM users;
-    SELECT id FROM users;
-    SELECT * FROM users;
-    "#;
+/// A statement is complete once it ends with a semicolon outside of a string literal. Input the
+/// scanner cannot make sense of is complete as well, so the error is reported straight away.
+fn is_complete(source: &str) -> bool {
+    let mut last = None;
 
+    for token in TokenIterator::new_iterator(source.chars()) {
+        match token {
+            Ok(token) => match token.token() {
+                Token::Space | Token::NewLine => {}
+                _ => last = Some(token.into_token()),
+            },
+            Err(ScannerError::UnterminatedStringLiteral(_)) => return false,
+            Err(_) => return true,
+        }
+    }
+
+    last == Some(Token::SemiColon)
+}
+
+fn run(backend: &mut dyn Backend, format: OutputFormat, source: &str) {
+    // Statements may span several lines, so line breaks are as insignificant as spaces
     let tokens = TokenIterator::new_iterator(
-        query_1.chars()
-    ).filter(|x| *x != Token::Space);
+        source.chars()
+    ).filter(|x| !matches!(x, Ok(token) if matches!(token.token(), Token::Space | Token::NewLine)));
+
+    let compiler = StatementCompiler::new(tokens);
+
+    for maybe_statement in compiler {
+        let statement = match maybe_statement {
+            Ok(statement) => statement,
+            Err(err) => {
+                match err.downcast_ref::<CompilerError>() {
+                    Some(err) => eprintln!("{}", err.render(source)),
+                    None => eprintln!("Error: {}", err),
+                }
+                continue;
+            }
+        };
 
-    let mut compiler = StatementCompiler::new(tokens);
+        if let Err(err) = execute(backend, format, statement) {
+            eprintln!("Error: {}", err)
+        }
+    }
+}
 
-    while let Some(maybe_statement) = compiler.next() {
-        let statement = maybe_statement?;
+fn run_meta_command(backend: &mut dyn Backend, format: &mut OutputFormat, command: &str) -> std::result::Result<MetaCommandResult, Box<dyn std::error::Error>> {
+    let mut words = command.split_whitespace();
 
-        match statement {
-            Statement::Create(statement) => {
-                backend.create_table(&statement)?;
-                info!("[create_table] ok")
+    match (words.next(), words.next(), words.next()) {
+        (Some(".quit"), None, _) | (Some(".exit"), None, _) => return Ok(MetaCommandResult::Quit),
+        (Some(".help"), None, _) => println!("{}", HELP),
+        (Some(".tables"), None, _) => {
+            for table in backend.table_names() {
+                println!("{}", table);
             }
-            Statement::Insert(statement) => {
-                backend.insert(&statement)?;
-                info!("[insert] ok")
+        }
+        (Some(".schema"), None, _) => {
+            for table in backend.table_names() {
+                println!("{}", backend.table_schema(&table)?);
             }
-            Statement::Select(statement) => {
-                let result = backend.select(&statement)?;
-                info!("{:?}", result)
+        }
+        (Some(".schema"), Some(table), None) => println!("{}", backend.table_schema(table)?),
+        (Some(".mode"), None, _) => println!("{}", format),
+        (Some(".mode"), Some(name), None) => *format = name.parse()?,
+        _ => return Err(format!("Unrecognised command {:?}, enter .help for a list of commands", command).into()),
+    }
+
+    Ok(MetaCommandResult::Continue)
+}
+
+fn execute(backend: &mut dyn Backend, format: OutputFormat, statement: Statement) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    match statement {
+        Statement::Create(statement) => {
+            if backend.create_table(&statement)? {
+                println!("Created table {}", statement.table_name())
+            } else {
+                println!("Table {} already exists, skipping", statement.table_name())
             }
         }
+        Statement::Insert(statement) => {
+            let inserted = backend.insert(&statement)?;
+            println!("Inserted {} rows", inserted)
+        }
+        Statement::Select(statement) => print_results(&backend.select(&statement)?, format),
+        Statement::Update(statement) => {
+            let affected = backend.update(&statement)?;
+            println!("Updated {} rows", affected)
+        }
+        Statement::Delete(statement) => {
+            let affected = backend.delete(&statement)?;
+            println!("Deleted {} rows", affected)
+        }
+        Statement::Drop(statement) => {
+            if backend.drop_table(&statement)? {
+                println!("Dropped table {}", statement.table_name())
+            } else {
+                println!("Table {} does not exist, skipping", statement.table_name())
+            }
+        }
+        Statement::Truncate(statement) => {
+            let removed = backend.truncate(&statement)?;
+            println!("Truncated table {}, removing {} rows", statement.table_name(), removed)
+        }
+        Statement::Alter(statement) => {
+            backend.alter_table(&statement)?;
+            println!("Altered table {}", statement.table_name())
+        }
+        Statement::ShowTables => print_results(&backend.show_tables()?, format),
+        Statement::Describe(statement) => print_results(&backend.describe(&statement)?, format),
     }
 
     Ok(())
 }
 
+fn print_results(results: &QueryResults, format: OutputFormat) {
+    print!("{}", format::render(results, format));
+    if format == OutputFormat::Table {
+        println!("({} rows)", results.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statements_are_complete_at_a_semicolon_outside_strings() {
+        assert!(is_complete("SELECT * FROM users;"));
+        assert!(is_complete("SELECT *\nFROM users;\n"));
+        assert!(!is_complete("SELECT * FROM users"));
+        assert!(!is_complete("INSERT INTO users VALUES ('a;"));
+        assert!(is_complete("INSERT INTO users VALUES ('a;\nb');"));
+        assert!(!is_complete(""));
+        // The scanner fails on `#`, which is reported straight away
+        assert!(is_complete("SELECT #"));
+    }
+
+    #[test]
+    fn meta_commands_change_the_output_format_and_quit() {
+        let mut backend = InMemoryBackend::new(HashMap::new());
+        let mut format = OutputFormat::Table;
+
+        assert!(matches!(run_meta_command(&mut backend, &mut format, ".mode csv"), Ok(MetaCommandResult::Continue)));
+        assert_eq!(format, OutputFormat::Csv);
+        assert!(run_meta_command(&mut backend, &mut format, ".mode xml").is_err());
+        assert_eq!(format, OutputFormat::Csv);
+
+        assert!(run_meta_command(&mut backend, &mut format, ".schema missing").is_err());
+        assert!(run_meta_command(&mut backend, &mut format, ".tables extra").is_err());
+        assert!(matches!(run_meta_command(&mut backend, &mut format, " .quit "), Ok(MetaCommandResult::Quit)));
+        assert!(matches!(run_meta_command(&mut backend, &mut format, ".exit"), Ok(MetaCommandResult::Quit)));
+    }
+}
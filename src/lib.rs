@@ -1,5 +1,6 @@
 pub mod backend;
 pub mod statements;
+pub mod types;
 
 pub type Error = Box<dyn std::error::Error>;
 pub type Result<T> = std::result::Result<T, Error>;
\ No newline at end of file
@@ -0,0 +1,32 @@
+use std::borrow::Borrow;
+
+use crate::statements::create::ColumnDefinition;
+
+#[derive(Debug)]
+pub enum AlterTableAction {
+    AddColumn(ColumnDefinition),
+    DropColumn(String),
+    /// Renames the first column to the second name.
+    RenameColumn(String, String),
+    RenameTable(String),
+}
+
+#[derive(Debug)]
+pub struct AlterTableStatement {
+    table: String,
+    action: AlterTableAction,
+}
+
+impl AlterTableStatement {
+    pub fn new(table: String, action: AlterTableAction) -> Self {
+        AlterTableStatement { table, action }
+    }
+
+    pub fn table_name(&self) -> &str {
+        self.table.borrow()
+    }
+
+    pub fn action(&self) -> &AlterTableAction {
+        &self.action
+    }
+}
@@ -1,25 +1,129 @@
-use std::iter::Peekable;
+use std::collections::VecDeque;
+use std::convert::TryFrom;
 
-use log::{debug, trace};
+use log::trace;
 
 use crate::statements::{insert, Statement, select};
-use crate::statements::create::{ColumnDefinition, CreateTableStatement, DataType};
-use crate::statements::insert::InsertStatement;
-use crate::statements::scanner::{KeywordToken, Token};
-use crate::statements::select::SelectStatement;
+use crate::statements::alter::{AlterTableAction, AlterTableStatement};
+use crate::statements::create::{ColumnConstraint, ColumnDefinition, CreateTableStatement, DataType};
+use crate::statements::delete::DeleteStatement;
+use crate::statements::describe::DescribeStatement;
+use crate::statements::drop::DropTableStatement;
+use crate::statements::expression::{Aggregate, AggregateFunction, BinaryOperator, ColumnReference, Expression, Literal, UnaryOperator};
+use crate::statements::insert::{InsertSource, InsertStatement};
+use crate::statements::scanner::{KeywordToken, ScannerError, Span, SpannedToken, Token};
+use crate::statements::select::{FromClause, Join, JoinKind, Limit, NullsOrder, OrderBy, SelectStatement, SortDirection, TableReference};
+use crate::statements::truncate::TruncateTableStatement;
+use crate::statements::update::{Assignment, UpdateStatement};
+use crate::types::decimal::{self, Decimal};
+use crate::types::temporal::{Date, Interval, Time, Timestamp};
 
-pub struct StatementCompiler<T: Iterator<Item=Token>> {
-    inner: Peekable<T>,
+/// A compilation failure annotated with the span of the token the compiler was looking at.
+#[derive(Debug)]
+pub struct CompilerError {
+    message: String,
+    span: Span,
 }
 
-impl<T: Iterator<Item=Token>> StatementCompiler<T> {
+impl CompilerError {
+    pub fn new(message: String, span: Span) -> Self {
+        CompilerError { message, span }
+    }
+
+    pub fn message(&self) -> &str {
+        self.message.as_str()
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// Renders the error together with a caret-annotated snippet of the statement it came from.
+    pub fn render(&self, source: &str) -> String {
+        format!("{}\n{}", self, self.span.render(source))
+    }
+}
+
+impl std::fmt::Display for CompilerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at line {}, column {}", self.message, self.span.start().line(), self.span.start().column())
+    }
+}
+
+impl std::error::Error for CompilerError {}
+
+impl From<ScannerError> for CompilerError {
+    fn from(err: ScannerError) -> Self {
+        let span = err.span().unwrap_or_default();
+        let message = match err {
+            ScannerError::Error(message) => message,
+            ScannerError::UnexpectedCharacter(character, _) => format!("Unexpected character {:?}", character),
+            ScannerError::UnterminatedStringLiteral(_) => "Unterminated string literal".to_string(),
+            ScannerError::MalformedNumber(_) => "Malformed number".to_string(),
+        };
+        CompilerError::new(message, span)
+    }
+}
+
+/// Lets the compiler look more than one token ahead, as it needs to tell `users.*` from `users.id`
+/// before compiling either.
+struct Lookahead<I: Iterator> {
+    inner: I,
+    buffer: VecDeque<I::Item>,
+}
+
+impl<I: Iterator> Lookahead<I> {
+    fn new(inner: I) -> Self {
+        Lookahead { inner, buffer: VecDeque::new() }
+    }
+
+    fn peek(&mut self) -> Option<&I::Item> {
+        self.peek_nth(0)
+    }
+
+    /// Looks at the item `n` places after the next one without taking any of them.
+    fn peek_nth(&mut self, n: usize) -> Option<&I::Item> {
+        while self.buffer.len() <= n {
+            self.buffer.push_back(self.inner.next()?);
+        }
+        self.buffer.get(n)
+    }
+}
+
+impl<I: Iterator> Iterator for Lookahead<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.buffer.pop_front().or_else(|| self.inner.next())
+    }
+}
+
+pub struct StatementCompiler<T: Iterator<Item=Result<SpannedToken, ScannerError>>> {
+    inner: Lookahead<T>,
+    span: Span,
+    at_end_of_statement: bool,
+}
+
+impl<T: Iterator<Item=Result<SpannedToken, ScannerError>>> StatementCompiler<T> {
     pub fn new(tokens: T) -> Self {
         StatementCompiler {
-            inner: tokens.peekable()
+            inner: Lookahead::new(tokens),
+            span: Span::default(),
+            at_end_of_statement: false,
         }
     }
 
     fn compile_create_table(&mut self) -> crate::Result<Statement> {
+        let if_not_exists = match self.peek_token() {
+            Some(Token::Keyword(KeywordToken::IF)) => {
+                self.skip();
+                self.assert_next_token_is(Token::Keyword(KeywordToken::NOT))?;
+                self.assert_next_token_is(Token::Keyword(KeywordToken::EXISTS))?;
+                true
+            }
+            _ => false,
+        };
+
         let identifier = self.assert_next_identifier()?;
 
         return Ok(
@@ -27,69 +131,192 @@ impl<T: Iterator<Item=Token>> StatementCompiler<T> {
                 CreateTableStatement::new(
                     identifier,
                     self.compile_create_table_column_definitions()?,
+                    if_not_exists,
                 )
             )
         );
     }
 
     fn compile_create_table_column_definitions(&mut self) -> crate::Result<Vec<ColumnDefinition>> {
-        self.repeat_statement(|stream| {
-            let name = stream.assert_next_identifier()?;
-            let data_type = stream.read_data_type()?;
-
-            Ok(ColumnDefinition::new(
-                name,
-                data_type,
-            ))
-        })
+        self.repeat_statement(|stream| stream.compile_create_table_column_definition())
     }
 
     fn compile_create_table_column_definition(&mut self) -> crate::Result<ColumnDefinition> {
         let name = self.assert_next_identifier()?;
         let data_type = self.read_data_type()?;
+        let constraints = self.compile_column_constraints()?;
 
         Ok(ColumnDefinition::new(
             name,
             data_type,
+            constraints,
         ))
     }
 
+    fn compile_column_constraints(&mut self) -> crate::Result<Vec<ColumnConstraint>> {
+        let mut constraints = Vec::new();
+
+        loop {
+            let constraint = match self.peek_token() {
+                Some(Token::Keyword(KeywordToken::PRIMARY)) => {
+                    self.skip();
+                    self.assert_next_token_is(Token::Keyword(KeywordToken::KEY))?;
+                    ColumnConstraint::PrimaryKey
+                }
+                Some(Token::Keyword(KeywordToken::NOT)) => {
+                    self.skip();
+                    self.assert_next_token_is(Token::Keyword(KeywordToken::NULL))?;
+                    ColumnConstraint::NotNull
+                }
+                Some(Token::Keyword(KeywordToken::UNIQUE)) => {
+                    self.skip();
+                    ColumnConstraint::Unique
+                }
+                Some(Token::Keyword(KeywordToken::DEFAULT)) => {
+                    self.skip();
+                    ColumnConstraint::Default(self.compile_literal()?)
+                }
+                _ => return Ok(constraints),
+            };
+            constraints.push(constraint);
+        }
+    }
+
+    fn compile_literal(&mut self) -> crate::Result<Literal> {
+        match self.next_token()? {
+            Some(Token::Number(text)) => parse_number(&text, false),
+            Some(Token::Minus) => match self.next_token()? {
+                Some(Token::Number(text)) => parse_number(&text, true),
+                Some(token) => Err(format!("Expected a number after - but got {:?}", token).into()),
+                None => Err("Expected a number after - but got nothing".into()),
+            },
+            Some(Token::StringLiteral(value)) => Ok(Literal::String(value)),
+            Some(Token::Keyword(KeywordToken::TRUE)) => Ok(Literal::Boolean(true)),
+            Some(Token::Keyword(KeywordToken::FALSE)) => Ok(Literal::Boolean(false)),
+            Some(Token::Keyword(KeywordToken::NULL)) => Ok(Literal::Null),
+            Some(Token::Keyword(keyword @ (KeywordToken::DATE | KeywordToken::TIME | KeywordToken::TIMESTAMP | KeywordToken::INTERVAL))) => {
+                self.compile_typed_literal(keyword)
+            }
+            Some(token) => Err(format!("Expected a literal value but got {:?}", token).into()),
+            None => Err("Expected a literal value but got nothing".into()),
+        }
+    }
+
+    /// Compiles the string after DATE, TIME, TIMESTAMP or INTERVAL, as in `DATE '2024-01-31'`. An
+    /// interval may name the unit of its quantity after the string, as in `INTERVAL '1' MONTH`.
+    fn compile_typed_literal(&mut self, keyword: KeywordToken) -> crate::Result<Literal> {
+        let text = match self.next_token()? {
+            Some(Token::StringLiteral(text)) => text,
+            Some(token) => return Err(format!("Expected a string after {:?} but got {:?}", keyword, token).into()),
+            None => return Err(format!("Expected a string after {:?} but got nothing", keyword).into()),
+        };
+
+        match keyword {
+            KeywordToken::DATE => Ok(Literal::Date(Date::parse(&text)?)),
+            KeywordToken::TIME => Ok(Literal::Time(Time::parse(&text)?)),
+            KeywordToken::TIMESTAMP => Ok(Literal::Timestamp(Timestamp::parse(&text)?)),
+            KeywordToken::INTERVAL => match self.peek_token() {
+                Some(Token::Keyword(unit @ (KeywordToken::YEAR | KeywordToken::MONTH | KeywordToken::DAY
+                    | KeywordToken::HOUR | KeywordToken::MINUTE | KeywordToken::SECOND))) => {
+                    let unit = unit.name();
+                    self.skip();
+                    Ok(Literal::Interval(Interval::parse_in(&text, &unit)?))
+                }
+                _ => Ok(Literal::Interval(Interval::parse(&text)?)),
+            },
+            keyword => unreachable!("{:?} does not start a typed literal", keyword),
+        }
+    }
+
     fn read_data_type(&mut self) -> crate::Result<DataType> {
-        match self.inner.next() {
+        match self.next_token()? {
             Some(Token::Keyword(KeywordToken::INT)) => Ok(DataType::Int32),
+            Some(Token::Keyword(KeywordToken::BIGINT)) => Ok(DataType::Int64),
+            Some(Token::Keyword(KeywordToken::DOUBLE)) | Some(Token::Keyword(KeywordToken::FLOAT)) => Ok(DataType::Double),
+            Some(Token::Keyword(KeywordToken::BOOLEAN)) => Ok(DataType::Boolean),
             Some(Token::Keyword(KeywordToken::TEXT)) => Ok(DataType::String),
+            Some(Token::Keyword(KeywordToken::DECIMAL)) | Some(Token::Keyword(KeywordToken::NUMERIC)) => self.compile_decimal_type(),
+            Some(Token::Keyword(KeywordToken::DATE)) => Ok(DataType::Date),
+            Some(Token::Keyword(KeywordToken::TIME)) => Ok(DataType::Time),
+            Some(Token::Keyword(KeywordToken::TIMESTAMP)) => Ok(DataType::Timestamp),
             Some(token) => Err(format!("Expected a datatype but got {:?}", token).into()),
             None => Err("Expected an identifier but got nothing".into()),
         }
     }
 
+    /// Compiles the `(precision, scale)` after DECIMAL, where the scale defaults to 0. A DECIMAL
+    /// on its own has the default precision and holds whole numbers.
+    fn compile_decimal_type(&mut self) -> crate::Result<DataType> {
+        if self.peek_token() != Some(&Token::LeftBracket) {
+            return Ok(DataType::Decimal(decimal::DEFAULT_PRECISION, 0));
+        }
+        self.skip();
+        let precision = self.assert_next_type_size("precision")?;
+
+        let scale = match self.peek_token() {
+            Some(Token::Comma) => {
+                self.skip();
+                self.assert_next_type_size("scale")?
+            }
+            _ => 0,
+        };
+
+        if !(1..=decimal::MAX_PRECISION).contains(&precision) {
+            return Err(format!("The precision of a DECIMAL must be between 1 and {} but got {}", decimal::MAX_PRECISION, precision).into());
+        }
+        if scale > precision {
+            return Err(format!("The scale of a DECIMAL cannot be more than its precision of {} but got {}", precision, scale).into());
+        }
+
+        self.assert_next_token_is(Token::RightBracket)?;
+
+        Ok(DataType::Decimal(precision, scale))
+    }
+
+    fn assert_next_type_size(&mut self, name: &str) -> crate::Result<u8> {
+        match self.next_token()? {
+            Some(Token::Number(text)) => text.parse::<u8>()
+                .map_err(|_| format!("Expected a {} but got {}", name, text).into()),
+            Some(token) => Err(format!("Expected a {} but got {:?}", name, token).into()),
+            None => Err(format!("Expected a {} but got nothing", name).into()),
+        }
+    }
+
     fn compile_insert(&mut self) -> crate::Result<Statement> {
         self.assert_next_token_is(Token::Keyword(KeywordToken::INTO))?;
 
         let identifier = self.assert_next_identifier()?;
 
-        self.assert_next_token_is(Token::Keyword(KeywordToken::VALUES))?;
+        let columns = match self.peek_token() {
+            Some(Token::LeftBracket) => Some(self.repeat_statement(|stream| stream.assert_next_identifier())?),
+            _ => None,
+        };
 
-        let expressions = self.repeat_statement(|stream| {
-            match stream.inner.next() {
-                Some(Token::U32(value)) => {
-                    Ok(insert::Expression::Literal(insert::Literal::U32(value)))
-                }
-                Some(Token::Apostrophe) => {
-                    let string_literal = stream.assert_next_identifier()?;
-                    stream.assert_next_token_is(Token::Apostrophe)?;
+        let source = match self.next_token()? {
+            Some(Token::Keyword(KeywordToken::VALUES)) => {
+                InsertSource::Values(self.repeat_vargs_statement(|stream| stream.compile_insert_values())?)
+            }
+            Some(Token::Keyword(KeywordToken::SELECT)) => InsertSource::Select(Box::new(self.compile_select_statement()?)),
+            Some(token) => return Err(format!("Expected VALUES or SELECT but got {:?}", token).into()),
+            None => return Err("Expected VALUES or SELECT but got nothing".into()),
+        };
 
-                    Ok(insert::Expression::Literal(insert::Literal::String(string_literal)))
+        Ok(Statement::Insert(InsertStatement::new(identifier, columns, source)))
+    }
+
+    fn compile_insert_values(&mut self) -> crate::Result<Vec<insert::Expression>> {
+        self.repeat_statement(|stream| {
+            match stream.peek_token() {
+                Some(Token::Keyword(KeywordToken::DEFAULT)) => {
+                    stream.skip();
+                    Ok(insert::Expression::Default)
                 }
-                Some(unhandled) => Err(format!("Unhandled token: {:?}", unhandled).into()),
-                None => Err("Expected a literal value but got nothing".into())
+                _ => Ok(insert::Expression::Literal(stream.compile_literal()?)),
             }
-        })?;
-
-        Ok(Statement::Insert(InsertStatement::new(identifier, expressions)))
+        })
     }
 
-    fn repeat_statement<F, R>(&mut self, mut f: F) -> crate::Result<Vec<R>>
+    fn repeat_statement<F, R>(&mut self, f: F) -> crate::Result<Vec<R>>
         where
             F: FnMut(&mut Self) -> crate::Result<R>,
     {
@@ -110,7 +337,7 @@ impl<T: Iterator<Item=Token>> StatementCompiler<T> {
 
         results.push(f(self)?);
 
-        while let Some(Token::Comma) = self.inner.peek() {
+        while let Some(Token::Comma) = self.peek_token() {
             self.skip();
             results.push(f(self)?);
         }
@@ -126,63 +353,787 @@ impl<T: Iterator<Item=Token>> StatementCompiler<T> {
     }
 
     fn compile_select(&mut self) -> crate::Result<Statement> {
-        let identifiers = self.repeat_vargs_statement(|stream| {
-            match stream.inner.next() {
-                Some(Token::Identifier(identifier)) => Ok(select::Expression::Column(identifier)),
-                Some(Token::Asterisk) => Ok(select::Expression::All),
-                Some(unhandled) => Err(format!("Unhandled token: {:?}", unhandled).into()),
-                None => Err("Expected a literal value but got nothing".into())
+        Ok(Statement::Select(self.compile_select_statement()?))
+    }
+
+    fn compile_select_statement(&mut self) -> crate::Result<SelectStatement> {
+        let identifiers = self.repeat_vargs_statement(|stream| stream.compile_select_item())?;
+
+        self.assert_next_token_is(Token::Keyword(KeywordToken::FROM))?;
+
+        let from = self.compile_from_clause()?;
+
+        let predicate = self.compile_where_clause()?;
+
+        let group_by = self.compile_group_by_clause()?;
+
+        let having = self.compile_having_clause()?;
+
+        let order_by = self.compile_order_by_clause()?;
+
+        let limit = self.compile_limit_clause()?;
+
+        Ok(SelectStatement::new(identifiers, from, predicate, group_by, having, order_by, limit))
+    }
+
+    /// Compiles one item of the list of values a SELECT returns, which is `*`, `table.*` or an
+    /// expression named by an optional alias, written either as `price * 2 AS total` or `price * 2 total`.
+    fn compile_select_item(&mut self) -> crate::Result<select::Expression> {
+        if let Some(Token::Asterisk) = self.peek_token() {
+            self.skip();
+            return Ok(select::Expression::All);
+        }
+
+        let all_of = self.peek_token().is_some_and(is_identifier)
+            && matches!(self.peek_nth_token(1), Some(Token::Dot))
+            && matches!(self.peek_nth_token(2), Some(Token::Asterisk));
+        if all_of {
+            let table = self.assert_next_identifier()?;
+            self.skip();
+            self.skip();
+            return Ok(select::Expression::AllOf(table));
+        }
+
+        let expression = self.compile_expression()?;
+
+        let alias = match self.peek_token() {
+            Some(Token::Keyword(KeywordToken::AS)) => {
+                self.skip();
+                Some(self.assert_next_identifier()?)
             }
+            Some(Token::Identifier(_)) => Some(self.assert_next_identifier()?),
+            _ => None,
+        };
+
+        Ok(select::Expression::Value(expression, alias))
+    }
+
+    fn compile_from_clause(&mut self) -> crate::Result<FromClause> {
+        let table = self.compile_table_reference()?;
+
+        let mut joins = Vec::new();
+        loop {
+            let kind = match self.peek_token() {
+                Some(Token::Keyword(KeywordToken::JOIN)) => JoinKind::Inner,
+                Some(Token::Keyword(KeywordToken::INNER)) => {
+                    self.skip();
+                    JoinKind::Inner
+                }
+                Some(Token::Keyword(KeywordToken::LEFT)) => {
+                    self.skip();
+                    if let Some(Token::Keyword(KeywordToken::OUTER)) = self.peek_token() {
+                        self.skip();
+                    }
+                    JoinKind::Left
+                }
+                _ => break,
+            };
+            self.assert_next_token_is(Token::Keyword(KeywordToken::JOIN))?;
+
+            let table = self.compile_table_reference()?;
+
+            self.assert_next_token_is(Token::Keyword(KeywordToken::ON))?;
+            let on = self.compile_expression()?;
+
+            joins.push(Join::new(kind, table, on));
+        }
+
+        Ok(FromClause::new(table, joins))
+    }
+
+    /// Compiles a table name with an optional alias, written either as `users AS u` or `users u`.
+    fn compile_table_reference(&mut self) -> crate::Result<TableReference> {
+        let name = self.assert_next_identifier()?;
+
+        let alias = match self.peek_token() {
+            Some(Token::Keyword(KeywordToken::AS)) => {
+                self.skip();
+                Some(self.assert_next_identifier()?)
+            }
+            Some(Token::Identifier(_)) => Some(self.assert_next_identifier()?),
+            _ => None,
+        };
+
+        Ok(TableReference::new(name, alias))
+    }
+
+    fn compile_group_by_clause(&mut self) -> crate::Result<Vec<Expression>> {
+        if let Some(Token::Keyword(KeywordToken::GROUP)) = self.peek_token() {
+            self.skip();
+            self.assert_next_token_is(Token::Keyword(KeywordToken::BY))?;
+            return self.repeat_vargs_statement(|stream| stream.compile_expression());
+        }
+        Ok(Vec::new())
+    }
+
+    fn compile_having_clause(&mut self) -> crate::Result<Option<Expression>> {
+        if let Some(Token::Keyword(KeywordToken::HAVING)) = self.peek_token() {
+            self.skip();
+            return Ok(Some(self.compile_expression()?));
+        }
+        Ok(None)
+    }
+
+    fn compile_limit_clause(&mut self) -> crate::Result<Option<Limit>> {
+        if let Some(Token::Keyword(KeywordToken::LIMIT)) = self.peek_token() {
+            self.skip();
+            let count = self.assert_next_count()?;
+
+            let offset = match self.peek_token() {
+                Some(Token::Keyword(KeywordToken::OFFSET)) => {
+                    self.skip();
+                    self.assert_next_count()?
+                }
+                _ => 0,
+            };
+
+            return Ok(Some(Limit::new(count, offset)));
+        }
+        Ok(None)
+    }
+
+    /// Compiles the bracketed arguments of a call to the aggregate function `name`.
+    fn compile_aggregate(&mut self, name: &str) -> crate::Result<Aggregate> {
+        let function = AggregateFunction::try_from(name)?;
+
+        self.assert_next_token_is(Token::LeftBracket)?;
+
+        let argument = match self.peek_token() {
+            Some(Token::Asterisk) if function == AggregateFunction::Count => {
+                self.skip();
+                None
+            }
+            _ => Some(self.compile_expression()?),
+        };
+
+        self.assert_next_token_is(Token::RightBracket)?;
+
+        Ok(Aggregate::new(function, argument))
+    }
+
+    fn assert_next_count(&mut self) -> crate::Result<usize> {
+        match self.next_token()? {
+            Some(Token::Number(text)) => text.parse::<usize>()
+                .map_err(|_| format!("Expected a number of rows but got {}", text).into()),
+            Some(token) => Err(format!("Expected a number of rows but got {:?}", token).into()),
+            None => Err("Expected a number of rows but got nothing".into()),
+        }
+    }
+
+    fn compile_order_by_clause(&mut self) -> crate::Result<Vec<OrderBy>> {
+        if let Some(Token::Keyword(KeywordToken::ORDER)) = self.peek_token() {
+            self.skip();
+            self.assert_next_token_is(Token::Keyword(KeywordToken::BY))?;
+            return self.repeat_vargs_statement(|stream| stream.compile_order_by_item());
+        }
+        Ok(Vec::new())
+    }
+
+    fn compile_order_by_item(&mut self) -> crate::Result<OrderBy> {
+        let expression = self.compile_expression()?;
+
+        let direction = match self.peek_token() {
+            Some(Token::Keyword(KeywordToken::ASC)) => {
+                self.skip();
+                SortDirection::Ascending
+            }
+            Some(Token::Keyword(KeywordToken::DESC)) => {
+                self.skip();
+                SortDirection::Descending
+            }
+            _ => SortDirection::Ascending,
+        };
+
+        let nulls = match self.peek_token() {
+            Some(Token::Keyword(KeywordToken::NULLS)) => {
+                self.skip();
+                match self.next_token()? {
+                    Some(Token::Keyword(KeywordToken::FIRST)) => Some(NullsOrder::First),
+                    Some(Token::Keyword(KeywordToken::LAST)) => Some(NullsOrder::Last),
+                    Some(token) => return Err(format!("Expected FIRST or LAST but got {:?}", token).into()),
+                    None => return Err("Expected FIRST or LAST but got nothing".into()),
+                }
+            }
+            _ => None,
+        };
+
+        Ok(OrderBy::new(expression, direction, nulls))
+    }
+
+    fn compile_update(&mut self) -> crate::Result<Statement> {
+        let table = self.assert_next_identifier()?;
+
+        self.assert_next_token_is(Token::Keyword(KeywordToken::SET))?;
+
+        let assignments = self.repeat_vargs_statement(|stream| {
+            let column = stream.assert_next_identifier()?;
+            stream.assert_next_token_is(Token::Assignment)?;
+            let value = stream.compile_expression()?;
+
+            Ok(Assignment::new(column, value))
         })?;
 
+        let predicate = self.compile_where_clause()?;
+
+        Ok(Statement::Update(UpdateStatement::new(table, assignments, predicate)))
+    }
+
+    fn compile_delete(&mut self) -> crate::Result<Statement> {
         self.assert_next_token_is(Token::Keyword(KeywordToken::FROM))?;
 
         let table = self.assert_next_identifier()?;
 
-        Ok(Statement::Select(SelectStatement::new(identifiers, table)))
+        let predicate = self.compile_where_clause()?;
+
+        Ok(Statement::Delete(DeleteStatement::new(table, predicate)))
+    }
+
+    fn compile_drop(&mut self) -> crate::Result<Statement> {
+        self.assert_next_token_is(Token::Keyword(KeywordToken::TABLE))?;
+
+        let if_exists = match self.peek_token() {
+            Some(Token::Keyword(KeywordToken::IF)) => {
+                self.skip();
+                self.assert_next_token_is(Token::Keyword(KeywordToken::EXISTS))?;
+                true
+            }
+            _ => false,
+        };
+
+        let table = self.assert_next_identifier()?;
+
+        Ok(Statement::Drop(DropTableStatement::new(table, if_exists)))
+    }
+
+    fn compile_truncate(&mut self) -> crate::Result<Statement> {
+        if let Some(Token::Keyword(KeywordToken::TABLE)) = self.peek_token() {
+            self.skip();
+        }
+
+        let table = self.assert_next_identifier()?;
+
+        Ok(Statement::Truncate(TruncateTableStatement::new(table)))
+    }
+
+    fn compile_alter(&mut self) -> crate::Result<Statement> {
+        self.assert_next_token_is(Token::Keyword(KeywordToken::TABLE))?;
+
+        let table = self.assert_next_identifier()?;
+
+        let action = match self.next_token()? {
+            Some(Token::Keyword(KeywordToken::ADD)) => {
+                self.skip_optional_column_keyword();
+                AlterTableAction::AddColumn(self.compile_create_table_column_definition()?)
+            }
+            Some(Token::Keyword(KeywordToken::DROP)) => {
+                self.skip_optional_column_keyword();
+                AlterTableAction::DropColumn(self.assert_next_identifier()?)
+            }
+            Some(Token::Keyword(KeywordToken::RENAME)) => {
+                // `RENAME to TO name` renames a column called `to` rather than the table
+                let renames_table = matches!(self.peek_token(), Some(Token::Keyword(KeywordToken::TO)))
+                    && !matches!(self.peek_nth_token(1), Some(Token::Keyword(KeywordToken::TO)));
+
+                if renames_table {
+                    self.skip();
+                    AlterTableAction::RenameTable(self.assert_next_identifier()?)
+                } else {
+                    self.skip_optional_column_keyword();
+                    let column = self.assert_next_identifier()?;
+                    self.assert_next_token_is(Token::Keyword(KeywordToken::TO))?;
+                    AlterTableAction::RenameColumn(column, self.assert_next_identifier()?)
+                }
+            }
+            Some(token) => return Err(format!("Expected ADD, DROP or RENAME but got {:?}", token).into()),
+            None => return Err("Expected ADD, DROP or RENAME but got nothing".into()),
+        };
+
+        Ok(Statement::Alter(AlterTableStatement::new(table, action)))
+    }
+
+    fn compile_show(&mut self) -> crate::Result<Statement> {
+        self.assert_next_token_is(Token::Keyword(KeywordToken::TABLES))?;
+
+        Ok(Statement::ShowTables)
+    }
+
+    fn compile_describe(&mut self) -> crate::Result<Statement> {
+        let table = self.assert_next_identifier()?;
+
+        Ok(Statement::Describe(DescribeStatement::new(table)))
+    }
+
+    /// The COLUMN in `ADD COLUMN`, `DROP COLUMN` and `RENAME COLUMN` may be left out, in which case
+    /// a COLUMN not followed by a name is the name of the column itself, as in `DROP column`.
+    fn skip_optional_column_keyword(&mut self) {
+        let is_keyword = matches!(self.peek_token(), Some(Token::Keyword(KeywordToken::COLUMN)))
+            && self.peek_nth_token(1).is_some_and(is_identifier);
+        if is_keyword {
+            self.skip();
+        }
+    }
+
+    fn compile_where_clause(&mut self) -> crate::Result<Option<Expression>> {
+        if let Some(Token::Keyword(KeywordToken::WHERE)) = self.peek_token() {
+            self.skip();
+            return Ok(Some(self.compile_expression()?));
+        }
+        Ok(None)
+    }
+
+    fn compile_expression(&mut self) -> crate::Result<Expression> {
+        self.compile_or_expression()
+    }
+
+    fn compile_or_expression(&mut self) -> crate::Result<Expression> {
+        let mut expression = self.compile_and_expression()?;
+
+        while let Some(Token::Keyword(KeywordToken::OR)) = self.peek_token() {
+            self.skip();
+            let right = self.compile_and_expression()?;
+            expression = Expression::Binary(Box::new(expression), BinaryOperator::Or, Box::new(right));
+        }
+
+        Ok(expression)
+    }
+
+    fn compile_and_expression(&mut self) -> crate::Result<Expression> {
+        let mut expression = self.compile_not_expression()?;
+
+        while let Some(Token::Keyword(KeywordToken::AND)) = self.peek_token() {
+            self.skip();
+            let right = self.compile_not_expression()?;
+            expression = Expression::Binary(Box::new(expression), BinaryOperator::And, Box::new(right));
+        }
+
+        Ok(expression)
+    }
+
+    fn compile_not_expression(&mut self) -> crate::Result<Expression> {
+        if let Some(Token::Keyword(KeywordToken::NOT)) = self.peek_token() {
+            self.skip();
+            let operand = self.compile_not_expression()?;
+            return Ok(Expression::Unary(UnaryOperator::Not, Box::new(operand)));
+        }
+        self.compile_comparison_expression()
+    }
+
+    fn compile_comparison_expression(&mut self) -> crate::Result<Expression> {
+        let left = self.compile_additive_expression()?;
+
+        if let Some(Token::Keyword(KeywordToken::IS)) = self.peek_token() {
+            self.skip();
+            let operator = match self.peek_token() {
+                Some(Token::Keyword(KeywordToken::NOT)) => {
+                    self.skip();
+                    UnaryOperator::IsNotNull
+                }
+                _ => UnaryOperator::IsNull,
+            };
+            self.assert_next_token_is(Token::Keyword(KeywordToken::NULL))?;
+
+            return Ok(Expression::Unary(operator, Box::new(left)));
+        }
+
+        let operator = match self.peek_token() {
+            Some(Token::Assignment) => BinaryOperator::Equal,
+            Some(Token::NotEqual) => BinaryOperator::NotEqual,
+            Some(Token::LessThan) => BinaryOperator::LessThan,
+            Some(Token::LessThanOrEqual) => BinaryOperator::LessThanOrEqual,
+            Some(Token::GreaterThan) => BinaryOperator::GreaterThan,
+            Some(Token::GreaterThanOrEqual) => BinaryOperator::GreaterThanOrEqual,
+            _ => return Ok(left),
+        };
+        self.skip();
+
+        let right = self.compile_additive_expression()?;
+
+        Ok(Expression::Binary(Box::new(left), operator, Box::new(right)))
+    }
+
+    fn compile_additive_expression(&mut self) -> crate::Result<Expression> {
+        let mut expression = self.compile_multiplicative_expression()?;
+
+        loop {
+            let operator = match self.peek_token() {
+                Some(Token::Plus) => BinaryOperator::Add,
+                Some(Token::Minus) => BinaryOperator::Subtract,
+                _ => return Ok(expression),
+            };
+            self.skip();
+
+            let right = self.compile_multiplicative_expression()?;
+            expression = Expression::Binary(Box::new(expression), operator, Box::new(right));
+        }
+    }
+
+    fn compile_multiplicative_expression(&mut self) -> crate::Result<Expression> {
+        let mut expression = self.compile_negation_expression()?;
+
+        loop {
+            let operator = match self.peek_token() {
+                Some(Token::Asterisk) => BinaryOperator::Multiply,
+                Some(Token::Slash) => BinaryOperator::Divide,
+                _ => return Ok(expression),
+            };
+            self.skip();
+
+            let right = self.compile_negation_expression()?;
+            expression = Expression::Binary(Box::new(expression), operator, Box::new(right));
+        }
+    }
+
+    fn compile_negation_expression(&mut self) -> crate::Result<Expression> {
+        // A minus in front of a number is part of the literal, so the smallest BIGINT can be written
+        if let Some(Token::Minus) = self.peek_token() {
+            if !matches!(self.peek_nth_token(1), Some(Token::Number(_))) {
+                self.skip();
+                let operand = self.compile_negation_expression()?;
+                return Ok(Expression::Unary(UnaryOperator::Negate, Box::new(operand)));
+            }
+        }
+        self.compile_primary_expression()
+    }
+
+    fn compile_primary_expression(&mut self) -> crate::Result<Expression> {
+        // The type words are only the start of a literal when a string follows them, so columns can
+        // still be called `date` or `timestamp`
+        let literal = matches!(
+            self.peek_token(),
+            Some(Token::Number(_) | Token::StringLiteral(_))
+                | Some(Token::Keyword(KeywordToken::NULL | KeywordToken::TRUE | KeywordToken::FALSE))
+        ) || (
+            matches!(self.peek_token(), Some(Token::Minus)) && matches!(self.peek_nth_token(1), Some(Token::Number(_)))
+        ) || (
+            matches!(self.peek_token(), Some(Token::Keyword(KeywordToken::DATE | KeywordToken::TIME | KeywordToken::TIMESTAMP | KeywordToken::INTERVAL)))
+                && matches!(self.peek_nth_token(1), Some(Token::StringLiteral(_)))
+        );
+        if literal {
+            return Ok(Expression::Literal(self.compile_literal()?));
+        }
+
+        match self.next_token()?.map(into_identifier) {
+            Some(Ok(identifier)) => match self.peek_token() {
+                Some(Token::LeftBracket) => Ok(Expression::Aggregate(self.compile_aggregate(&identifier)?)),
+                Some(Token::Dot) => {
+                    self.skip();
+                    let column = self.assert_next_identifier()?;
+                    Ok(Expression::Column(ColumnReference::new(Some(identifier), column)))
+                }
+                _ => Ok(Expression::Column(ColumnReference::new(None, identifier))),
+            },
+            Some(Err(Token::LeftBracket)) => {
+                let expression = self.compile_expression()?;
+                self.assert_next_token_is(Token::RightBracket)?;
+
+                Ok(expression)
+            }
+            Some(Err(unhandled)) => Err(format!("Expected an expression but got {:?}", unhandled).into()),
+            None => Err("Expected an expression but got nothing".into())
+        }
     }
 
     fn assert_next_token_is(&mut self, item: Token) -> crate::Result<Token> {
-        if let Some(v) = self.inner.next() {
+        if let Some(v) = self.next_token()? {
             return if v == item {
                 Ok(v)
             } else {
                 Err(format!("Expected a {:?} but got {:?}", item, v).into())
             };
         }
-        return Err("Expected a token but got nothing".into());
+        Err("Expected a token but got nothing".into())
     }
 
     fn assert_next_identifier(&mut self) -> crate::Result<String> {
-        match self.inner.next() {
-            Some(Token::Identifier(identifier)) => Ok(identifier),
-            Some(token) => Err(format!("Expected an identifier but got {:?}", token).into()),
+        match self.next_token()?.map(into_identifier) {
+            Some(Ok(identifier)) => Ok(identifier),
+            Some(Err(token)) => Err(format!("Expected an identifier but got {:?}", token).into()),
             None => Err("Expected an identifier but got nothing".into())
         }
     }
 
+    fn next_token(&mut self) -> crate::Result<Option<Token>> {
+        match self.inner.next() {
+            Some(Ok(token)) => {
+                self.span = token.span();
+                self.at_end_of_statement = *token.token() == Token::SemiColon;
+                Ok(Some(token.into_token()))
+            }
+            Some(Err(err)) => Err(Box::new(CompilerError::from(err))),
+            None => Ok(None),
+        }
+    }
+
+    /// Peeks at the next token, treating a scanner error as the end of the current construct so that
+    /// the error itself is surfaced by the following call to `next_token`.
+    fn peek_token(&mut self) -> Option<&Token> {
+        self.peek_nth_token(0)
+    }
+
+    /// Peeks at the token `n` places after the next one, in the same way as `peek_token`.
+    fn peek_nth_token(&mut self, n: usize) -> Option<&Token> {
+        match self.inner.peek_nth(n) {
+            Some(Ok(token)) => Some(token.token()),
+            _ => None,
+        }
+    }
+
+    fn assert_end_of_statement(&mut self) -> crate::Result<()> {
+        match self.inner.peek() {
+            None => Ok(()),
+            Some(Ok(token)) if matches!(token.token(), Token::SemiColon | Token::NewLine) => Ok(()),
+            Some(_) => match self.next_token()? {
+                Some(token) => Err(format!("Expected the end of the statement but got {:?}", token).into()),
+                None => Ok(()),
+            },
+        }
+    }
+
+    /// Discards the remainder of a statement which failed to compile so the next call resumes at the
+    /// following statement.
+    fn skip_statement(&mut self) {
+        if self.at_end_of_statement {
+            return;
+        }
+        for token in self.inner.by_ref() {
+            if matches!(token, Ok(ref token) if *token.token() == Token::SemiColon) {
+                break;
+            }
+        }
+    }
+
     fn skip(&mut self) {
         let token = self.inner.next();
         trace!("Skipping token: {:?}", token)
     }
 }
 
-impl<T: Iterator<Item=Token>> Iterator for StatementCompiler<T> {
+/// Whether the token can be used as a name, which it can when it is an identifier or a keyword that
+/// is not reserved.
+fn is_identifier(token: &Token) -> bool {
+    match token {
+        Token::Identifier(_) => true,
+        Token::Keyword(keyword) => !keyword.is_reserved(),
+        _ => false,
+    }
+}
+
+/// The name the token stands for, or the token itself when it cannot be used as a name.
+fn into_identifier(token: Token) -> Result<String, Token> {
+    match token {
+        Token::Identifier(identifier) => Ok(identifier),
+        Token::Keyword(keyword) if !keyword.is_reserved() => Ok(keyword.name()),
+        token => Err(token),
+    }
+}
+
+/// Works out the value of a number. Numbers written with an exponent are doubles, while the rest
+/// are kept exact: an integer when it fits in a BIGINT and a decimal otherwise. Numbers too large
+/// for any of them are an error rather than being wrapped or rounded to infinity.
+fn parse_number(text: &str, negative: bool) -> crate::Result<Literal> {
+    let text = if negative { format!("-{}", text) } else { text.to_owned() };
+
+    if text.contains(['e', 'E']) {
+        return match text.parse::<f64>() {
+            Ok(value) if value.is_finite() => Ok(Literal::Double(value)),
+            _ => Err(format!("Number {} is out of range for a DOUBLE", text).into()),
+        };
+    }
+
+    match text.parse::<i64>() {
+        Ok(value) => Ok(Literal::Integer(value)),
+        Err(_) => Ok(Literal::Decimal(Decimal::parse(&text)?)),
+    }
+}
+
+impl<T: Iterator<Item=Result<SpannedToken, ScannerError>>> Iterator for StatementCompiler<T> {
     type Item = crate::Result<Statement>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        while let Some(token) = self.inner.next() {
+        loop {
+            let token = match self.next_token() {
+                Ok(Some(token)) => token,
+                Ok(None) => return None,
+                Err(err) => return Some(Err(err)),
+            };
+
             trace!("popped a token: {:?}", token);
 
-            match token {
-                Token::Keyword(KeywordToken::CREATE) => return Some(self.compile_create()),
-                Token::Keyword(KeywordToken::INSERT) => return Some(self.compile_insert()),
-                Token::Keyword(KeywordToken::SELECT) => return Some(self.compile_select()),
-                Token::SemiColon => {} //skip
-                Token::NewLine => {} //skip
-                unhandled => return Some(Err(format!("Unable to compile keyword: [{:?}]. It looks the compiler does not understand it", unhandled).into())),
+            let statement = match token {
+                Token::Keyword(KeywordToken::CREATE) => self.compile_create(),
+                Token::Keyword(KeywordToken::INSERT) => self.compile_insert(),
+                Token::Keyword(KeywordToken::SELECT) => self.compile_select(),
+                Token::Keyword(KeywordToken::UPDATE) => self.compile_update(),
+                Token::Keyword(KeywordToken::DELETE) => self.compile_delete(),
+                Token::Keyword(KeywordToken::DROP) => self.compile_drop(),
+                Token::Keyword(KeywordToken::TRUNCATE) => self.compile_truncate(),
+                Token::Keyword(KeywordToken::ALTER) => self.compile_alter(),
+                Token::Keyword(KeywordToken::SHOW) => self.compile_show(),
+                Token::Keyword(KeywordToken::DESCRIBE) => self.compile_describe(),
+                Token::SemiColon => continue,
+                Token::NewLine => continue,
+                unhandled => Err(format!("Unable to compile keyword: [{:?}]. It looks the compiler does not understand it", unhandled).into()),
             };
+
+            let statement = statement.and_then(|statement| {
+                self.assert_end_of_statement()?;
+                Ok(statement)
+            });
+
+            return Some(statement.map_err(|err| {
+                let err: crate::Error = if err.is::<CompilerError>() {
+                    err
+                } else {
+                    Box::new(CompilerError::new(err.to_string(), self.span))
+                };
+                self.skip_statement();
+                err
+            }));
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use crate::statements::alter::AlterTableAction;
+    use crate::statements::expression::{ColumnReference, Expression, Literal};
+    use crate::types::decimal::Decimal;
+    use crate::statements::scanner::{Token, TokenIterator};
+    use crate::statements::{select, Statement};
+
+    use super::{parse_number, CompilerError, StatementCompiler};
+
+    /// Compiles the one statement in `sql`.
+    fn compile(sql: &str) -> crate::Result<Statement> {
+        let tokens = TokenIterator::new_iterator(sql.chars())
+            .filter(|x| !matches!(x, Ok(token) if matches!(token.token(), Token::Space | Token::NewLine)));
+        StatementCompiler::new(tokens).next().expect("a statement")
+    }
+
+    fn column(table: Option<&str>, name: &str) -> Expression {
+        Expression::Column(ColumnReference::new(table.map(str::to_owned), name.to_owned()))
+    }
+
+    #[test]
+    fn keywords_which_are_not_reserved_are_names() {
+        match compile("CREATE TABLE tables (key TEXT PRIMARY KEY, first INT, last INT, set BOOLEAN, to TEXT)").unwrap() {
+            Statement::Create(statement) => {
+                assert_eq!(statement.table_name(), "tables");
+                let names = statement.columns().iter().map(|column| column.name()).collect::<Vec<&str>>();
+                assert_eq!(names, vec!["key", "first", "last", "set", "to"]);
+            }
+            statement => panic!("Expected a create but got {:?}", statement),
+        }
+
+        match compile("SELECT key, t.first AS last FROM tables AS add WHERE set ORDER BY last NULLS FIRST").unwrap() {
+            Statement::Select(statement) => {
+                match statement.expression() {
+                    [select::Expression::Value(first, None), select::Expression::Value(second, Some(alias))] => {
+                        assert_eq!(*first, column(None, "key"));
+                        assert_eq!(*second, column(Some("t"), "first"));
+                        assert_eq!(alias, "last");
+                    }
+                    items => panic!("Unexpected items {:?}", items),
+                }
+                assert_eq!(statement.from().table().alias(), Some("add"));
+                assert_eq!(statement.predicate(), Some(&column(None, "set")));
+                assert!(statement.order_by()[0].nulls_first());
+            }
+            statement => panic!("Expected a select but got {:?}", statement),
+        }
+
+        match compile("UPDATE tables SET set = FALSE, key = 'k' WHERE key = 'a'").unwrap() {
+            Statement::Update(statement) => {
+                let names = statement.assignments().iter().map(|assignment| assignment.column()).collect::<Vec<&str>>();
+                assert_eq!(names, vec!["set", "key"]);
+            }
+            statement => panic!("Expected an update but got {:?}", statement),
+        }
+    }
+
+    #[test]
+    fn alter_table_tells_keywords_from_names() {
+        let action = |sql: &str| match compile(sql).unwrap() {
+            Statement::Alter(statement) => format!("{:?}", statement.action()),
+            statement => panic!("Expected an alter but got {:?}", statement),
+        };
+
+        assert_eq!(action("ALTER TABLE t RENAME TO column"), format!("{:?}", AlterTableAction::RenameTable("column".to_owned())));
+        assert_eq!(action("ALTER TABLE t RENAME to TO add"), format!("{:?}", AlterTableAction::RenameColumn("to".to_owned(), "add".to_owned())));
+        assert_eq!(action("ALTER TABLE t DROP COLUMN key"), format!("{:?}", AlterTableAction::DropColumn("key".to_owned())));
+        assert_eq!(action("ALTER TABLE t DROP column"), format!("{:?}", AlterTableAction::DropColumn("column".to_owned())));
+    }
+
+    #[test]
+    fn reserved_keywords_are_not_names() {
+        assert!(compile("SELECT from FROM t").is_err());
+        assert!(compile("CREATE TABLE select (a INT)").is_err());
+        assert!(compile("SELECT a FROM t AS where").is_err());
+
+        // Aliases written without AS have to be identifiers, or `FROM t LEFT JOIN` would alias `t`
+        assert!(compile("SELECT a key FROM t").is_err());
+        assert!(compile("SELECT a AS key FROM t").is_ok());
+        assert!(compile("SELECT a FROM t LEFT JOIN u ON t.a = u.a").is_ok());
+    }
+
+    #[test]
+    fn errors_point_at_the_token_they_are_about() {
+        let sql = "SELECT a FROM users\nWHERE a > ;\nSELECT b FROM users";
+        let tokens = TokenIterator::new_iterator(sql.chars())
+            .filter(|x| !matches!(x, Ok(token) if matches!(token.token(), Token::Space | Token::NewLine)));
+        let mut statements = StatementCompiler::new(tokens);
+
+        let err = statements.next().unwrap().unwrap_err();
+        let err = err.downcast_ref::<CompilerError>().unwrap();
+        assert_eq!((err.span().start().line(), err.span().start().column()), (2, 11));
+        assert_eq!(err.to_string(), "Expected an expression but got SemiColon at line 2, column 11");
+        assert_eq!(err.render(sql), "Expected an expression but got SemiColon at line 2, column 11\n  |\n2 | WHERE a > ;\n  |           ^");
+
+        // The statement after the one which failed still compiles
+        assert!(matches!(statements.next(), Some(Ok(Statement::Select(_)))));
+        assert!(statements.next().is_none());
+    }
+
+    #[test]
+    fn scanner_errors_keep_their_span() {
+        let err = compile("SELECT a FROM users WHERE a = 'open").unwrap_err();
+        let err = err.downcast_ref::<CompilerError>().unwrap();
+        assert_eq!(err.message(), "Unterminated string literal");
+        assert_eq!((err.span().start().column(), err.span().end().column()), (31, 36));
+    }
+
+    #[test]
+    fn numbers_are_read_as_the_narrowest_exact_type() {
+        assert_eq!(parse_number("42", false).unwrap(), Literal::Integer(42));
+        assert_eq!(parse_number("9223372036854775808", true).unwrap(), Literal::Integer(i64::MIN));
+        assert_eq!(parse_number("9223372036854775808", false).unwrap(), Literal::Decimal(Decimal::parse("9223372036854775808").unwrap()));
+        assert_eq!(parse_number("1.50", false).unwrap(), Literal::Decimal(Decimal::parse("1.50").unwrap()));
+        assert_eq!(parse_number("2.5e3", true).unwrap(), Literal::Double(-2500.0));
+
+        assert!(parse_number("1e400", false).unwrap_err().to_string().contains("out of range for a DOUBLE"));
+        assert!(parse_number("1234567890123456789012345678901234567890", false).is_err());
+    }
+
+    #[test]
+    fn a_minus_negates_any_expression_but_belongs_to_a_number() {
+        match compile("SELECT -id, 2 - -3, -(id + 1) * 2 FROM m WHERE id > -id").unwrap() {
+            Statement::Select(statement) => {
+                let items = statement.expression().iter().map(|item| match item {
+                    select::Expression::Value(expression, _) => expression.to_string(),
+                    item => panic!("Unexpected item {:?}", item),
+                }).collect::<Vec<String>>();
+                assert_eq!(items, vec!["-id", "2 - -3", "(-(id + 1)) * 2"]);
+                assert_eq!(statement.predicate().unwrap().to_string(), "id > (-id)");
+
+                match statement.expression() {
+                    [_, select::Expression::Value(Expression::Binary(_, _, right), _), _] => {
+                        assert_eq!(**right, Expression::Literal(Literal::Integer(-3)));
+                    }
+                    items => panic!("Unexpected items {:?}", items),
+                }
+            }
+            statement => panic!("Expected a select but got {:?}", statement),
         }
-        return None;
+
+        assert!(compile("SELECT - FROM m").is_err());
     }
-}
\ No newline at end of file
+}
@@ -1,20 +1,40 @@
 use std::borrow::Borrow;
+use std::fmt;
+
+use crate::statements::expression::Literal;
 
 #[derive(Debug)]
 pub enum DataType {
     Int32,
+    Int64,
+    Double,
+    Boolean,
     String,
+    Date,
+    Time,
+    Timestamp,
+    /// An exact number with `precision` digits, `scale` of them after the decimal point.
+    Decimal(u8, u8),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ColumnConstraint {
+    PrimaryKey,
+    NotNull,
+    Unique,
+    Default(Literal),
 }
 
 #[derive(Debug)]
 pub struct ColumnDefinition {
     name: String,
     data_type: DataType,
+    constraints: Vec<ColumnConstraint>,
 }
 
 impl ColumnDefinition {
-    pub fn new(name: String, data_type: DataType) -> Self {
-        ColumnDefinition { name, data_type }
+    pub fn new(name: String, data_type: DataType, constraints: Vec<ColumnConstraint>) -> Self {
+        ColumnDefinition { name, data_type, constraints }
     }
     pub fn name(&self) -> &str {
         self.name.borrow()
@@ -22,17 +42,21 @@ impl ColumnDefinition {
     pub fn data_type(&self) -> &DataType {
         self.data_type.borrow()
     }
+    pub fn constraints(&self) -> &[ColumnConstraint] {
+        self.constraints.borrow()
+    }
 }
 
 #[derive(Debug)]
 pub struct CreateTableStatement {
     name: String,
     columns: Vec<ColumnDefinition>,
+    if_not_exists: bool,
 }
 
 impl CreateTableStatement {
-    pub fn new(name: String, columns: Vec<ColumnDefinition>) -> Self {
-        CreateTableStatement { name, columns }
+    pub fn new(name: String, columns: Vec<ColumnDefinition>, if_not_exists: bool) -> Self {
+        CreateTableStatement { name, columns, if_not_exists }
     }
 
     pub fn table_name(&self) -> &str {
@@ -42,4 +66,55 @@ impl CreateTableStatement {
     pub fn columns(&self) -> &[ColumnDefinition] {
         self.columns.borrow()
     }
+
+    /// Whether an existing table of the same name is left alone rather than reported as an error.
+    pub fn if_not_exists(&self) -> bool {
+        self.if_not_exists
+    }
+}
+
+impl fmt::Display for DataType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataType::Int32 => write!(f, "INT"),
+            DataType::Int64 => write!(f, "BIGINT"),
+            DataType::Double => write!(f, "DOUBLE"),
+            DataType::Boolean => write!(f, "BOOLEAN"),
+            DataType::String => write!(f, "TEXT"),
+            DataType::Date => write!(f, "DATE"),
+            DataType::Time => write!(f, "TIME"),
+            DataType::Timestamp => write!(f, "TIMESTAMP"),
+            DataType::Decimal(precision, scale) => write!(f, "DECIMAL({}, {})", precision, scale),
+        }
+    }
+}
+
+impl fmt::Display for ColumnConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColumnConstraint::PrimaryKey => write!(f, "PRIMARY KEY"),
+            ColumnConstraint::NotNull => write!(f, "NOT NULL"),
+            ColumnConstraint::Unique => write!(f, "UNIQUE"),
+            ColumnConstraint::Default(value) => write!(f, "DEFAULT {}", value),
+        }
+    }
+}
+
+/// Renders the statement back as SQL which would create the same table.
+impl fmt::Display for CreateTableStatement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let columns = self.columns.iter()
+            .map(|column| {
+                let mut definition = format!("{} {}", column.name(), column.data_type());
+                for constraint in column.constraints() {
+                    definition.push_str(&format!(" {}", constraint));
+                }
+                definition
+            })
+            .collect::<Vec<String>>();
+
+        let if_not_exists = if self.if_not_exists { "IF NOT EXISTS " } else { "" };
+
+        write!(f, "CREATE TABLE {}{} ({});", if_not_exists, self.name, columns.join(", "))
+    }
 }
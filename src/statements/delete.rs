@@ -0,0 +1,23 @@
+use std::borrow::Borrow;
+
+use crate::statements::expression::Expression;
+
+#[derive(Debug)]
+pub struct DeleteStatement {
+    table: String,
+    predicate: Option<Expression>,
+}
+
+impl DeleteStatement {
+    pub fn new(table: String, predicate: Option<Expression>) -> Self {
+        DeleteStatement { table, predicate }
+    }
+
+    pub fn table_name(&self) -> &str {
+        self.table.borrow()
+    }
+
+    pub fn predicate(&self) -> Option<&Expression> {
+        self.predicate.as_ref()
+    }
+}
@@ -0,0 +1,16 @@
+use std::borrow::Borrow;
+
+#[derive(Debug)]
+pub struct DescribeStatement {
+    table: String,
+}
+
+impl DescribeStatement {
+    pub fn new(table: String) -> Self {
+        DescribeStatement { table }
+    }
+
+    pub fn table_name(&self) -> &str {
+        self.table.borrow()
+    }
+}
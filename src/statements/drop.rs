@@ -0,0 +1,22 @@
+use std::borrow::Borrow;
+
+#[derive(Debug)]
+pub struct DropTableStatement {
+    table: String,
+    if_exists: bool,
+}
+
+impl DropTableStatement {
+    pub fn new(table: String, if_exists: bool) -> Self {
+        DropTableStatement { table, if_exists }
+    }
+
+    pub fn table_name(&self) -> &str {
+        self.table.borrow()
+    }
+
+    /// Whether a missing table is skipped rather than reported as an error.
+    pub fn if_exists(&self) -> bool {
+        self.if_exists
+    }
+}
@@ -0,0 +1,241 @@
+use std::fmt;
+
+use crate::types::decimal::Decimal;
+use crate::types::temporal::{Date, Interval, Time, Timestamp};
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Literal {
+    Integer(i64),
+    Decimal(Decimal),
+    Double(f64),
+    String(String),
+    Boolean(bool),
+    Date(Date),
+    Time(Time),
+    Timestamp(Timestamp),
+    Interval(Interval),
+    Null,
+}
+
+/// Renders the literal as it would be written in SQL.
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Literal::Integer(value) => write!(f, "{}", value),
+            Literal::Decimal(value) => write!(f, "{}", value),
+            // Written with an exponent, as a number with only a decimal point reads back as a DECIMAL
+            Literal::Double(value) => write!(f, "{:e}", value),
+            Literal::String(value) => write!(f, "'{}'", value.replace('\'', "''")),
+            Literal::Boolean(true) => write!(f, "TRUE"),
+            Literal::Boolean(false) => write!(f, "FALSE"),
+            Literal::Date(value) => write!(f, "DATE '{}'", value),
+            Literal::Time(value) => write!(f, "TIME '{}'", value),
+            Literal::Timestamp(value) => write!(f, "TIMESTAMP '{}'", value),
+            Literal::Interval(value) => write!(f, "INTERVAL '{}'", value),
+            Literal::Null => write!(f, "NULL"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum BinaryOperator {
+    Equal,
+    NotEqual,
+    LessThan,
+    LessThanOrEqual,
+    GreaterThan,
+    GreaterThanOrEqual,
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    And,
+    Or,
+}
+
+impl BinaryOperator {
+    /// Whether the operator works out a new value, rather than comparing or combining conditions.
+    pub fn is_arithmetic(&self) -> bool {
+        matches!(self, BinaryOperator::Add | BinaryOperator::Subtract | BinaryOperator::Multiply | BinaryOperator::Divide)
+    }
+}
+
+impl fmt::Display for BinaryOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BinaryOperator::Equal => write!(f, "="),
+            BinaryOperator::NotEqual => write!(f, "<>"),
+            BinaryOperator::LessThan => write!(f, "<"),
+            BinaryOperator::LessThanOrEqual => write!(f, "<="),
+            BinaryOperator::GreaterThan => write!(f, ">"),
+            BinaryOperator::GreaterThanOrEqual => write!(f, ">="),
+            BinaryOperator::Add => write!(f, "+"),
+            BinaryOperator::Subtract => write!(f, "-"),
+            BinaryOperator::Multiply => write!(f, "*"),
+            BinaryOperator::Divide => write!(f, "/"),
+            BinaryOperator::And => write!(f, "AND"),
+            BinaryOperator::Or => write!(f, "OR"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UnaryOperator {
+    Not,
+    Negate,
+    IsNull,
+    IsNotNull,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum AggregateFunction {
+    Count,
+    Sum,
+    Min,
+    Max,
+    Avg,
+}
+
+impl std::convert::TryFrom<&str> for AggregateFunction {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.to_uppercase().as_str() {
+            "COUNT" => Ok(AggregateFunction::Count),
+            "SUM" => Ok(AggregateFunction::Sum),
+            "MIN" => Ok(AggregateFunction::Min),
+            "MAX" => Ok(AggregateFunction::Max),
+            "AVG" => Ok(AggregateFunction::Avg),
+            v => Err(format!("Unknown function: [{}]", v))
+        }
+    }
+}
+
+impl fmt::Display for AggregateFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AggregateFunction::Count => write!(f, "COUNT"),
+            AggregateFunction::Sum => write!(f, "SUM"),
+            AggregateFunction::Min => write!(f, "MIN"),
+            AggregateFunction::Max => write!(f, "MAX"),
+            AggregateFunction::Avg => write!(f, "AVG"),
+        }
+    }
+}
+
+/// A call to an aggregate function. A missing argument stands for `*`, as in `COUNT(*)`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Aggregate {
+    function: AggregateFunction,
+    argument: Option<Box<Expression>>,
+}
+
+impl Aggregate {
+    pub fn new(function: AggregateFunction, argument: Option<Expression>) -> Self {
+        Aggregate { function, argument: argument.map(Box::new) }
+    }
+
+    pub fn function(&self) -> AggregateFunction {
+        self.function
+    }
+
+    pub fn argument(&self) -> Option<&Expression> {
+        self.argument.as_deref()
+    }
+}
+
+impl fmt::Display for Aggregate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.argument {
+            Some(argument) => write!(f, "{}({})", self.function, argument),
+            None => write!(f, "{}(*)", self.function),
+        }
+    }
+}
+
+/// A column, optionally qualified by the name or alias of its table as in `users.id`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ColumnReference {
+    table: Option<String>,
+    name: String,
+}
+
+impl ColumnReference {
+    pub fn new(table: Option<String>, name: String) -> Self {
+        ColumnReference { table, name }
+    }
+
+    pub fn table(&self) -> Option<&str> {
+        self.table.as_deref()
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+impl fmt::Display for ColumnReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.table {
+            Some(table) => write!(f, "{}.{}", table, self.name),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Expression {
+    Literal(Literal),
+    Column(ColumnReference),
+    Aggregate(Aggregate),
+    Unary(UnaryOperator, Box<Expression>),
+    Binary(Box<Expression>, BinaryOperator, Box<Expression>),
+}
+
+impl Expression {
+    /// Calls `f` with every aggregate in the expression, outermost first.
+    pub fn visit_aggregates<F: FnMut(&Aggregate)>(&self, f: &mut F) {
+        match self {
+            Expression::Literal(_) | Expression::Column(_) => {}
+            Expression::Aggregate(aggregate) => {
+                f(aggregate);
+                if let Some(argument) = aggregate.argument() {
+                    argument.visit_aggregates(f);
+                }
+            }
+            Expression::Unary(_, operand) => operand.visit_aggregates(f),
+            Expression::Binary(left, _, right) => {
+                left.visit_aggregates(f);
+                right.visit_aggregates(f);
+            }
+        }
+    }
+}
+
+/// Renders the expression as SQL, with brackets around every nested operation so the result never
+/// depends on precedence.
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expression::Literal(literal) => write!(f, "{}", literal),
+            Expression::Column(column) => write!(f, "{}", column),
+            Expression::Aggregate(aggregate) => write!(f, "{}", aggregate),
+            Expression::Unary(UnaryOperator::Not, operand) => write!(f, "NOT {}", Bracketed(operand)),
+            Expression::Unary(UnaryOperator::Negate, operand) => write!(f, "-{}", Bracketed(operand)),
+            Expression::Unary(UnaryOperator::IsNull, operand) => write!(f, "{} IS NULL", Bracketed(operand)),
+            Expression::Unary(UnaryOperator::IsNotNull, operand) => write!(f, "{} IS NOT NULL", Bracketed(operand)),
+            Expression::Binary(left, operator, right) => write!(f, "{} {} {}", Bracketed(left), operator, Bracketed(right)),
+        }
+    }
+}
+
+struct Bracketed<'a>(&'a Expression);
+
+impl fmt::Display for Bracketed<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Expression::Unary(..) | Expression::Binary(..) => write!(f, "({})", self.0),
+            expression => write!(f, "{}", expression),
+        }
+    }
+}
@@ -1,32 +1,45 @@
 use std::borrow::Borrow;
 
+use crate::statements::expression::Literal;
+use crate::statements::select::SelectStatement;
+
 #[derive(Debug)]
-pub enum Literal {
-    U32(u32),
-    String(String),
+pub enum Expression {
+    Literal(Literal),
+    /// The `DEFAULT` keyword, which stands for the column's default value.
+    Default,
 }
 
+/// Where the inserted rows come from.
 #[derive(Debug)]
-pub enum Expression {
-    Literal(Literal)
+pub enum InsertSource {
+    Values(Vec<Vec<Expression>>),
+    Select(Box<SelectStatement>),
 }
 
 #[derive(Debug)]
 pub struct InsertStatement {
     table: String,
-    values: Vec<Expression>,
+    columns: Option<Vec<String>>,
+    source: InsertSource,
 }
 
 impl InsertStatement {
-    pub fn new(table: String, values: Vec<Expression>) -> Self {
-        InsertStatement { table, values }
+    pub fn new(table: String, columns: Option<Vec<String>>, source: InsertSource) -> Self {
+        InsertStatement { table, columns, source }
     }
 
     pub fn table_name(&self) -> &str {
         self.table.borrow()
     }
 
-    pub fn values(&self) -> &[Expression] {
-        self.values.borrow()
+    /// The columns the values are given for, or `None` when there is a value for every column in
+    /// the order they were declared.
+    pub fn columns(&self) -> Option<&[String]> {
+        self.columns.as_deref()
+    }
+
+    pub fn source(&self) -> &InsertSource {
+        &self.source
     }
 }
\ No newline at end of file
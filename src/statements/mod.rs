@@ -1,12 +1,25 @@
+use crate::statements::alter::AlterTableStatement;
 use crate::statements::create::CreateTableStatement;
+use crate::statements::delete::DeleteStatement;
+use crate::statements::describe::DescribeStatement;
+use crate::statements::drop::DropTableStatement;
 use crate::statements::insert::InsertStatement;
 use crate::statements::select::SelectStatement;
+use crate::statements::truncate::TruncateTableStatement;
+use crate::statements::update::UpdateStatement;
 
+pub mod alter;
 pub mod create;
 pub mod compiler;
+pub mod delete;
+pub mod describe;
+pub mod drop;
+pub mod expression;
 pub mod insert;
 pub mod select;
 pub mod scanner;
+pub mod truncate;
+pub mod update;
 
 
 #[derive(Debug)]
@@ -14,5 +27,12 @@ pub enum Statement {
     Create(CreateTableStatement),
     Insert(InsertStatement),
     Select(SelectStatement),
+    Update(UpdateStatement),
+    Delete(DeleteStatement),
+    Drop(DropTableStatement),
+    Truncate(TruncateTableStatement),
+    Alter(AlterTableStatement),
+    ShowTables,
+    Describe(DescribeStatement),
 }
 
@@ -3,11 +3,126 @@ use std::iter::Peekable;
 
 use log::trace;
 
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Position {
+    line: usize,
+    column: usize,
+    offset: usize,
+}
+
+impl Position {
+    pub fn new(line: usize, column: usize, offset: usize) -> Self {
+        Position { line, column, offset }
+    }
+
+    /// The 1-based line number.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The 1-based column number, counted in characters.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// The 0-based character offset from the start of the input.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Position::new(1, 1, 0)
+    }
+}
+
+/// The half-open range of characters a token was read from.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Span {
+    start: Position,
+    end: Position,
+}
+
+impl Span {
+    pub fn new(start: Position, end: Position) -> Self {
+        Span { start, end }
+    }
+
+    pub fn start(&self) -> Position {
+        self.start
+    }
+
+    pub fn end(&self) -> Position {
+        self.end
+    }
+
+    /// Renders the line the span starts on with a caret underline beneath the spanned characters.
+    pub fn render(&self, source: &str) -> String {
+        let line = source.lines().nth(self.start.line - 1).unwrap_or("");
+        let gutter = self.start.line.to_string();
+
+        let padding = line.chars()
+            .take(self.start.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+
+        let width = if self.end.line == self.start.line && self.end.column > self.start.column {
+            self.end.column - self.start.column
+        } else {
+            1
+        };
+
+        format!(
+            "{empty:>indent$} |\n{gutter} | {line}\n{empty:>indent$} | {padding}{carets}",
+            empty = "",
+            indent = gutter.len(),
+            gutter = gutter,
+            line = line,
+            padding = padding,
+            carets = "^".repeat(width),
+        )
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum ScannerError {
     Error(String),
+    UnexpectedCharacter(char, Span),
+    UnterminatedStringLiteral(Span),
+    MalformedNumber(Span),
+}
+
+impl ScannerError {
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            ScannerError::Error(_) => None,
+            ScannerError::UnexpectedCharacter(_, span) => Some(*span),
+            ScannerError::UnterminatedStringLiteral(span) => Some(*span),
+            ScannerError::MalformedNumber(span) => Some(*span),
+        }
+    }
+}
+
+impl std::fmt::Display for ScannerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScannerError::Error(message) => write!(f, "{}", message),
+            ScannerError::UnexpectedCharacter(character, span) => {
+                write!(f, "Unexpected character {:?} at line {}, column {}", character, span.start().line(), span.start().column())
+            }
+            ScannerError::UnterminatedStringLiteral(span) => {
+                write!(f, "Unterminated string literal at line {}, column {}", span.start().line(), span.start().column())
+            }
+            ScannerError::MalformedNumber(span) => {
+                write!(f, "Malformed number at line {}, column {}", span.start().line(), span.start().column())
+            }
+        }
+    }
 }
 
+impl std::error::Error for ScannerError {}
+
 impl std::convert::From<std::io::Error> for ScannerError {
     fn from(err: std::io::Error) -> Self {
         ScannerError::Error(format!("{}", err))
@@ -16,18 +131,53 @@ impl std::convert::From<std::io::Error> for ScannerError {
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum Token {
-    U32(u32),
+    /// A number as it was written, like `42`, `3.14` or `1e-3`. It is kept as text so the compiler
+    /// can decide which type it fits, and report the numbers which fit none.
+    Number(String),
     SemiColon,
     Keyword(KeywordToken),
     Space,
     Identifier(String),
+    StringLiteral(String),
     Assignment,
     NewLine,
     LeftBracket,
     RightBracket,
     Comma,
-    Apostrophe,
     Asterisk,
+    LessThan,
+    LessThanOrEqual,
+    GreaterThan,
+    GreaterThanOrEqual,
+    NotEqual,
+    Dot,
+    Plus,
+    Minus,
+    Slash,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SpannedToken {
+    token: Token,
+    span: Span,
+}
+
+impl SpannedToken {
+    pub fn new(token: Token, span: Span) -> Self {
+        SpannedToken { token, span }
+    }
+
+    pub fn token(&self) -> &Token {
+        &self.token
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn into_token(self) -> Token {
+        self.token
+    }
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -42,6 +192,93 @@ pub enum KeywordToken {
     VALUES,
     INT,
     TEXT,
+    WHERE,
+    AND,
+    OR,
+    NOT,
+    UPDATE,
+    SET,
+    DELETE,
+    NULL,
+    IS,
+    PRIMARY,
+    KEY,
+    UNIQUE,
+    DEFAULT,
+    ORDER,
+    BY,
+    ASC,
+    DESC,
+    NULLS,
+    FIRST,
+    LAST,
+    LIMIT,
+    OFFSET,
+    GROUP,
+    HAVING,
+    JOIN,
+    INNER,
+    LEFT,
+    OUTER,
+    ON,
+    DROP,
+    IF,
+    EXISTS,
+    TRUNCATE,
+    ALTER,
+    ADD,
+    COLUMN,
+    RENAME,
+    TO,
+    SHOW,
+    TABLES,
+    DESCRIBE,
+    BIGINT,
+    DOUBLE,
+    FLOAT,
+    BOOLEAN,
+    TRUE,
+    FALSE,
+    DECIMAL,
+    NUMERIC,
+    DATE,
+    TIME,
+    TIMESTAMP,
+    INTERVAL,
+    YEAR,
+    MONTH,
+    DAY,
+    HOUR,
+    MINUTE,
+    SECOND,
+}
+
+impl KeywordToken {
+    /// Whether the keyword can never be used as the name of a table, column or alias. These are the
+    /// words which start a statement or clause, join expressions together or are values themselves,
+    /// where the compiler could not tell what was meant. The rest only mean something in the one
+    /// place they are expected, like KEY after PRIMARY, so anywhere else they are names.
+    pub fn is_reserved(&self) -> bool {
+        matches!(
+            self,
+            KeywordToken::CREATE | KeywordToken::TABLE | KeywordToken::SELECT | KeywordToken::FROM | KeywordToken::AS
+                | KeywordToken::INSERT | KeywordToken::INTO | KeywordToken::VALUES | KeywordToken::WHERE
+                | KeywordToken::AND | KeywordToken::OR | KeywordToken::NOT | KeywordToken::UPDATE | KeywordToken::DELETE
+                | KeywordToken::NULL | KeywordToken::IS | KeywordToken::PRIMARY | KeywordToken::UNIQUE
+                | KeywordToken::DEFAULT | KeywordToken::ORDER | KeywordToken::BY | KeywordToken::GROUP
+                | KeywordToken::HAVING | KeywordToken::LIMIT | KeywordToken::OFFSET | KeywordToken::JOIN
+                | KeywordToken::INNER | KeywordToken::LEFT | KeywordToken::OUTER | KeywordToken::ON | KeywordToken::DROP
+                | KeywordToken::IF | KeywordToken::TRUNCATE | KeywordToken::ALTER | KeywordToken::SHOW
+                | KeywordToken::DESCRIBE | KeywordToken::TRUE | KeywordToken::FALSE
+        )
+    }
+
+    /// The name a keyword stands for when it is used as an identifier. Keywords are read whatever
+    /// their case, so the name is always in lower case.
+    pub fn name(&self) -> String {
+        // The variants are spelled exactly like the words they are read from
+        format!("{:?}", self).to_lowercase()
+    }
 }
 
 impl std::convert::TryFrom<&str> for KeywordToken {
@@ -59,6 +296,65 @@ impl std::convert::TryFrom<&str> for KeywordToken {
             "VALUES" => Ok(KeywordToken::VALUES),
             "INT" => Ok(KeywordToken::INT),
             "TEXT" => Ok(KeywordToken::TEXT),
+            "WHERE" => Ok(KeywordToken::WHERE),
+            "AND" => Ok(KeywordToken::AND),
+            "OR" => Ok(KeywordToken::OR),
+            "NOT" => Ok(KeywordToken::NOT),
+            "UPDATE" => Ok(KeywordToken::UPDATE),
+            "SET" => Ok(KeywordToken::SET),
+            "DELETE" => Ok(KeywordToken::DELETE),
+            "NULL" => Ok(KeywordToken::NULL),
+            "IS" => Ok(KeywordToken::IS),
+            "PRIMARY" => Ok(KeywordToken::PRIMARY),
+            "KEY" => Ok(KeywordToken::KEY),
+            "UNIQUE" => Ok(KeywordToken::UNIQUE),
+            "DEFAULT" => Ok(KeywordToken::DEFAULT),
+            "ORDER" => Ok(KeywordToken::ORDER),
+            "BY" => Ok(KeywordToken::BY),
+            "ASC" => Ok(KeywordToken::ASC),
+            "DESC" => Ok(KeywordToken::DESC),
+            "NULLS" => Ok(KeywordToken::NULLS),
+            "FIRST" => Ok(KeywordToken::FIRST),
+            "LAST" => Ok(KeywordToken::LAST),
+            "LIMIT" => Ok(KeywordToken::LIMIT),
+            "OFFSET" => Ok(KeywordToken::OFFSET),
+            "GROUP" => Ok(KeywordToken::GROUP),
+            "HAVING" => Ok(KeywordToken::HAVING),
+            "JOIN" => Ok(KeywordToken::JOIN),
+            "INNER" => Ok(KeywordToken::INNER),
+            "LEFT" => Ok(KeywordToken::LEFT),
+            "OUTER" => Ok(KeywordToken::OUTER),
+            "ON" => Ok(KeywordToken::ON),
+            "DROP" => Ok(KeywordToken::DROP),
+            "IF" => Ok(KeywordToken::IF),
+            "EXISTS" => Ok(KeywordToken::EXISTS),
+            "TRUNCATE" => Ok(KeywordToken::TRUNCATE),
+            "ALTER" => Ok(KeywordToken::ALTER),
+            "ADD" => Ok(KeywordToken::ADD),
+            "COLUMN" => Ok(KeywordToken::COLUMN),
+            "RENAME" => Ok(KeywordToken::RENAME),
+            "TO" => Ok(KeywordToken::TO),
+            "SHOW" => Ok(KeywordToken::SHOW),
+            "TABLES" => Ok(KeywordToken::TABLES),
+            "DESCRIBE" => Ok(KeywordToken::DESCRIBE),
+            "BIGINT" => Ok(KeywordToken::BIGINT),
+            "DOUBLE" => Ok(KeywordToken::DOUBLE),
+            "FLOAT" => Ok(KeywordToken::FLOAT),
+            "BOOLEAN" => Ok(KeywordToken::BOOLEAN),
+            "TRUE" => Ok(KeywordToken::TRUE),
+            "FALSE" => Ok(KeywordToken::FALSE),
+            "DECIMAL" => Ok(KeywordToken::DECIMAL),
+            "NUMERIC" => Ok(KeywordToken::NUMERIC),
+            "DATE" => Ok(KeywordToken::DATE),
+            "TIME" => Ok(KeywordToken::TIME),
+            "TIMESTAMP" => Ok(KeywordToken::TIMESTAMP),
+            "INTERVAL" => Ok(KeywordToken::INTERVAL),
+            "YEAR" => Ok(KeywordToken::YEAR),
+            "MONTH" => Ok(KeywordToken::MONTH),
+            "DAY" => Ok(KeywordToken::DAY),
+            "HOUR" => Ok(KeywordToken::HOUR),
+            "MINUTE" => Ok(KeywordToken::MINUTE),
+            "SECOND" => Ok(KeywordToken::SECOND),
             v => Err(format!("Unable to handle KeywordToken: [{}]", v))
         }
     }
@@ -66,6 +362,7 @@ impl std::convert::TryFrom<&str> for KeywordToken {
 
 pub struct TokenIterator<T: Iterator<Item=char>> {
     inner: Peekable<T>,
+    position: Position,
 }
 
 impl<T: Iterator<Item=char>> TokenIterator<T> {
@@ -74,69 +371,259 @@ impl<T: Iterator<Item=char>> TokenIterator<T> {
     }
 
     fn new(inner: Peekable<T>) -> Self {
-        TokenIterator { inner }
-    }
-
-    fn read_symbol(&mut self) -> Option<Token> {
-        if let Some(t) = self.inner.next() {
-            return Some(match t {
-                ';' => Token::SemiColon,
-                '\n' => Token::NewLine,
-                ' ' => Token::Space,
-                '(' => Token::LeftBracket,
-                ')' => Token::RightBracket,
-                ',' => Token::Comma,
-                '\'' => Token::Apostrophe,
-                '*' => Token::Asterisk,
-                v => panic!("Unable to handle token: {:?}", v)
-            });
+        TokenIterator { inner, position: Position::default() }
+    }
+
+    fn advance(&mut self) -> Option<char> {
+        let next = self.inner.next();
+        match next {
+            Some('\n') => {
+                self.position.line += 1;
+                self.position.column = 1;
+                self.position.offset += 1;
+            }
+            Some(_) => {
+                self.position.column += 1;
+                self.position.offset += 1;
+            }
+            None => {}
+        }
+        next
+    }
+
+    fn advance_if(&mut self, expected: char) -> bool {
+        if self.inner.peek() == Some(&expected) {
+            self.advance();
+            return true;
+        }
+        false
+    }
+
+    fn read_symbol(&mut self) -> Result<Token, ScannerError> {
+        let start = self.position;
+        let symbol = match self.advance() {
+            Some(symbol) => symbol,
+            None => return Err(ScannerError::Error("Received no character but expected a symbol".to_string())),
+        };
+
+        match symbol {
+            ';' => Ok(Token::SemiColon),
+            '\n' => Ok(Token::NewLine),
+            ' ' => Ok(Token::Space),
+            '(' => Ok(Token::LeftBracket),
+            ')' => Ok(Token::RightBracket),
+            ',' => Ok(Token::Comma),
+            '*' => Ok(Token::Asterisk),
+            '.' => Ok(Token::Dot),
+            '+' => Ok(Token::Plus),
+            '-' => Ok(Token::Minus),
+            '/' => Ok(Token::Slash),
+            '=' => Ok(Token::Assignment),
+            '<' if self.advance_if('=') => Ok(Token::LessThanOrEqual),
+            '<' if self.advance_if('>') => Ok(Token::NotEqual),
+            '<' => Ok(Token::LessThan),
+            '>' if self.advance_if('=') => Ok(Token::GreaterThanOrEqual),
+            '>' => Ok(Token::GreaterThan),
+            '!' if self.advance_if('=') => Ok(Token::NotEqual),
+            v => Err(ScannerError::UnexpectedCharacter(v, Span::new(start, self.position))),
         }
-        panic!("Error - Received no token but expected a whitespace")
     }
 
-    fn read_alphabetic_token(&mut self) -> Option<Token> {
+    fn read_alphabetic_token(&mut self) -> Result<Token, ScannerError> {
         let mut result = String::new();
         while self.inner.peek().map_or_else(|| false, |x| x.is_alphanumeric()) {
-            let next = self.inner.next().unwrap();
+            let next = self.advance().unwrap();
             result.push(next)
         }
 
         match KeywordToken::try_from(result.as_str()) {
-            Ok(v) => Some(Token::Keyword(v)),
+            Ok(v) => Ok(Token::Keyword(v)),
             Err(v) => {
                 trace!("Error while reading the keyword [{:?}], defaulting to identifier", v);
-                Some(Token::Identifier(result))
+                Ok(Token::Identifier(result))
             }
         }
     }
 
-    fn read_int_lit_token(&mut self) -> Option<Token> {
-        let mut result = 0;
+    /// Reads a single-quoted string literal, where a doubled quote (`''`) stands for one quote character.
+    fn read_string_literal_token(&mut self) -> Result<Token, ScannerError> {
+        let start = self.position;
+        self.advance();
 
-        while self.inner.peek().map_or_else(|| false, |x| x.is_digit(10)) {
-            let next = self.inner.next().unwrap();
-            result = (result * 10) + next.to_digit(10).unwrap()
+        let mut result = String::new();
+        loop {
+            match self.advance() {
+                Some('\'') if self.advance_if('\'') => result.push('\''),
+                Some('\'') => return Ok(Token::StringLiteral(result)),
+                Some(c) => result.push(c),
+                None => return Err(ScannerError::UnterminatedStringLiteral(Span::new(start, self.position))),
+            }
         }
+    }
 
-        return Some(Token::U32(result));
+    /// Reads the digits of a number, with an optional fraction and exponent as in `6.02e23`.
+    fn read_number_token(&mut self) -> Result<Token, ScannerError> {
+        let start = self.position;
+        let mut result = String::new();
+
+        self.read_digits(&mut result);
+
+        if self.advance_if('.') {
+            result.push('.');
+            self.read_digits(&mut result);
+        }
+
+        if let Some(&exponent) = self.inner.peek().filter(|c| **c == 'e' || **c == 'E') {
+            self.advance();
+            result.push(exponent);
+
+            if let Some(&sign) = self.inner.peek().filter(|c| **c == '+' || **c == '-') {
+                self.advance();
+                result.push(sign);
+            }
+
+            if !self.read_digits(&mut result) {
+                return Err(ScannerError::MalformedNumber(Span::new(start, self.position)));
+            }
+        }
+
+        Ok(Token::Number(result))
+    }
+
+    /// Appends the digits which come next to `result`, returning whether there were any.
+    fn read_digits(&mut self, result: &mut String) -> bool {
+        let length = result.len();
+        while self.inner.peek().map_or_else(|| false, |x| x.is_ascii_digit()) {
+            result.push(self.advance().unwrap());
+        }
+        result.len() > length
     }
 }
 
 impl<T: Iterator<Item=char>> Iterator for TokenIterator<T> {
-    type Item = Token;
-    fn next(&mut self) -> Option<Token> {
-        if let Some(&c) = self.inner.peek() {
+    type Item = Result<SpannedToken, ScannerError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.position;
+
+        let token = if let Some(&c) = self.inner.peek() {
             trace!("Peeked a char: [{}]", c);
-            if c.is_digit(10) {
-                return self.read_int_lit_token();
-            }
-            if c.is_alphabetic() {
-                return self.read_alphabetic_token();
+            if c.is_ascii_digit() {
+                self.read_number_token()
+            } else if c.is_alphabetic() {
+                self.read_alphabetic_token()
+            } else if c == '\'' {
+                self.read_string_literal_token()
+            } else {
+                self.read_symbol()
             }
-            return self.read_symbol();
-        }
+        } else {
+            return None;
+        };
 
-        return None;
+        Some(token.map(|token| SpannedToken::new(token, Span::new(start, self.position))))
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every token or error read from `sql`, leaving out the spaces.
+    fn scan(sql: &str) -> Vec<Result<Token, ScannerError>> {
+        TokenIterator::new_iterator(sql.chars())
+            .map(|token| token.map(SpannedToken::into_token))
+            .filter(|token| !matches!(token, Ok(Token::Space)))
+            .collect()
+    }
+
+    fn tokens(sql: &str) -> Vec<Token> {
+        scan(sql).into_iter().map(Result::unwrap).collect()
+    }
+
+    #[test]
+    fn reads_keywords_identifiers_numbers_and_symbols() {
+        assert_eq!(tokens("select Name from users where id >= 10;"), vec![
+            Token::Keyword(KeywordToken::SELECT),
+            Token::Identifier("Name".to_owned()),
+            Token::Keyword(KeywordToken::FROM),
+            Token::Identifier("users".to_owned()),
+            Token::Keyword(KeywordToken::WHERE),
+            Token::Identifier("id".to_owned()),
+            Token::GreaterThanOrEqual,
+            Token::Number("10".to_owned()),
+            Token::SemiColon,
+        ]);
+        assert_eq!(tokens("<> != <= < > = ( ) , * . + - /"), vec![
+            Token::NotEqual, Token::NotEqual, Token::LessThanOrEqual, Token::LessThan, Token::GreaterThan,
+            Token::Assignment, Token::LeftBracket, Token::RightBracket, Token::Comma, Token::Asterisk,
+            Token::Dot, Token::Plus, Token::Minus, Token::Slash,
+        ]);
+        assert_eq!(tokens("1.5 6.02e23 1E-3 7. a1"), vec![
+            Token::Number("1.5".to_owned()),
+            Token::Number("6.02e23".to_owned()),
+            Token::Number("1E-3".to_owned()),
+            Token::Number("7.".to_owned()),
+            Token::Identifier("a1".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn reports_errors_and_carries_on_after_them() {
+        let scanned = scan("a # b 1e+ c");
+        assert_eq!(scanned.len(), 5);
+        assert_eq!(scanned[0].as_ref().unwrap(), &Token::Identifier("a".to_owned()));
+        assert!(matches!(scanned[1], Err(ScannerError::UnexpectedCharacter('#', _))));
+        assert_eq!(scanned[2].as_ref().unwrap(), &Token::Identifier("b".to_owned()));
+        assert!(matches!(scanned[3], Err(ScannerError::MalformedNumber(_))));
+        assert_eq!(scanned[4].as_ref().unwrap(), &Token::Identifier("c".to_owned()));
+
+        assert_eq!(scan("!").len(), 1);
+        assert!(matches!(scan("!")[0], Err(ScannerError::UnexpectedCharacter('!', _))));
+        assert_eq!(scan("'abc")[0].as_ref().unwrap_err().to_string(), "Unterminated string literal at line 1, column 1");
+    }
+
+    #[test]
+    fn tokens_know_where_they_were_read_from() {
+        let spans = TokenIterator::new_iterator("SELECT a\n  FROM 'x y'".chars())
+            .map(|token| {
+                let span = token.unwrap().span();
+                (span.start().line(), span.start().column(), span.end().line(), span.end().column(), span.start().offset())
+            })
+            .collect::<Vec<(usize, usize, usize, usize, usize)>>();
+
+        assert_eq!(spans, vec![
+            (1, 1, 1, 7, 0),
+            (1, 7, 1, 8, 6),
+            (1, 8, 1, 9, 7),
+            (1, 9, 2, 1, 8),
+            (2, 1, 2, 2, 9),
+            (2, 2, 2, 3, 10),
+            (2, 3, 2, 7, 11),
+            (2, 7, 2, 8, 15),
+            (2, 8, 2, 13, 16),
+        ]);
+    }
+
+    #[test]
+    fn spans_are_rendered_under_their_line() {
+        let source = "SELECT a\nFROM users WHERE";
+        let span = Span::new(Position::new(2, 6, 14), Position::new(2, 11, 19));
+        assert_eq!(span.render(source), "  |\n2 | FROM users WHERE\n  |      ^^^^^");
+
+        let span = Span::new(Position::new(1, 9, 8), Position::new(2, 1, 9));
+        assert_eq!(span.render(source), "  |\n1 | SELECT a\n  |         ^");
+    }
+
+    #[test]
+    fn string_literals_keep_spaces_and_doubled_quotes() {
+        assert_eq!(tokens("'hello world'"), vec![Token::StringLiteral("hello world".to_owned())]);
+        assert_eq!(tokens("'it''s' ''''"), vec![Token::StringLiteral("it's".to_owned()), Token::StringLiteral("'".to_owned())]);
+        assert_eq!(tokens("''"), vec![Token::StringLiteral(String::new())]);
+        assert_eq!(tokens("'a;b\nc -- d'"), vec![Token::StringLiteral("a;b\nc -- d".to_owned())]);
+        assert_eq!(tokens("'SELECT' 'x'"), vec![Token::StringLiteral("SELECT".to_owned()), Token::StringLiteral("x".to_owned())]);
+        assert_eq!(tokens("'é ✓'"), vec![Token::StringLiteral("é ✓".to_owned())]);
+
+        let scanned = scan("'it''s");
+        assert!(matches!(&scanned[..], [Err(ScannerError::UnterminatedStringLiteral(span))] if span.end().column() == 7));
+    }
+}
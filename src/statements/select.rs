@@ -1,27 +1,238 @@
 use std::borrow::Borrow;
 
+use crate::statements::expression;
+
+/// One item of the list of values a SELECT returns.
 #[derive(Debug)]
 pub enum Expression {
-    Column(String),
+    /// A value worked out for every row, with the alias it is given, if any.
+    Value(expression::Expression, Option<String>),
     All,
+    /// Every column of one table, as in `users.*`.
+    AllOf(String),
+}
+
+/// A table named in the FROM clause, which may be given an alias to refer to it by.
+#[derive(Debug)]
+pub struct TableReference {
+    name: String,
+    alias: Option<String>,
+}
+
+impl TableReference {
+    pub fn new(name: String, alias: Option<String>) -> Self {
+        TableReference { name, alias }
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn alias(&self) -> Option<&str> {
+        self.alias.as_deref()
+    }
+
+    /// The name the rest of the statement uses for the table, which is its alias when it has one.
+    pub fn binding(&self) -> &str {
+        self.alias().unwrap_or_else(|| self.name())
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum JoinKind {
+    Inner,
+    /// Keeps every row on the left, filling the right table's columns with NULL when nothing matches.
+    Left,
+}
+
+/// A table joined to the ones before it, keeping the combinations of rows where `on` holds.
+#[derive(Debug)]
+pub struct Join {
+    kind: JoinKind,
+    table: TableReference,
+    on: expression::Expression,
+}
+
+impl Join {
+    pub fn new(kind: JoinKind, table: TableReference, on: expression::Expression) -> Self {
+        Join { kind, table, on }
+    }
+
+    pub fn kind(&self) -> JoinKind {
+        self.kind
+    }
+
+    pub fn table(&self) -> &TableReference {
+        &self.table
+    }
+
+    pub fn on(&self) -> &expression::Expression {
+        &self.on
+    }
+}
+
+/// The tables a SELECT reads from: the first one and any joined to it, in the order written.
+#[derive(Debug)]
+pub struct FromClause {
+    table: TableReference,
+    joins: Vec<Join>,
+}
+
+impl FromClause {
+    pub fn new(table: TableReference, joins: Vec<Join>) -> Self {
+        FromClause { table, joins }
+    }
+
+    pub fn table(&self) -> &TableReference {
+        &self.table
+    }
+
+    pub fn joins(&self) -> &[Join] {
+        self.joins.borrow()
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum NullsOrder {
+    First,
+    Last,
+}
+
+/// One item of an ORDER BY clause.
+#[derive(Debug)]
+pub struct OrderBy {
+    expression: expression::Expression,
+    direction: SortDirection,
+    nulls: Option<NullsOrder>,
+}
+
+impl OrderBy {
+    pub fn new(expression: expression::Expression, direction: SortDirection, nulls: Option<NullsOrder>) -> Self {
+        OrderBy { expression, direction, nulls }
+    }
+
+    pub fn expression(&self) -> &expression::Expression {
+        &self.expression
+    }
+
+    pub fn direction(&self) -> SortDirection {
+        self.direction
+    }
+
+    /// NULLs sort as if they were larger than every value unless NULLS FIRST or NULLS LAST says
+    /// otherwise, so they come last when ascending and first when descending.
+    pub fn nulls_first(&self) -> bool {
+        match self.nulls {
+            Some(NullsOrder::First) => true,
+            Some(NullsOrder::Last) => false,
+            None => self.direction == SortDirection::Descending,
+        }
+    }
+}
+
+/// A LIMIT clause with its optional OFFSET.
+#[derive(Debug, Clone, Copy)]
+pub struct Limit {
+    count: usize,
+    offset: usize,
+}
+
+impl Limit {
+    pub fn new(count: usize, offset: usize) -> Self {
+        Limit { count, offset }
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
 }
 
 #[derive(Debug)]
 pub struct SelectStatement {
     item: Vec<Expression>,
-    from: String,
+    from: FromClause,
+    predicate: Option<expression::Expression>,
+    group_by: Vec<expression::Expression>,
+    having: Option<expression::Expression>,
+    order_by: Vec<OrderBy>,
+    limit: Option<Limit>,
 }
 
 impl SelectStatement {
-    pub fn new(item: Vec<Expression>, from: String) -> Self {
-        SelectStatement { item, from }
+    pub fn new(
+        item: Vec<Expression>,
+        from: FromClause,
+        predicate: Option<expression::Expression>,
+        group_by: Vec<expression::Expression>,
+        having: Option<expression::Expression>,
+        order_by: Vec<OrderBy>,
+        limit: Option<Limit>,
+    ) -> Self {
+        SelectStatement { item, from, predicate, group_by, having, order_by, limit }
     }
 
+    /// The name of the first table in the FROM clause.
     pub fn table_name(&self) -> &str {
-        self.from.as_str()
+        self.from.table().name()
+    }
+
+    pub fn from(&self) -> &FromClause {
+        &self.from
     }
 
     pub fn expression(&self) -> &[Expression] {
         self.item.borrow()
     }
+
+    pub fn predicate(&self) -> Option<&expression::Expression> {
+        self.predicate.as_ref()
+    }
+
+    pub fn group_by(&self) -> &[expression::Expression] {
+        self.group_by.borrow()
+    }
+
+    pub fn having(&self) -> Option<&expression::Expression> {
+        self.having.as_ref()
+    }
+
+    pub fn order_by(&self) -> &[OrderBy] {
+        self.order_by.borrow()
+    }
+
+    /// The most rows to return, or `None` for all of them.
+    pub fn limit(&self) -> Option<usize> {
+        self.limit.map(|limit| limit.count())
+    }
+
+    /// How many rows to skip before returning any.
+    pub fn offset(&self) -> usize {
+        self.limit.map_or(0, |limit| limit.offset())
+    }
+
+    /// Whether the rows are collapsed into groups, which happens when there is a GROUP BY, a HAVING
+    /// or an aggregate function anywhere in the statement.
+    pub fn is_grouped(&self) -> bool {
+        let values = self.item.iter().filter_map(|item| match item {
+            Expression::Value(expression, _) => Some(expression),
+            Expression::All | Expression::AllOf(_) => None,
+        });
+
+        let mut has_aggregate = false;
+        for expression in values.chain(&self.having).chain(self.order_by.iter().map(|item| item.expression())) {
+            expression.visit_aggregates(&mut |_| has_aggregate = true);
+        }
+
+        has_aggregate || !self.group_by.is_empty() || self.having.is_some()
+    }
 }
\ No newline at end of file
@@ -0,0 +1,16 @@
+use std::borrow::Borrow;
+
+#[derive(Debug)]
+pub struct TruncateTableStatement {
+    table: String,
+}
+
+impl TruncateTableStatement {
+    pub fn new(table: String) -> Self {
+        TruncateTableStatement { table }
+    }
+
+    pub fn table_name(&self) -> &str {
+        self.table.borrow()
+    }
+}
@@ -0,0 +1,48 @@
+use std::borrow::Borrow;
+
+use crate::statements::expression::Expression;
+
+#[derive(Debug)]
+pub struct Assignment {
+    column: String,
+    value: Expression,
+}
+
+impl Assignment {
+    pub fn new(column: String, value: Expression) -> Self {
+        Assignment { column, value }
+    }
+
+    pub fn column(&self) -> &str {
+        self.column.borrow()
+    }
+
+    pub fn value(&self) -> &Expression {
+        &self.value
+    }
+}
+
+#[derive(Debug)]
+pub struct UpdateStatement {
+    table: String,
+    assignments: Vec<Assignment>,
+    predicate: Option<Expression>,
+}
+
+impl UpdateStatement {
+    pub fn new(table: String, assignments: Vec<Assignment>, predicate: Option<Expression>) -> Self {
+        UpdateStatement { table, assignments, predicate }
+    }
+
+    pub fn table_name(&self) -> &str {
+        self.table.borrow()
+    }
+
+    pub fn assignments(&self) -> &[Assignment] {
+        self.assignments.borrow()
+    }
+
+    pub fn predicate(&self) -> Option<&Expression> {
+        self.predicate.as_ref()
+    }
+}
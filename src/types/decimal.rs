@@ -0,0 +1,439 @@
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use crate::Result;
+
+/// The most digits a decimal can have, which is as many as always fit in an `i128`.
+pub const MAX_PRECISION: u8 = 38;
+
+/// The precision of a DECIMAL written without one, which is as many digits as always fit in a BIGINT.
+pub const DEFAULT_PRECISION: u8 = 18;
+
+/// The fewest decimal places the result of a division has, so that `1 / 3` is not rounded to 0.
+const MIN_DIVISION_SCALE: u8 = 6;
+
+fn pow10(exponent: u32) -> Option<i128> {
+    10i128.checked_pow(exponent)
+}
+
+/// Divides, rounding halfway cases away from zero.
+fn div_round(numerator: i128, denominator: i128) -> i128 {
+    let quotient = numerator / denominator;
+    let remainder = (numerator % denominator).unsigned_abs();
+
+    if remainder >= denominator.unsigned_abs() - remainder {
+        if (numerator < 0) == (denominator < 0) { quotient + 1 } else { quotient - 1 }
+    } else {
+        quotient
+    }
+}
+
+/// An unsigned 256-bit number, wide enough to hold the product of two decimals, or a dividend with
+/// the extra places a division needs, before it is rounded back down to 38 digits.
+#[derive(Debug, Clone, Copy)]
+struct Wide {
+    high: u128,
+    low: u128,
+}
+
+impl Wide {
+    const HALF: u32 = 64;
+    const HALF_MASK: u128 = u64::MAX as u128;
+
+    fn new(value: u128) -> Self {
+        Wide { high: 0, low: value }
+    }
+
+    /// Multiplies two numbers, which can never overflow.
+    fn product(left: u128, right: u128) -> Wide {
+        let (left_high, left_low) = (left >> Wide::HALF, left & Wide::HALF_MASK);
+        let (right_high, right_low) = (right >> Wide::HALF, right & Wide::HALF_MASK);
+
+        let low = left_low * right_low;
+        let cross_left = left_high * right_low;
+        let cross_right = left_low * right_high;
+        let high = left_high * right_high;
+
+        // At most three 64-bit numbers, so this cannot overflow
+        let middle = (low >> Wide::HALF) + (cross_left & Wide::HALF_MASK) + (cross_right & Wide::HALF_MASK);
+
+        Wide {
+            high: high + (cross_left >> Wide::HALF) + (cross_right >> Wide::HALF) + (middle >> Wide::HALF),
+            low: (low & Wide::HALF_MASK) | (middle << Wide::HALF),
+        }
+    }
+
+    fn checked_mul(&self, factor: u128) -> Option<Wide> {
+        let low = Wide::product(self.low, factor);
+        let high = self.high.checked_mul(factor)?.checked_add(low.high)?;
+        Some(Wide { high, low: low.low })
+    }
+
+    /// Divides one bit at a time, returning the quotient and the remainder.
+    fn div_rem(&self, divisor: u128) -> (Wide, u128) {
+        let mut quotient = Wide::new(0);
+        let mut remainder = 0u128;
+
+        for bit in (0..256).rev() {
+            let (word, shift) = if bit >= 128 { (self.high, bit - 128) } else { (self.low, bit) };
+            let carry = remainder >> 127;
+            remainder = (remainder << 1) | ((word >> shift) & 1);
+
+            if carry == 1 || remainder >= divisor {
+                remainder = remainder.wrapping_sub(divisor);
+                if bit >= 128 {
+                    quotient.high |= 1 << (bit - 128);
+                } else {
+                    quotient.low |= 1 << bit;
+                }
+            }
+        }
+
+        (quotient, remainder)
+    }
+
+    /// The number as a `u128`, or `None` when it is too large for one.
+    fn narrow(&self) -> Option<u128> {
+        if self.high == 0 { Some(self.low) } else { None }
+    }
+
+    /// Divides, rounding halfway cases up, which is away from zero as the number has no sign.
+    fn div_round(&self, divisor: u128) -> Option<u128> {
+        let (quotient, remainder) = self.div_rem(divisor);
+        let quotient = quotient.narrow()?;
+        if remainder >= divisor - remainder {
+            quotient.checked_add(1)
+        } else {
+            Some(quotient)
+        }
+    }
+}
+
+/// Gives `magnitude` the sign of the result of multiplying or dividing `left` and `right`, or
+/// `None` when it does not fit in an `i128`.
+fn signed(magnitude: u128, left: i128, right: i128) -> Option<i128> {
+    let value = i128::try_from(magnitude).ok()?;
+    Some(if (left < 0) != (right < 0) { -value } else { value })
+}
+
+/// An exact decimal number: `value` divided by 10 to the power of `scale`, so 12.50 is a value of
+/// 1250 with a scale of 2.
+#[derive(Debug, Clone, Copy)]
+pub struct Decimal {
+    value: i128,
+    scale: u8,
+}
+
+impl Decimal {
+    pub fn new(value: i128, scale: u8) -> Self {
+        Decimal { value, scale }
+    }
+
+    /// Reads a number such as `-12.50`, keeping every digit it was written with.
+    pub fn parse(text: &str) -> Result<Decimal> {
+        let invalid = || -> crate::Error { format!("Invalid DECIMAL {:?}", text).into() };
+
+        let (negative, unsigned) = match text.strip_prefix('-') {
+            Some(unsigned) => (true, unsigned),
+            None => (false, text),
+        };
+        let (whole, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+
+        if (whole.is_empty() && fraction.is_empty()) || !whole.bytes().chain(fraction.bytes()).all(|byte| byte.is_ascii_digit()) {
+            return Err(invalid());
+        }
+
+        let digits = format!("{}{}", whole, fraction);
+        if digits.trim_start_matches('0').len() > MAX_PRECISION as usize || fraction.len() > MAX_PRECISION as usize {
+            return Err(format!("Number {} has more than the {} digits a DECIMAL can hold", text, MAX_PRECISION).into());
+        }
+
+        let value = if digits.is_empty() { 0 } else { digits.parse::<i128>().map_err(|_| invalid())? };
+        Ok(Decimal::new(if negative { -value } else { value }, fraction.len() as u8))
+    }
+
+    /// The digits of the number, without its decimal point.
+    pub fn value(&self) -> i128 {
+        self.value
+    }
+
+    /// How many of the digits are after the decimal point.
+    pub fn scale(&self) -> u8 {
+        self.scale
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        // Going through the text rounds correctly, which dividing by a power of ten would not
+        self.to_string().parse().unwrap_or(f64::NAN)
+    }
+
+    /// The value when it is a whole number, which is `None` for anything with a fraction.
+    pub fn to_integer(&self) -> Option<i128> {
+        let divisor = pow10(self.scale.into())?;
+        if self.value % divisor == 0 {
+            Some(self.value / divisor)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the number can be written with no more than `precision` digits at its scale.
+    pub fn fits(&self, precision: u8) -> bool {
+        pow10(precision.into()).is_none_or(|limit| self.value.unsigned_abs() < limit.unsigned_abs())
+    }
+
+    /// Changes the number of decimal places. Removing places rounds halfway cases away from zero,
+    /// while adding them returns `None` when the digits no longer fit.
+    pub fn rescale(&self, scale: u8) -> Option<Decimal> {
+        match scale.cmp(&self.scale) {
+            Ordering::Equal => Some(*self),
+            Ordering::Greater => Some(Decimal::new(self.value.checked_mul(pow10((scale - self.scale).into())?)?, scale)),
+            Ordering::Less => Some(Decimal::new(div_round(self.value, pow10((self.scale - scale).into())?), scale)),
+        }
+    }
+
+    /// Rescales both numbers to the larger of their scales.
+    fn align(&self, other: &Decimal) -> Option<(i128, i128, u8)> {
+        let scale = self.scale.max(other.scale);
+        Some((self.rescale(scale)?.value, other.rescale(scale)?.value, scale))
+    }
+
+    pub fn checked_add(&self, other: &Decimal) -> Option<Decimal> {
+        let (left, right, scale) = self.align(other)?;
+        Decimal::within_precision(left.checked_add(right)?, scale)
+    }
+
+    pub fn checked_sub(&self, other: &Decimal) -> Option<Decimal> {
+        let (left, right, scale) = self.align(other)?;
+        Decimal::within_precision(left.checked_sub(right)?, scale)
+    }
+
+    /// Multiplies exactly, unless that needs more than the maximum number of decimal places, when
+    /// the result is rounded to fit. The product is worked out in 256 bits, as it can need twice as
+    /// many digits as either number before it is rounded.
+    pub fn checked_mul(&self, other: &Decimal) -> Option<Decimal> {
+        let scale = self.scale + other.scale;
+        let product = Wide::product(self.value.unsigned_abs(), other.value.unsigned_abs());
+
+        let magnitude = if scale > MAX_PRECISION {
+            product.div_round(pow10((scale - MAX_PRECISION).into())?.unsigned_abs())?
+        } else {
+            product.narrow()?
+        };
+
+        Decimal::within_precision(signed(magnitude, self.value, other.value)?, scale.min(MAX_PRECISION))
+    }
+
+    /// Divides, rounding to `division_scale` decimal places. Returns `None` when `other` is zero.
+    pub fn checked_div(&self, other: &Decimal) -> Option<Decimal> {
+        if other.value == 0 {
+            return None;
+        }
+
+        // The dividend is widened to 256 bits, as it can need up to twice the maximum number of
+        // decimal places for the quotient to have `scale` of them
+        let scale = Decimal::division_scale(self.scale, other.scale);
+        let mut numerator = Wide::new(self.value.unsigned_abs());
+        let mut places = u32::from(scale + other.scale - self.scale);
+        while places > 0 {
+            let step = places.min(MAX_PRECISION.into());
+            numerator = numerator.checked_mul(pow10(step)?.unsigned_abs())?;
+            places -= step;
+        }
+
+        let quotient = numerator.div_round(other.value.unsigned_abs())?;
+        Decimal::within_precision(signed(quotient, self.value, other.value)?, scale)
+    }
+
+    /// The number of decimal places in the result of dividing numbers with these scales.
+    pub fn division_scale(left: u8, right: u8) -> u8 {
+        left.max(right).max(MIN_DIVISION_SCALE)
+    }
+
+    fn within_precision(value: i128, scale: u8) -> Option<Decimal> {
+        let decimal = Decimal::new(value, scale);
+        if decimal.fits(MAX_PRECISION) {
+            Some(decimal)
+        } else {
+            None
+        }
+    }
+
+    /// The same number without trailing zeros after the decimal point, so 1.50 and 1.5 are equal.
+    fn normalized(&self) -> Decimal {
+        let mut decimal = *self;
+        while decimal.scale > 0 && decimal.value % 10 == 0 {
+            decimal = Decimal::new(decimal.value / 10, decimal.scale - 1);
+        }
+        decimal
+    }
+}
+
+impl From<i64> for Decimal {
+    fn from(value: i64) -> Self {
+        Decimal::new(value.into(), 0)
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (left, right) = (self.normalized(), other.normalized());
+        match left.align(&right) {
+            Some((left, right, _)) => left.cmp(&right),
+            // Aligning only overflows when the number with fewer places has more whole digits
+            None if left.scale < right.scale => if left.value < 0 { Ordering::Less } else { Ordering::Greater },
+            None => if right.value < 0 { Ordering::Greater } else { Ordering::Less },
+        }
+    }
+}
+
+impl Hash for Decimal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let normalized = self.normalized();
+        normalized.value.hash(state);
+        normalized.scale.hash(state);
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.value < 0 { "-" } else { "" };
+        let digits = format!("{:0>width$}", self.value.unsigned_abs(), width = self.scale as usize + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - self.scale as usize);
+
+        if fraction.is_empty() {
+            write!(f, "{}{}", sign, whole)
+        } else {
+            write!(f, "{}{}.{}", sign, whole, fraction)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::hash_map::DefaultHasher;
+
+    use super::*;
+
+    fn decimal(text: &str) -> Decimal {
+        Decimal::parse(text).unwrap()
+    }
+
+    fn hash(decimal: &Decimal) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        decimal.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn parses_every_digit_it_is_written_with() {
+        assert_eq!((decimal("12.50").value(), decimal("12.50").scale()), (1250, 2));
+        assert_eq!((decimal("-0.05").value(), decimal("-0.05").scale()), (-5, 2));
+        assert_eq!((decimal("007.10").value(), decimal("007.10").scale()), (710, 2));
+        assert_eq!((decimal(".5").value(), decimal(".5").scale()), (5, 1));
+        assert_eq!((decimal("5.").value(), decimal("5.").scale()), (5, 0));
+        assert_eq!(decimal("-0"), decimal("0"));
+
+        for text in ["", "-", ".", "1.2.3", "--1", "+1", "1e5", "1,5", " 1"] {
+            assert!(Decimal::parse(text).is_err(), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn parses_at_most_38_digits() {
+        let digits = "9".repeat(38);
+        assert_eq!(decimal(&digits).value(), 10i128.pow(38) - 1);
+        assert_eq!(decimal(&format!("-{}", digits)).value(), 1 - 10i128.pow(38));
+        assert_eq!(decimal(&format!("000{}", digits)).value(), 10i128.pow(38) - 1);
+        assert_eq!(decimal(&format!("0.{}", digits)).scale(), 38);
+
+        assert!(Decimal::parse(&format!("1{}", digits)).is_err());
+        assert!(Decimal::parse(&format!("9.{}", digits)).is_err());
+        assert!(Decimal::parse(&format!("0.0{}", digits)).is_err());
+    }
+
+    #[test]
+    fn rounds_halfway_cases_away_from_zero() {
+        assert_eq!(div_round(5, 2), 3);
+        assert_eq!(div_round(-5, 2), -3);
+        assert_eq!(div_round(5, -2), -3);
+        assert_eq!(div_round(-5, -2), 3);
+        assert_eq!(div_round(-7, 3), -2);
+        assert_eq!(div_round(-8, 3), -3);
+        assert_eq!(div_round(-1, 4), 0);
+        assert_eq!(div_round(-2, 4), -1);
+    }
+
+    #[test]
+    fn rescales() {
+        assert_eq!(decimal("1.25").rescale(1).unwrap().to_string(), "1.3");
+        assert_eq!(decimal("-1.25").rescale(1).unwrap().to_string(), "-1.3");
+        assert_eq!(decimal("-1.24").rescale(1).unwrap().to_string(), "-1.2");
+        assert_eq!(decimal("-0.5").rescale(0).unwrap().to_string(), "-1");
+        assert_eq!(decimal("0.4").rescale(0).unwrap().to_string(), "0");
+        assert_eq!(decimal("1.5").rescale(4).unwrap().to_string(), "1.5000");
+        assert!(decimal(&"9".repeat(38)).rescale(1).is_none());
+    }
+
+    #[test]
+    fn equal_values_compare_and_hash_the_same_at_any_scale() {
+        assert_eq!(decimal("1.50"), decimal("1.5"));
+        assert_eq!(hash(&decimal("1.50")), hash(&decimal("1.5")));
+        assert_eq!(hash(&decimal("-2.000")), hash(&Decimal::from(-2)));
+        assert_eq!(hash(&decimal("0.00")), hash(&decimal("-0")));
+
+        assert!(decimal("1.5") < decimal("1.51"));
+        assert!(decimal("-1.5") < decimal("-1.49"));
+        assert!(decimal("0.00000000000000000000000000000000000001") < decimal(&"9".repeat(38)));
+        assert!(decimal(&format!("-{}", "9".repeat(38))) < decimal("-0.00000000000000000000000000000000000001"));
+    }
+
+    #[test]
+    fn displays_leading_zeros_and_the_sign() {
+        assert_eq!(decimal("-0.05").to_string(), "-0.05");
+        assert_eq!(decimal("0.05").to_string(), "0.05");
+        assert_eq!(decimal("-12.50").to_string(), "-12.50");
+        assert_eq!(Decimal::new(-5, 0).to_string(), "-5");
+        assert_eq!(Decimal::new(7, 3).to_string(), "0.007");
+    }
+
+    #[test]
+    fn multiplies_and_divides_without_overflowing() {
+        // Both products need more than 38 digits before they are rounded to 38 decimal places
+        let half = decimal("0.50000000000000000001");
+        assert_eq!(half.checked_mul(&half).unwrap().to_string(), "0.25000000000000000001000000000000000000");
+        let small = decimal("0.00000000000000000008");
+        assert_eq!(small.checked_mul(&small).unwrap().to_string(), format!("0.{}1", "0".repeat(37)));
+        assert_eq!(decimal("0.12345678901234567890").checked_mul(&decimal("-0.98765432109876543210")).unwrap().to_string(),
+            "-0.12193263113702179522374638011112635269");
+
+        let third = decimal("1").checked_div(&decimal("3")).unwrap();
+        assert_eq!(third.to_string(), "0.333333");
+        assert_eq!(decimal("-2").checked_div(&decimal("3")).unwrap().to_string(), "-0.666667");
+        // The dividend needs 14 more places, which takes it past an i128
+        assert_eq!(decimal("1234567890123456789012.345678").checked_div(&decimal("2.0000000000")).unwrap().to_string(),
+            "617283945061728394506.1728390000");
+        assert_eq!(decimal("1").checked_div(&decimal("0.00000000000000000000000000000000000003")).map(|value| value.to_string()), None);
+        assert_eq!(decimal("1").checked_div(&decimal("0")), None);
+
+        assert_eq!(decimal(&"9".repeat(20)).checked_mul(&decimal(&"9".repeat(20))), None);
+        assert_eq!(decimal(&"9".repeat(19)).checked_mul(&decimal(&"9".repeat(19))).unwrap().to_string(),
+            format!("{}8{}1", "9".repeat(18), "0".repeat(18)));
+    }
+}
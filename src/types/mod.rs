@@ -0,0 +1,2 @@
+pub mod decimal;
+pub mod temporal;
@@ -0,0 +1,582 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+use crate::Result;
+
+const MICROS_PER_SECOND: i64 = 1_000_000;
+const MICROS_PER_MINUTE: i64 = 60 * MICROS_PER_SECOND;
+const MICROS_PER_HOUR: i64 = 60 * MICROS_PER_MINUTE;
+const MICROS_PER_DAY: i64 = 24 * MICROS_PER_HOUR;
+
+/// Dates run from 0001-01-01 to 9999-12-31, so every one of them has a four digit year.
+const MIN_DAYS: i64 = days_from_civil(1, 1, 1);
+const MAX_DAYS: i64 = days_from_civil(9999, 12, 31);
+
+/// Counts the days from 1970-01-01 to a date of the proleptic Gregorian calendar.
+const fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// The inverse of `days_from_civil`, returning the year, month and day.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = if days >= 0 { days } else { days - 146096 } / 146097;
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    (if month <= 2 { year_of_era + era * 400 + 1 } else { year_of_era + era * 400 }, month, day)
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Reads exactly `length` ASCII digits.
+fn digits(text: &str, length: usize) -> Option<i64> {
+    if text.len() != length || !text.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok()
+}
+
+/// Reads a number of seconds with up to six decimal places, like `-1.5`, as microseconds.
+fn parse_seconds(text: &str) -> Option<i64> {
+    let (negative, text) = match text.strip_prefix('-') {
+        Some(text) => (true, text),
+        None => (false, text),
+    };
+    let (whole, fraction) = match text.split_once('.') {
+        Some((whole, fraction)) => (whole, fraction),
+        None => (text, ""),
+    };
+
+    if whole.is_empty() || fraction.len() > 6 || !whole.bytes().chain(fraction.bytes()).all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+
+    let fraction = if fraction.is_empty() { 0 } else { format!("{:0<6}", fraction).parse::<i64>().ok()? };
+    let micros = whole.parse::<i64>().ok()?.checked_mul(MICROS_PER_SECOND)?.checked_add(fraction)?;
+    Some(if negative { -micros } else { micros })
+}
+
+/// Writes the fraction of a second, without trailing zeros, or nothing for a whole second.
+fn write_fraction(f: &mut fmt::Formatter<'_>, micros: i64) -> fmt::Result {
+    if micros == 0 {
+        return Ok(());
+    }
+    write!(f, ".{}", format!("{:06}", micros).trim_end_matches('0'))
+}
+
+/// A calendar date, counted in days since 1970-01-01.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct Date(i32);
+
+impl Date {
+    /// Returns `None` for days outside of the years 1 to 9999.
+    pub fn from_days(days: i64) -> Option<Date> {
+        if (MIN_DAYS..=MAX_DAYS).contains(&days) {
+            Some(Date(days as i32))
+        } else {
+            None
+        }
+    }
+
+    pub fn from_ymd(year: i64, month: i64, day: i64) -> Option<Date> {
+        if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+            return None;
+        }
+        Date::from_days(days_from_civil(year, month, day))
+    }
+
+    /// Reads an ISO-8601 date such as `2024-01-31`.
+    pub fn parse(text: &str) -> Result<Date> {
+        Date::parse_parts(text).ok_or_else(|| format!("Invalid DATE {:?}, expected YYYY-MM-DD", text).into())
+    }
+
+    fn parse_parts(text: &str) -> Option<Date> {
+        let mut parts = text.split('-');
+        let date = Date::from_ymd(digits(parts.next()?, 4)?, digits(parts.next()?, 2)?, digits(parts.next()?, 2)?)?;
+        match parts.next() {
+            Some(_) => None,
+            None => Some(date),
+        }
+    }
+
+    pub fn days(&self) -> i32 {
+        self.0
+    }
+
+    /// The year, month and day.
+    pub fn ymd(&self) -> (i64, i64, i64) {
+        civil_from_days(self.0.into())
+    }
+
+    /// Moves the date by whole months and days. Adding months keeps the day of the month, unless
+    /// the month is too short for it, so 2024-01-31 plus a month is 2024-02-29.
+    pub fn checked_add(&self, interval: &Interval) -> Option<Date> {
+        let (year, month, day) = self.ymd();
+
+        let months = (year * 12 + month - 1).checked_add(interval.months.into())?;
+        let (year, month) = (months.div_euclid(12), months.rem_euclid(12) + 1);
+        if !(1..=9999).contains(&year) {
+            return None;
+        }
+
+        let date = Date::from_ymd(year, month, day.min(days_in_month(year, month)))?;
+        Date::from_days(i64::from(date.0) + i64::from(interval.days))
+    }
+
+    /// The number of days from `other` to this date.
+    pub fn since(&self, other: &Date) -> Interval {
+        Interval::new(0, self.0 - other.0, 0)
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (year, month, day) = self.ymd();
+        write!(f, "{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+/// A time of day, counted in microseconds since midnight.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct Time(i64);
+
+impl Time {
+    pub fn from_micros(micros: i64) -> Option<Time> {
+        if (0..MICROS_PER_DAY).contains(&micros) {
+            Some(Time(micros))
+        } else {
+            None
+        }
+    }
+
+    /// Reads an ISO-8601 time such as `13:45`, `13:45:30` or `13:45:30.25`.
+    pub fn parse(text: &str) -> Result<Time> {
+        Time::parse_parts(text).ok_or_else(|| format!("Invalid TIME {:?}, expected HH:MM:SS", text).into())
+    }
+
+    fn parse_parts(text: &str) -> Option<Time> {
+        let mut parts = text.split(':');
+        let hours = digits(parts.next()?, 2)?;
+        let minutes = digits(parts.next()?, 2)?;
+        let seconds = match parts.next() {
+            Some(seconds) if seconds.len() >= 2 && !seconds.starts_with('-') => parse_seconds(seconds)?,
+            Some(_) => return None,
+            None => 0,
+        };
+
+        if parts.next().is_some() || hours > 23 || minutes > 59 || seconds >= MICROS_PER_MINUTE {
+            return None;
+        }
+
+        Time::from_micros(hours * MICROS_PER_HOUR + minutes * MICROS_PER_MINUTE + seconds)
+    }
+
+    pub fn micros(&self) -> i64 {
+        self.0
+    }
+
+    /// Moves the time by `micros`, wrapping around midnight like a clock.
+    pub fn wrapping_add(&self, micros: i64) -> Time {
+        Time((self.0 + micros.rem_euclid(MICROS_PER_DAY)).rem_euclid(MICROS_PER_DAY))
+    }
+
+    pub fn since(&self, other: &Time) -> Interval {
+        Interval::new(0, 0, self.0 - other.0)
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let seconds = self.0 / MICROS_PER_SECOND;
+        write!(f, "{:02}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)?;
+        write_fraction(f, self.0 % MICROS_PER_SECOND)
+    }
+}
+
+/// A date and time of day without a time zone, counted in microseconds since 1970-01-01 00:00:00.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct Timestamp(i64);
+
+impl Timestamp {
+    pub fn new(date: Date, time: Time) -> Self {
+        Timestamp(i64::from(date.0) * MICROS_PER_DAY + time.0)
+    }
+
+    pub fn from_micros(micros: i64) -> Option<Timestamp> {
+        if (MIN_DAYS * MICROS_PER_DAY..(MAX_DAYS + 1) * MICROS_PER_DAY).contains(&micros) {
+            Some(Timestamp(micros))
+        } else {
+            None
+        }
+    }
+
+    /// Reads an ISO-8601 date and time such as `2024-01-31T13:45:30`, where a space may stand in
+    /// for the `T`. A date on its own is midnight at the start of that day.
+    pub fn parse(text: &str) -> Result<Timestamp> {
+        let parsed = match text.find(['T', ' ']) {
+            Some(index) => Date::parse_parts(&text[..index])
+                .and_then(|date| Some(Timestamp::new(date, Time::parse_parts(&text[index + 1..])?))),
+            None => Date::parse_parts(text).map(Timestamp::from),
+        };
+
+        parsed.ok_or_else(|| format!("Invalid TIMESTAMP {:?}, expected YYYY-MM-DD HH:MM:SS", text).into())
+    }
+
+    pub fn micros(&self) -> i64 {
+        self.0
+    }
+
+    pub fn date(&self) -> Date {
+        Date(self.0.div_euclid(MICROS_PER_DAY) as i32)
+    }
+
+    pub fn time(&self) -> Time {
+        Time(self.0.rem_euclid(MICROS_PER_DAY))
+    }
+
+    /// Adds the months and days of the interval to the date, as `Date::checked_add` does, and then
+    /// the rest of it to the time.
+    pub fn checked_add(&self, interval: &Interval) -> Option<Timestamp> {
+        let date = self.date().checked_add(&Interval::new(interval.months, interval.days, 0))?;
+        Timestamp::from_micros(Timestamp::new(date, self.time()).0.checked_add(interval.micros)?)
+    }
+
+    /// The time from `other` to this timestamp, as whole days and the time left over.
+    pub fn since(&self, other: &Timestamp) -> Interval {
+        let micros = self.0 - other.0;
+        Interval::new(0, (micros / MICROS_PER_DAY) as i32, micros % MICROS_PER_DAY)
+    }
+}
+
+impl From<Date> for Timestamp {
+    fn from(date: Date) -> Self {
+        Timestamp::new(date, Time(0))
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.date(), self.time())
+    }
+}
+
+/// A length of time. Months and days are kept apart from the rest, as how long they are depends on
+/// the date they are added to.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct Interval {
+    months: i32,
+    days: i32,
+    micros: i64,
+}
+
+#[derive(PartialEq, Clone, Copy)]
+enum Unit {
+    Year,
+    Month,
+    Week,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+impl Unit {
+    /// Reads the name of a unit in any case, in the singular or plural or shortened.
+    fn parse(name: &str) -> Option<Unit> {
+        match name.to_lowercase().as_str() {
+            "year" | "years" => Some(Unit::Year),
+            "month" | "months" | "mon" | "mons" => Some(Unit::Month),
+            "week" | "weeks" => Some(Unit::Week),
+            "day" | "days" => Some(Unit::Day),
+            "hour" | "hours" => Some(Unit::Hour),
+            "minute" | "minutes" | "min" | "mins" => Some(Unit::Minute),
+            "second" | "seconds" | "sec" | "secs" => Some(Unit::Second),
+            _ => None,
+        }
+    }
+}
+
+impl Interval {
+    pub fn new(months: i32, days: i32, micros: i64) -> Self {
+        Interval { months, days, micros }
+    }
+
+    /// Reads an interval written either as quantities and units, like `1 day 2 hours` or
+    /// `-30 minutes`, or as an ISO-8601 duration, like `P1DT2H`.
+    pub fn parse(text: &str) -> Result<Interval> {
+        let trimmed = text.trim();
+
+        let parsed = match trimmed.strip_prefix(['P', 'p']) {
+            Some(duration) => Interval::parse_duration(&duration.to_uppercase()),
+            None => Interval::parse_quantities(trimmed),
+        };
+
+        parsed.ok_or_else(|| format!("Invalid INTERVAL {:?}, expected something like '1 day 2 hours' or 'P1DT2H'", text).into())
+    }
+
+    fn parse_quantities(text: &str) -> Option<Interval> {
+        let mut words = text.split_whitespace();
+        let mut parts = Parts::default();
+
+        while let Some(quantity) = words.next() {
+            parts.add(quantity, Unit::parse(words.next()?)?)?;
+        }
+
+        parts.build()
+    }
+
+    /// Reads an interval written as a single quantity of the unit that follows it in SQL, like the
+    /// `'1'` of `INTERVAL '1' MONTH`.
+    pub fn parse_in(text: &str, unit: &str) -> Result<Interval> {
+        let parsed = Unit::parse(unit).and_then(|unit| {
+            let mut parts = Parts::default();
+            parts.add(text.trim(), unit)?;
+            parts.build()
+        });
+
+        parsed.ok_or_else(|| format!("Invalid INTERVAL {:?} {}, expected a number of {}s", text, unit.to_uppercase(), unit.to_lowercase()).into())
+    }
+
+    /// Reads the part of an ISO-8601 duration after the `P`, where the time follows a `T` so that
+    /// `M` can stand for both months and minutes.
+    fn parse_duration(text: &str) -> Option<Interval> {
+        let (date, time) = match text.split_once('T') {
+            Some((date, time)) if !time.is_empty() => (date, time),
+            Some(_) => return None,
+            None => (text, ""),
+        };
+
+        let mut parts = Parts::default();
+
+        for (text, is_time) in &[(date, false), (time, true)] {
+            let mut quantity = String::new();
+            for c in text.chars() {
+                let unit = match (c, is_time) {
+                    ('0'..='9', _) | ('.', true) => {
+                        quantity.push(c);
+                        continue;
+                    }
+                    ('Y', false) => Unit::Year,
+                    ('M', false) => Unit::Month,
+                    ('W', false) => Unit::Week,
+                    ('D', false) => Unit::Day,
+                    ('H', true) => Unit::Hour,
+                    ('M', true) => Unit::Minute,
+                    ('S', true) => Unit::Second,
+                    _ => return None,
+                };
+                parts.add(&quantity, unit)?;
+                quantity.clear();
+            }
+
+            if !quantity.is_empty() {
+                return None;
+            }
+        }
+
+        if date.is_empty() && time.is_empty() {
+            return None;
+        }
+
+        parts.build()
+    }
+
+    pub fn months(&self) -> i32 {
+        self.months
+    }
+
+    pub fn days(&self) -> i32 {
+        self.days
+    }
+
+    pub fn micros(&self) -> i64 {
+        self.micros
+    }
+
+    pub fn checked_add(&self, other: &Interval) -> Option<Interval> {
+        Some(Interval::new(
+            self.months.checked_add(other.months)?,
+            self.days.checked_add(other.days)?,
+            self.micros.checked_add(other.micros)?,
+        ))
+    }
+
+    pub fn checked_neg(&self) -> Option<Interval> {
+        Some(Interval::new(self.months.checked_neg()?, self.days.checked_neg()?, self.micros.checked_neg()?))
+    }
+
+    /// The length of the interval for comparing it with others, taking a month to be 30 days.
+    pub fn approximate_micros(&self) -> i128 {
+        (i128::from(self.months) * 30 + i128::from(self.days)) * i128::from(MICROS_PER_DAY) + i128::from(self.micros)
+    }
+}
+
+/// Renders the interval as quantities and units, which `Interval::parse` reads back.
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        let mut push = |quantity: i64, unit: &str| {
+            if quantity != 0 {
+                let plural = if quantity.abs() == 1 { "" } else { "s" };
+                parts.push(format!("{} {}{}", quantity, unit, plural));
+            }
+        };
+
+        push(i64::from(self.months / 12), "year");
+        push(i64::from(self.months % 12), "month");
+        push(i64::from(self.days), "day");
+        push(self.micros / MICROS_PER_HOUR, "hour");
+        push(self.micros % MICROS_PER_HOUR / MICROS_PER_MINUTE, "minute");
+
+        let micros = self.micros % MICROS_PER_MINUTE;
+        if micros % MICROS_PER_SECOND == 0 {
+            push(micros / MICROS_PER_SECOND, "second");
+        } else {
+            // Written by hand as the whole seconds may be zero, which would lose the sign
+            let sign = if micros < 0 { "-" } else { "" };
+            let fraction = format!("{:06}", micros.abs() % MICROS_PER_SECOND);
+            parts.push(format!("{}{}.{} seconds", sign, micros.abs() / MICROS_PER_SECOND, fraction.trim_end_matches('0')));
+        }
+
+        if parts.is_empty() {
+            write!(f, "0 seconds")
+        } else {
+            write!(f, "{}", parts.join(" "))
+        }
+    }
+}
+
+/// Adds up the quantities of an interval as it is read, checking none of them overflow.
+#[derive(Default)]
+struct Parts {
+    months: i64,
+    days: i64,
+    micros: i64,
+}
+
+impl Parts {
+    fn add(&mut self, quantity: &str, unit: Unit) -> Option<()> {
+        if unit == Unit::Second {
+            self.micros = self.micros.checked_add(parse_seconds(quantity)?)?;
+            return Some(());
+        }
+
+        let quantity = quantity.parse::<i64>().ok()?;
+        match unit {
+            Unit::Year => self.months = self.months.checked_add(quantity.checked_mul(12)?)?,
+            Unit::Month => self.months = self.months.checked_add(quantity)?,
+            Unit::Week => self.days = self.days.checked_add(quantity.checked_mul(7)?)?,
+            Unit::Day => self.days = self.days.checked_add(quantity)?,
+            Unit::Hour => self.micros = self.micros.checked_add(quantity.checked_mul(MICROS_PER_HOUR)?)?,
+            Unit::Minute => self.micros = self.micros.checked_add(quantity.checked_mul(MICROS_PER_MINUTE)?)?,
+            Unit::Second => unreachable!("seconds may have a fraction so are read above"),
+        }
+        Some(())
+    }
+
+    fn build(self) -> Option<Interval> {
+        Some(Interval::new(i32::try_from(self.months).ok()?, i32::try_from(self.days).ok()?, self.micros))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(text: &str) -> Date {
+        Date::parse(text).unwrap()
+    }
+
+    fn timestamp(text: &str) -> Timestamp {
+        Timestamp::parse(text).unwrap()
+    }
+
+    fn interval(text: &str) -> Interval {
+        Interval::parse(text).unwrap()
+    }
+
+    #[test]
+    fn adding_months_clamps_to_the_end_of_the_month() {
+        assert_eq!(date("2024-01-31").checked_add(&interval("1 month")), Some(date("2024-02-29")));
+        assert_eq!(date("2023-01-31").checked_add(&interval("1 month")), Some(date("2023-02-28")));
+        assert_eq!(date("2024-03-31").checked_add(&interval("-1 month")), Some(date("2024-02-29")));
+        assert_eq!(date("2024-05-31").checked_add(&interval("1 month")), Some(date("2024-06-30")));
+        assert_eq!(date("2024-01-31").checked_add(&interval("1 month 1 day")), Some(date("2024-03-01")));
+        assert_eq!(date("2024-12-15").checked_add(&interval("1 month")), Some(date("2025-01-15")));
+        assert_eq!(date("9999-12-01").checked_add(&interval("1 month")), None);
+    }
+
+    #[test]
+    fn leap_years() {
+        assert!(Date::from_ymd(2024, 2, 29).is_some());
+        assert!(Date::from_ymd(2000, 2, 29).is_some());
+        assert!(Date::from_ymd(2023, 2, 29).is_none());
+        assert!(Date::from_ymd(1900, 2, 29).is_none());
+
+        assert_eq!(date("2024-02-29").checked_add(&interval("1 year")), Some(date("2025-02-28")));
+        assert_eq!(date("2024-02-29").checked_add(&interval("4 years")), Some(date("2028-02-29")));
+        assert_eq!(date("2024-03-01").since(&date("2024-02-01")), Interval::new(0, 29, 0));
+        assert_eq!(date("2023-03-01").since(&date("2023-02-01")), Interval::new(0, 28, 0));
+    }
+
+    #[test]
+    fn timestamps_move_across_days() {
+        assert_eq!(timestamp("2024-02-28 23:30:00").checked_add(&interval("1 hour")), Some(timestamp("2024-02-29 00:30:00")));
+        assert_eq!(timestamp("2024-03-01 00:15:00").checked_add(&interval("-30 minutes")), Some(timestamp("2024-02-29 23:45:00")));
+        assert_eq!(timestamp("2024-12-31 23:59:59.5").checked_add(&interval("0.5 seconds")), Some(timestamp("2025-01-01 00:00:00")));
+        assert_eq!(timestamp("2024-01-31 12:00:00").checked_add(&interval("1 month 36 hours")), Some(timestamp("2024-03-02 00:00:00")));
+        assert_eq!(timestamp("2024-03-02 06:00:00").since(&timestamp("2024-02-28 18:00:00")), Interval::new(0, 2, 12 * MICROS_PER_HOUR));
+        assert_eq!(timestamp("9999-12-31 23:00:00").checked_add(&interval("1 hour")), None);
+    }
+
+    #[test]
+    fn invalid_dates_and_times_are_rejected() {
+        for text in ["2023-02-29", "2024-13-01", "2024-00-10", "2024-04-31", "2024-1-01", "24-01-01", "2024-01-01-01", ""] {
+            assert!(Date::parse(text).is_err(), "{}", text);
+        }
+        for text in ["24:00:00", "23:60", "12:00:60", "1:00", "12:00:-1", "12"] {
+            assert!(Time::parse(text).is_err(), "{}", text);
+        }
+        assert!(Timestamp::parse("2023-02-29 10:00").is_err());
+        assert!(Timestamp::parse("2024-02-29 24:00").is_err());
+
+        assert_eq!(Time::parse("23:59:59.999999").unwrap().to_string(), "23:59:59.999999");
+        assert_eq!(timestamp("2024-02-29T13:45").to_string(), "2024-02-29 13:45:00");
+    }
+
+    #[test]
+    fn intervals_are_read_in_a_unit() {
+        assert_eq!(Interval::parse_in("1", "MONTH").unwrap(), Interval::new(1, 0, 0));
+        assert_eq!(Interval::parse_in("2", "year").unwrap(), Interval::new(24, 0, 0));
+        assert_eq!(Interval::parse_in(" -3 ", "DAY").unwrap(), Interval::new(0, -3, 0));
+        assert_eq!(Interval::parse_in("1.5", "SECOND").unwrap(), Interval::new(0, 0, 1_500_000));
+        assert!(Interval::parse_in("1 day", "MONTH").is_err());
+        assert!(Interval::parse_in("1.5", "DAY").is_err());
+        assert!(Interval::parse_in("1", "fortnight").is_err());
+    }
+
+    #[test]
+    fn intervals_are_written_as_they_are_read() {
+        for text in ["1 year 2 months 3 days 4 hours 5 minutes 6 seconds", "-1 day -0.5 seconds", "0 seconds"] {
+            assert_eq!(interval(text).to_string(), text);
+        }
+        assert_eq!(interval("P1Y2M3DT4H5M6.5S"), interval("1 year 2 months 3 days 4 hours 5 minutes 6.5 seconds"));
+        assert_eq!(interval("2 weeks"), Interval::new(0, 14, 0));
+    }
+}
@@ -0,0 +1,106 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use learn_to_write_a_database::backend::Backend;
+use learn_to_write_a_database::backend::file::FileBackend;
+use learn_to_write_a_database::statements::compiler::StatementCompiler;
+use learn_to_write_a_database::statements::scanner::{Token, TokenIterator};
+use learn_to_write_a_database::statements::Statement;
+
+const SETUP: &str = r#"
+CREATE TABLE users (id INT, name TEXT);
+INSERT INTO users VALUES (1, 'Phil');
+"#;
+
+const WORKLOAD: &[&str] = &[
+    "INSERT INTO users VALUES (2, 'Kate');",
+    "CREATE TABLE logins (user INT);",
+    "INSERT INTO logins VALUES (2);",
+    "UPDATE users SET name = 'Bob' WHERE id = 1;",
+    "DELETE FROM users WHERE id = 2;",
+];
+
+fn execute(backend: &mut dyn Backend, sql: &str) {
+    let tokens = TokenIterator::new_iterator(sql.chars())
+        .filter(|x| !matches!(x, Ok(token) if *token.token() == Token::Space));
+
+    for statement in StatementCompiler::new(tokens) {
+        match statement.unwrap() {
+            Statement::Create(statement) => { backend.create_table(&statement).unwrap(); }
+            Statement::Insert(statement) => { backend.insert(&statement).unwrap(); }
+            Statement::Update(statement) => { backend.update(&statement).unwrap(); }
+            Statement::Delete(statement) => { backend.delete(&statement).unwrap(); }
+            statement => panic!("Unexpected statement in the workload: {:?}", statement),
+        }
+    }
+}
+
+/// Renders the contents of every table the workload touches, including the error for tables which
+/// do not exist yet.
+fn state(backend: &mut dyn Backend) -> String {
+    ["users", "logins"].iter()
+        .map(|table| {
+            let sql = format!("SELECT * FROM {}", table);
+            let tokens = TokenIterator::new_iterator(sql.chars())
+                .filter(|x| !matches!(x, Ok(token) if *token.token() == Token::Space));
+            match StatementCompiler::new(tokens).next().unwrap().unwrap() {
+                Statement::Select(statement) => format!("{:?}", backend.select(&statement)),
+                statement => panic!("Expected a select but got {:?}", statement),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn wal_path(path: &Path) -> PathBuf {
+    PathBuf::from(format!("{}-wal", path.display()))
+}
+
+fn scratch_directory() -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("crash-recovery-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(&directory).unwrap();
+    directory
+}
+
+#[test]
+fn recovers_the_last_committed_statement_at_every_log_offset() {
+    let directory = scratch_directory();
+    let path = directory.join("database");
+
+    // Start from a database which has been cleanly closed, so the log replays on top of real pages
+    {
+        let mut backend = FileBackend::open(&path).unwrap();
+        execute(&mut backend, SETUP);
+    }
+
+    let mut backend = FileBackend::open(&path).unwrap();
+    let mut log_lengths = vec![fs::metadata(wal_path(&path)).unwrap().len()];
+    let mut states = vec![state(&mut backend)];
+
+    for sql in WORKLOAD {
+        execute(&mut backend, sql);
+        log_lengths.push(fs::metadata(wal_path(&path)).unwrap().len());
+        states.push(state(&mut backend));
+    }
+
+    let database = fs::read(&path).unwrap();
+    let log = fs::read(wal_path(&path)).unwrap();
+    assert_eq!(*log_lengths.last().unwrap(), log.len() as u64, "the workload should not have checkpointed");
+
+    // Simulate the crash by never dropping the backend, which would otherwise checkpoint the log
+    std::mem::forget(backend);
+
+    let crashed = directory.join("crashed");
+    for offset in 0..=log.len() {
+        fs::write(&crashed, &database).unwrap();
+        fs::write(wal_path(&crashed), &log[..offset]).unwrap();
+
+        let expected = log_lengths.iter().rposition(|length| *length <= offset as u64).unwrap();
+
+        let mut recovered = FileBackend::open(&crashed).unwrap();
+        assert_eq!(states[expected], state(&mut recovered), "recovering a log cut at byte {}", offset);
+    }
+
+    fs::remove_dir_all(&directory).unwrap();
+}